@@ -1,14 +1,28 @@
 from __future__ import annotations
 
 import random
+from typing import Optional
 
+from poker_client.cards import RANK_NAMES
 
-def choose_smart_action(state, no_fold: bool = False) -> dict:
+
+def _facing_bet_weights(strength: float) -> list[float]:
+    """call/raise/fold weights when facing a bet with a known hand strength."""
+    raise_w = max(0.0, strength - 0.5) * 120
+    fold_w = max(0.0, 0.45 - strength) * 150
+    return [40, raise_w, fold_w]
+
+
+def choose_smart_action(state, no_fold: bool = False, strength: Optional[float] = None) -> dict:
     """Pick a legal, funds-aware action consistent with the bet base.
 
     Check is only available when the player has already matched the current bet
     (``current_bet == bet_base``). Raises are capped so they never exceed the
     player's funds, and the player folds when they cannot afford the call.
+
+    ``strength`` is the hand's equity from the Rust evaluator (see
+    ``poker_client.strength``). When given, strong hands raise more often and
+    weak hands facing a bet fold more often.
     """
     to_call = max(0, state.bet_base - state.current_bet)
     can_check = state.current_bet == state.bet_base
@@ -17,18 +31,22 @@ def choose_smart_action(state, no_fold: bool = False) -> dict:
     if can_check:
         if max_raise <= 0:
             return {"type": "check"}
-        choice = random.choices(["check", "raise"], weights=[65, 35])[0]
+        weights = [65, 35] if strength is None else [100 - 80 * strength, 80 * strength]
+        choice = random.choices(["check", "raise"], weights=weights)[0]
     elif max_raise < 0:
         return {"type": "fold"} if not no_fold else {"type": "call"}
     elif max_raise == 0:
         if no_fold:
             return {"type": "call"}
-        choice = random.choices(["call", "fold"], weights=[90, 10])[0]
+        weights = [90, 10] if strength is None else [20 + 80 * strength, 80 - 80 * strength]
+        choice = random.choices(["call", "fold"], weights=weights)[0]
     else:
         if no_fold:
-            choice = random.choices(["call", "raise"], weights=[65, 35])[0]
+            weights = [65, 35] if strength is None else [100 - 70 * strength, 70 * strength]
+            choice = random.choices(["call", "raise"], weights=weights)[0]
         else:
-            choice = random.choices(["call", "raise", "fold"], weights=[60, 30, 10])[0]
+            weights = [60, 30, 10] if strength is None else _facing_bet_weights(strength)
+            choice = random.choices(["call", "raise", "fold"], weights=weights)[0]
 
     if choice == "raise":
         amount = min(random.randint(10, 200), max(1, max_raise))
@@ -36,6 +54,18 @@ def choose_smart_action(state, no_fold: bool = False) -> dict:
     return {"type": choice}
 
 
+def choose_discards(hole: list[str]) -> list[str]:
+    """Cards to throw on a draw: keep pairs or better, else the two highest.
+
+    Plays for the high hand only, so it draws poorly in lowball games.
+    """
+    ranks = [card[0] for card in hole]
+    kept = [card for card in hole if ranks.count(card[0]) > 1]
+    if not kept:
+        kept = sorted(hole, key=lambda card: RANK_NAMES.index(card[0]), reverse=True)[:2]
+    return [card for card in hole if card not in kept]
+
+
 def random_action(no_fold: bool = False) -> dict:
     """Random (fuzz) action; deliberately ignores turn/bet coherence."""
     if no_fold:
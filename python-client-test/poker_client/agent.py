@@ -11,8 +11,9 @@ from websockets.exceptions import ConnectionClosedError, ConnectionClosedOK
 if TYPE_CHECKING:
     from textual.app import App
 
-from poker_client.actions import choose_smart_action
-from poker_client.cards import fmt_cards
+from poker_client import strength
+from poker_client.actions import choose_discards, choose_smart_action
+from poker_client.cards import fmt_cards, fmt_notations, to_notation
 from poker_client.messages import (
     CommunityCardsUpdated,
     GameEvent,
@@ -22,21 +23,39 @@ from poker_client.messages import (
     SessionStopped,
 )
 from poker_client.protocol import (
+    ACTION_ADD_ON,
+    ACTION_REBUY,
     MSG_BETTING_PLAYERS,
+    MSG_ALL_IN_EQUITY,
+    MSG_ANTE,
     MSG_BLIND,
+    MSG_BRING_IN,
     MSG_CARD_DEAL,
+    MSG_DEAL_OFFER,
+    MSG_DEAL_VOTE,
     MSG_PING,
     MSG_PLAYER_ACTION,
     MSG_PLAYER_TURN_TIMEOUT,
     MSG_PONG_ACK,
+    MSG_PURCHASE,
     MSG_RESULT,
+    MSG_RUNOUT,
     MSG_SESSION,
     MSG_STEP,
+    MSG_TABLE_ASSIGNED,
     MSG_TERMINATE_SESSION,
+    MSG_TOURNAMENT_STATE,
     MSG_TURN,
+    MSG_UPCOMING_GAME,
+    MSG_WALLET,
     MSG_WARNING,
     STEPS_RESET_BET,
+    STEP_DRAW,
+    encode_action,
+    encode_deal_vote,
+    encode_discard,
     encode_pong,
+    encode_run_it,
     encode_update,
     parse_player_action,
 )
@@ -90,6 +109,9 @@ async def run_agent(
                     join = encode_update(True)
                     dump("TX", join)
                     await ws.send(json.dumps(join))
+                    run_it = encode_run_it(2)
+                    dump("TX", run_it)
+                    await ws.send(json.dumps(run_it))
 
                 elif msg_type == MSG_PING:
                     pong = encode_pong(now_ms(), data.get("server_ts", 0))
@@ -113,6 +135,7 @@ async def run_agent(
 
                 elif msg_type == MSG_BETTING_PLAYERS:
                     players = data.get("players", [])
+                    state.active_players = set(players)
                     is_active = bool(state.my_id) and state.my_id in players
                     status = "Active" if is_active else "Folded"
                     state.status = status
@@ -123,13 +146,33 @@ async def run_agent(
                     bb_pid = data.get("big_blind_player", "")
                     sa = data.get("small_blind_amount", 0)
                     ba = data.get("big_blind_amount", 0)
+                    ante = data.get("ante_amount", 0)
+                    # Big blind and button antes are paid by one player and
+                    # do not count towards the bet
+                    ante_type = data.get("ante_type", "every_player")
+                    every_ante = ante if ante_type == "every_player" else 0
+                    straddle_pid = data.get("straddle_player")
+                    straddle = data.get("straddle_amount", 0)
 
                     state.community_cards = []
                     state.hole_cards_text = ""
+                    state.hole_cards = []
+                    state.board_cards = []
                     upd("cards", "")
                     post(CommunityCardsUpdated(""))
 
-                    state.bet_base = ba
+                    state.bet_base = every_ante + max(ba, straddle)
+                    if every_ante and state.my_id in state.active_players:
+                        state.apply_blind(every_ante)
+                        upd("bet", state.current_bet)
+                        post(PlayerFundsChanged(player_index, state.funds))
+                    if ante and state.my_id == data.get("ante_player"):
+                        state.apply_dead_ante(ante)
+                        post(PlayerFundsChanged(player_index, state.funds))
+                    if state.my_id == straddle_pid:
+                        state.apply_blind(straddle)
+                        upd("bet", state.current_bet)
+                        post(PlayerFundsChanged(player_index, state.funds))
                     if state.my_id == sb_pid:
                         state.apply_blind(sa)
                         upd("bet", state.current_bet)
@@ -143,24 +186,132 @@ async def run_agent(
                         f"[cyan]Blinds:[/] SB [bold]{short(sb_pid)}[/]({sa}) "
                         f"BB [bold]{short(bb_pid)}[/]({ba})"
                     )
+                    if straddle_pid:
+                        log(f"[cyan]Straddle:[/] [bold]{short(straddle_pid)}[/]({straddle})")
+
+                elif msg_type == MSG_ANTE:
+                    ante = data.get("amount", 0)
+                    state.community_cards = []
+                    state.hole_cards_text = ""
+                    state.hole_cards = []
+                    state.board_cards = []
+                    upd("cards", "")
+                    post(CommunityCardsUpdated(""))
+
+                    state.bet_base = ante
+                    if state.my_id in state.active_players:
+                        state.apply_blind(ante)
+                        upd("bet", state.current_bet)
+                        post(PlayerFundsChanged(player_index, state.funds))
+                    log(f"[cyan]Ante:[/] {ante}")
+
+                elif msg_type == MSG_BRING_IN:
+                    pid = data.get("player_id", "")
+                    amount = data.get("amount", 0)
+                    if pid == state.my_id:
+                        state.apply_blind(amount)
+                        upd("bet", state.current_bet)
+                        post(PlayerFundsChanged(player_index, state.funds))
+                    state.bet_base += amount
+                    log(f"[cyan]Bring-in:[/] [bold]{short(pid)}[/]({amount})")
+
+                elif msg_type == MSG_UPCOMING_GAME:
+                    game = data.get("game", "?")
+                    limit = data.get("limit", "?").replace("_", " ")
+                    log(f"[cyan]Next game:[/] [bold]{game}[/] ({limit})")
+
+                elif msg_type == MSG_TOURNAMENT_STATE:
+                    if not state.in_tournament:
+                        state.in_tournament = True
+                        state.funds = data.get("starting_stack", state.funds)
+                        post(PlayerFundsChanged(player_index, state.funds))
+                    blinds = data.get("blinds", {})
+                    log(
+                        f"[cyan]Level {data.get('level', 1)}:[/] "
+                        f"{blinds.get('small_blind', 0)}/{blinds.get('big_blind', 0)} "
+                        f"ante {blinds.get('ante', 0)}, "
+                        f"{data.get('players_remaining', 0)} left, "
+                        f"prize pool {data.get('prize_pool', 0)}"
+                    )
+                    for standing in data.get("standings", []):
+                        if standing.get("player_id") == state.my_id:
+                            log(
+                                f"[bold]P{player_index + 1} finished "
+                                f"#{standing.get('position')}[/] (+{standing.get('prize', 0)})"
+                            )
+                    # Rebuy when busted and take the add-on, the server
+                    # turns down repeats
+                    windows = data.get("buy_windows", {})
+                    purchase = None
+                    if windows.get("rebuy") and state.funds == 0:
+                        purchase = encode_action(ACTION_REBUY)
+                    elif windows.get("add_on") and not state.took_add_on:
+                        state.took_add_on = True
+                        purchase = encode_action(ACTION_ADD_ON)
+                    if purchase:
+                        dump("TX", purchase)
+                        await ws.send(json.dumps(purchase))
+
+                elif msg_type == MSG_PURCHASE:
+                    kind = data.get("purchase", "?").replace("_", " ")
+                    if data.get("player_id") == state.my_id:
+                        state.funds += data.get("chips", 0)
+                        post(PlayerFundsChanged(player_index, state.funds))
+                    log(
+                        f"[cyan]{short(data.get('player_id', ''))} bought {kind}:[/] "
+                        f"{data.get('chips', 0)} chips"
+                    )
+
+                elif msg_type == MSG_WALLET:
+                    log(f"[cyan]Wallet balance:[/] {data.get('balance', 0)}")
+
+                elif msg_type == MSG_TABLE_ASSIGNED:
+                    log(f"[cyan]Seated at table[/] {short(data.get('table_id', ''))}")
+
+                elif msg_type == MSG_DEAL_OFFER:
+                    shares = ", ".join(
+                        f"{short(share.get('player_id', ''))} {share.get('prize', 0)}"
+                        for share in data.get("shares", [])
+                    )
+                    log(f"[cyan]{data.get('method', '?')} deal offered:[/] {shares}")
+                    vote = encode_deal_vote(True)
+                    dump("TX", vote)
+                    await ws.send(json.dumps(vote))
+
+                elif msg_type == MSG_DEAL_VOTE:
+                    answer = "accepts" if data.get("accept") else "declines"
+                    log(f"[cyan]Deal:[/] [bold]{short(data.get('player_id', ''))}[/] {answer}")
 
                 elif msg_type == MSG_CARD_DEAL:
                     cards = data.get("cards", [])
                     owner = data.get("owner", "player")
+                    pid = data.get("player_id")
                     cards_text = fmt_cards(cards)
-                    if owner == "player":
-                        state.hole_cards_text = cards_text
-                        upd("cards", cards_text)
+                    notations = [to_notation(c) for c in cards]
+                    if owner == "player" and pid not in (None, state.my_id):
+                        # Another player's face-up stud cards
+                        log(f"[dim]{short(pid)} shows: {cards_text}[/dim]")
+                    elif owner == "player":
+                        # Stud deals hole cards street by street, the hand
+                        # start (blind/ante) clears them
+                        state.hole_cards.extend(notations)
+                        state.hole_cards_text = " ".join(
+                            filter(None, [state.hole_cards_text, cards_text])
+                        )
+                        upd("cards", state.hole_cards_text)
                         log(f"[blue]P{player_index + 1} hole:[/] [bold]{cards_text}[/]")
                     else:
                         if len(cards) > 1:
                             state.community_cards = cards_text.split()
+                            state.board_cards = notations
                         else:
                             state.community_cards.extend(cards_text.split())
+                            state.board_cards.extend(notations)
                         post(CommunityCardsUpdated(" ".join(state.community_cards)))
 
                 elif msg_type == MSG_PLAYER_TURN_TIMEOUT:
                     pid = data.get("player", "")
+                    state.active_players.discard(pid)
                     if pid == state.my_id:
                         state.status = "Folded"
                         upd("status", "Folded")
@@ -180,7 +331,32 @@ async def run_agent(
                         async def think_and_act(ws=ws, state=state):
                             try:
                                 await asyncio.sleep(random.uniform(0.3, 1.5))
-                                action_data = choose_smart_action(state, no_fold=no_fold)
+                                if state.step == STEP_DRAW:
+                                    # Replacements arrive as a card deal
+                                    discards = choose_discards(state.hole_cards)
+                                    state.hole_cards = [
+                                        card for card in state.hole_cards if card not in discards
+                                    ]
+                                    state.hole_cards_text = fmt_notations(state.hole_cards)
+                                    upd("cards", state.hole_cards_text)
+                                    log(
+                                        f"[yellow]P{player_index + 1}[/] "
+                                        f"→ [bold]DISCARD {len(discards)}[/]"
+                                    )
+                                    action_data = encode_discard(discards)
+                                    dump("TX", action_data)
+                                    await ws.send(json.dumps(action_data))
+                                    return
+                                opponents = max(1, len(state.active_players) - 1)
+                                hand_strength = await asyncio.to_thread(
+                                    strength.hand_strength,
+                                    list(state.hole_cards),
+                                    list(state.board_cards),
+                                    opponents,
+                                )
+                                action_data = choose_smart_action(
+                                    state, no_fold=no_fold, strength=hand_strength
+                                )
                                 action_type = action_data["type"].upper()
                                 amount = action_data.get("amount", 0)
 
@@ -209,6 +385,8 @@ async def run_agent(
                         data.get("action"), data.get("bet_base", 0)
                     )
                     state.bet_base = new_bet_base
+                    if action_name == "FOLD":
+                        state.active_players.discard(acted_pid)
                     if acted_pid == state.my_id:
                         if action_name == "FOLD":
                             state.status = "Folded"
@@ -216,9 +394,42 @@ async def run_agent(
                     else:
                         log(f"[magenta]{short(acted_pid)}[/] → [bold]{action_name}[/]")
 
+                elif msg_type == MSG_ALL_IN_EQUITY:
+                    for player in data.get("players", []):
+                        pid = player.get("player_id", "")
+                        equity = player.get("equity", 0.0) * 100
+                        cards_text = fmt_cards(player.get("cards", []))
+                        log(f"[yellow]All in:[/] {short(pid)} {cards_text} {equity:.1f}%")
+                    runouts = data.get("runouts", 1)
+                    if runouts > 1:
+                        log(f"[yellow]Running it {runouts} times[/]")
+
+                elif msg_type == MSG_RUNOUT:
+                    cards = data.get("cards", [])
+                    board = state.board_cards + [to_notation(c) for c in cards]
+                    state.runout_boards.append(board)
+                    log(f"[yellow]Board {data.get('runout', 0) + 1}:[/] {fmt_notations(board)}")
+
                 elif msg_type == MSG_RESULT:
                     winners = data.get("winners", [])
                     prizes = data.get("prizes", [])
+                    shown = {
+                        hand.get("player_id", ""): [to_notation(c) for c in hand.get("cards", [])]
+                        for hand in data.get("player_hands", [])
+                        if hand.get("player_id", "") in state.active_players
+                    }
+                    # Side pots and low halves can pay other players, so only
+                    # the high half of the main pot on the first board is checked
+                    pots = data.get("pots", [])
+                    main_winners = pots[0].get("high_winners", []) if pots else winners
+                    board = state.runout_boards[0] if state.runout_boards else state.board_cards
+                    expected = strength.expected_winners(shown, board)
+                    if expected is not None and set(expected) != set(main_winners):
+                        log(
+                            f"[red bold]Showdown mismatch:[/] server paid "
+                            f"{[short(w) for w in main_winners]}, evaluator expects "
+                            f"{[short(w) for w in expected]}"
+                        )
                     state.reset_hand()
                     upd("cards", "")
                     post(CommunityCardsUpdated(""))
@@ -233,6 +444,14 @@ async def run_agent(
                     for hand in data.get("player_hands", []):
                         pid = hand.get("player_id", "")
                         log(f"[dim]{short(pid)} showed: {fmt_cards(hand.get('cards', []))}[/dim]")
+                    for bounty in data.get("bounties", []):
+                        log(
+                            f"[magenta]Bounty:[/] [bold]{short(bounty.get('eliminator', ''))}[/] "
+                            f"knocked out {short(bounty.get('eliminated', ''))} "
+                            f"(+{bounty.get('cash', 0)}, head +{bounty.get('added_to_head', 0)})"
+                        )
+                    if data.get("rake"):
+                        log(f"[dim]Rake: {data['rake']}[/dim]")
                     upd("status", "Waiting")
                     upd("action", "None")
                     upd("bet", 0)
@@ -13,3 +13,15 @@ def fmt_card(suit: str, rank: int) -> str:
 
 def fmt_cards(cards: list[dict]) -> str:
     return " ".join(fmt_card(c.get("suit", "?"), c.get("rank", 0)) for c in cards)
+
+
+def to_notation(card: dict) -> str:
+    """Short notation understood by the Rust evaluator, e.g. ``As``."""
+    rank = card.get("rank", 0)
+    r = RANK_NAMES[rank] if 0 <= rank < 13 else "?"
+    return f"{r}{card.get('suit', '?')}"
+
+
+def fmt_notations(notations: list[str]) -> str:
+    """Like ``fmt_cards`` for cards kept in short notation."""
+    return " ".join(fmt_card(n[1], RANK_NAMES.index(n[0])) for n in notations)
@@ -5,6 +5,14 @@ ACTION_FOLD = "fold"
 ACTION_CHECK = "check"
 ACTION_CALL = "call"
 ACTION_RAISE = "raise"
+ACTION_DISCARD = "discard"
+ACTION_STRADDLE = "straddle"
+ACTION_RUN_IT = "run_it"
+ACTION_PROPOSE_DEAL = "propose_deal"
+ACTION_DEAL_VOTE = "deal_vote"
+ACTION_REBUY = "rebuy"
+ACTION_ADD_ON = "add_on"
+ACTION_REENTER = "reenter"
 ACTION_PONG = "pong"
 ACTION_UPDATE = "update"
 
@@ -16,8 +24,19 @@ MSG_PLAYER_TURN_TIMEOUT = "player_turn_timeout"
 MSG_TURN = "turn"
 MSG_GAME_STATE = "game_state"  # defined by server but never broadcast
 MSG_BLIND = "blind"
+MSG_ANTE = "ante"
+MSG_BRING_IN = "bring_in"
+MSG_UPCOMING_GAME = "upcoming_game"
+MSG_TOURNAMENT_STATE = "tournament_state"
+MSG_TABLE_ASSIGNED = "table_assigned"
+MSG_DEAL_OFFER = "deal_offer"
+MSG_DEAL_VOTE = "deal_vote"
+MSG_PURCHASE = "purchase"
+MSG_WALLET = "wallet"
 MSG_CARD_DEAL = "card_deal"
 MSG_PLAYER_ACTION = "player_action"
+MSG_ALL_IN_EQUITY = "all_in_equity"
+MSG_RUNOUT = "runout"
 MSG_RESULT = "result"
 MSG_WARNING = "warning"
 MSG_PING = "ping"
@@ -27,7 +46,10 @@ MSG_TERMINATE_SESSION = "terminate_session"
 # Steps that reset the per-hand bet state (serde rename_all = "snake_case").
 # bet_base / player bet are cumulative across streets, so only a new hand
 # (blind) resets them.
-STEPS_RESET_BET = ("blind",)
+STEPS_RESET_BET = ("blind", "ante")
+
+# Step where players replace cards instead of betting.
+STEP_DRAW = "draw"
 
 
 def encode_action(action_type: str, amount: int | None = None) -> dict:
@@ -36,6 +58,31 @@ def encode_action(action_type: str, amount: int | None = None) -> dict:
     return {"type": action_type}
 
 
+def encode_discard(cards: list[str]) -> dict:
+    """Cards to replace in short notation, empty to stand pat."""
+    return {"type": ACTION_DISCARD, "cards": cards}
+
+
+def encode_straddle(enabled: bool) -> dict:
+    """Opt in or out of posting the straddle when in the straddle seat."""
+    return {"type": ACTION_STRADDLE, "enabled": enabled}
+
+
+def encode_run_it(times: int) -> dict:
+    """Most times to run the board when all in, the table uses the lowest."""
+    return {"type": ACTION_RUN_IT, "times": times}
+
+
+def encode_propose_deal(method: str) -> dict:
+    """Offer the players left a split of the prizes, "icm" or "chip_chop"."""
+    return {"type": ACTION_PROPOSE_DEAL, "method": method}
+
+
+def encode_deal_vote(accept: bool) -> dict:
+    """Answer the deal on offer, every player left must accept it."""
+    return {"type": ACTION_DEAL_VOTE, "accept": accept}
+
+
 def encode_pong(client_ts: int, server_ts: int) -> dict:
     return {"type": ACTION_PONG, "client_ts": client_ts, "server_ts": server_ts}
 
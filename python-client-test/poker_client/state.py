@@ -17,11 +17,25 @@ class PlayerState:
     latency_ms: int = 0
     hole_cards_text: str = ""
     community_cards: list = field(default_factory=list)
+    # Short notation ("As") for the Rust evaluator
+    hole_cards: list = field(default_factory=list)
+    board_cards: list = field(default_factory=list)
+    # Full boards of a hand run more than once
+    runout_boards: list = field(default_factory=list)
+    # Players still contesting the pot this hand
+    active_players: set = field(default_factory=set)
+    # Sit-and-go stacks start from the tournament's starting stack
+    in_tournament: bool = False
+    took_add_on: bool = False
 
     # ── Fund bookkeeping ──────────────────────────────────────────────────────
     def apply_blind(self, amount: int) -> None:
-        """Deduct a posted blind (small or big) from funds."""
-        self.current_bet = amount
+        """Deduct a posted ante, blind or bring-in from funds."""
+        self.current_bet += amount
+        self.funds -= amount
+
+    def apply_dead_ante(self, amount: int) -> None:
+        """Deduct a big blind or button ante, which is not part of the bet."""
         self.funds -= amount
 
     def apply_action(self, action_type: str, amount: int = 0) -> None:
@@ -46,3 +60,7 @@ class PlayerState:
         self.current_bet = 0
         self.hole_cards_text = ""
         self.community_cards = []
+        self.hole_cards = []
+        self.board_cards = []
+        self.runout_boards = []
+        self.active_players = set()
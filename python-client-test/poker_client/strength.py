@@ -0,0 +1,56 @@
+"""Hand strength and showdown checks backed by the Rust evaluator.
+
+The ``mini_poker`` extension lives in ``webserver/python`` and is built with
+``maturin develop -m ../webserver/python/pyproject.toml``. When it is not
+installed every helper returns ``None`` and the agents fall back to their
+funds-only decisions.
+"""
+from __future__ import annotations
+
+from typing import Optional
+
+try:
+    import mini_poker
+except ImportError:  # pragma: no cover - optional native module
+    mini_poker = None
+
+# Monte Carlo samples per decision; small enough to keep turns snappy.
+STRENGTH_ITERATIONS = 2_000
+
+
+def available() -> bool:
+    return mini_poker is not None
+
+
+def game_for(hole: list[str]) -> str:
+    return "omaha" if len(hole) == 4 else "holdem"
+
+
+def hand_strength(hole: list[str], board: list[str], opponents: int = 1) -> Optional[float]:
+    """Equity of ``hole`` against ``opponents`` random hands, 0.0 to 1.0."""
+    if mini_poker is None or not hole or opponents < 1:
+        return None
+    try:
+        result = mini_poker.equity(
+            ["".join(hole)] + ["??"] * opponents,
+            board="".join(board),
+            game=game_for(hole),
+            iterations=STRENGTH_ITERATIONS,
+            threads=1,
+        )
+    except ValueError:
+        return None
+    return result.players[0].equity
+
+
+def expected_winners(hands: dict[str, list[str]], board: list[str]) -> Optional[list[str]]:
+    """Player ids the evaluator says should win the showdown."""
+    if mini_poker is None or len(hands) < 2 or len(board) < 5:
+        return None
+    ids = list(hands)
+    holes = ["".join(hands[pid]) for pid in ids]
+    try:
+        winners = mini_poker.compare_hands(holes, board="".join(board), game=game_for(hands[ids[0]]))
+    except ValueError:
+        return None
+    return [ids[idx] for idx in winners]
@@ -53,6 +53,49 @@ impl Display for Rank {
     }
 }
 
+impl From<Rank> for char {
+    fn from(value: Rank) -> Self {
+        match value {
+            Rank::Two => '2',
+            Rank::Three => '3',
+            Rank::Four => '4',
+            Rank::Five => '5',
+            Rank::Six => '6',
+            Rank::Seven => '7',
+            Rank::Eight => '8',
+            Rank::Nine => '9',
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Ace => 'A',
+        }
+    }
+}
+
+impl TryFrom<char> for Rank {
+    type Error = &'static str;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value.to_ascii_uppercase() {
+            '2' => Ok(Rank::Two),
+            '3' => Ok(Rank::Three),
+            '4' => Ok(Rank::Four),
+            '5' => Ok(Rank::Five),
+            '6' => Ok(Rank::Six),
+            '7' => Ok(Rank::Seven),
+            '8' => Ok(Rank::Eight),
+            '9' => Ok(Rank::Nine),
+            'T' => Ok(Rank::Ten),
+            'J' => Ok(Rank::Jack),
+            'Q' => Ok(Rank::Queen),
+            'K' => Ok(Rank::King),
+            'A' => Ok(Rank::Ace),
+            _ => Err("invalid card rank"),
+        }
+    }
+}
+
 pub const fn build_deck() -> [Card; 52] {
     let mut deck = [Card { rank: Rank::Two, suit: Suit::Clubs, owner: Owner::Community }; 52];
     let mut i = 0;
@@ -73,6 +116,14 @@ pub const fn build_deck() -> [Card; 52] {
 
 pub const DECK: [Card; 52] = build_deck();
 
+/// Deck without the ranks below `lowest_rank`, e.g. the 36 card Short Deck
+pub fn build_deck_from(lowest_rank: Rank) -> Vec<Card> {
+    DECK.iter()
+        .filter(|card| card.rank >= lowest_rank)
+        .copied()
+        .collect()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Suit {
     Clubs = 0,
@@ -92,6 +143,20 @@ impl From<Suit> for char {
     }
 }
 
+impl TryFrom<char> for Suit {
+    type Error = &'static str;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value.to_ascii_lowercase() {
+            'c' => Ok(Suit::Clubs),
+            'd' => Ok(Suit::Diamonds),
+            'h' => Ok(Suit::Hearts),
+            's' => Ok(Suit::Spades),
+            _ => Err("invalid card suit"),
+        }
+    }
+}
+
 pub const CARD_SUITS: [Suit; 4] = [
     Suit::Clubs,
     Suit::Diamonds,
@@ -134,6 +199,40 @@ pub struct Card {
     pub owner: Owner
 }
 
+impl Card {
+    /// Position of the card in `DECK`, ignoring the owner
+    pub fn index(&self) -> usize {
+        self.suit as usize * CARD_RANKS.len() + self.rank as usize
+    }
+
+    /// Short rank + suit notation, e.g. `As` or `Td`
+    pub fn notation(&self) -> String {
+        format!("{}{}", char::from(self.rank), char::from(self.suit))
+    }
+}
+
+/// Parses cards written in short notation ("AsKs", "As Ks" or "As,Ks")
+pub fn parse_cards(text: &str, owner: Owner) -> Result<Vec<Card>, &'static str> {
+    let chars: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err("card notation needs a rank and a suit per card");
+    }
+
+    chars
+        .chunks(2)
+        .map(|pair| {
+            Ok(Card {
+                rank: Rank::try_from(pair[0])?,
+                suit: Suit::try_from(pair[1])?,
+                owner,
+            })
+        })
+        .collect()
+}
+
 impl Ord for Card {
     fn cmp(&self, other:&Self) -> Ordering {
         self.rank.cmp(&other.rank)
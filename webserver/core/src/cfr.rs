@@ -0,0 +1,737 @@
+pub mod holdem;
+pub mod kuhn;
+pub mod leduc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Move of a limit betting game. Checks are calls of nothing and bets are
+/// raises of nothing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Fold,
+    Call,
+    Raise,
+}
+
+impl From<Action> for char {
+    fn from(value: Action) -> Self {
+        match value {
+            Action::Fold => 'f',
+            Action::Call => 'c',
+            Action::Raise => 'r',
+        }
+    }
+}
+
+/// Whose turn a state of the game is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    /// Cards are dealt
+    Chance,
+    Player(usize),
+    /// Hand over
+    Terminal,
+}
+
+/// Two-player zero-sum card game CFR can solve
+pub trait CfrGame {
+    type State: Clone;
+
+    /// Name policies trained on the game are saved under
+    fn name(&self) -> String;
+
+    fn root(&self) -> Self::State;
+
+    fn turn(&self, state: &Self::State) -> Turn;
+
+    /// Chips the first player wins at a terminal state, the second player
+    /// wins the opposite
+    fn payoff(&self, state: &Self::State) -> f64;
+
+    /// Every deal of a chance state with its probability, none when there
+    /// are too many to list
+    fn chance_outcomes(&self, state: &Self::State) -> Option<Vec<(f64, Self::State)>>;
+
+    /// One deal of a chance state, drawn at random
+    fn sample_chance(&self, state: &Self::State, rng: &mut StdRng) -> Self::State;
+
+    /// Legal actions of the player to act, always listed in the same order
+    fn actions(&self, state: &Self::State) -> Vec<Action>;
+
+    fn apply(&self, state: &Self::State, action: Action) -> Self::State;
+
+    /// What the player to act knows: their cards and the betting so far
+    fn info_set(&self, state: &Self::State) -> String {
+        let mut key = String::new();
+        self.write_info_set(state, &mut key);
+        key
+    }
+
+    /// Appends the information set to `key`, letting solvers look up every
+    /// state they visit without allocating a key for it
+    fn write_info_set(&self, state: &Self::State, key: &mut String);
+}
+
+/// Rules of a two-player limit betting game
+#[derive(Debug)]
+struct LimitRules {
+    /// Chips each player puts in before the cards are dealt
+    blinds: [u32; 2],
+    /// Bet size of each betting round
+    bet_sizes: &'static [u32],
+    /// Bets and raises a round allows, a posted big blind counting as one
+    max_bets: u32,
+    /// Player first to act on each round
+    first_to_act: &'static [usize],
+}
+
+/// Action letters of a hand, rounds separated by slashes. Kept inline so
+/// the states a solver walks copy it without allocating
+#[derive(Clone, Copy)]
+struct History {
+    letters: [u8; Self::CAPACITY],
+    len: usize,
+}
+
+impl History {
+    /// Four rounds of a bet and three raises, each with a check and a call
+    const CAPACITY: usize = 32;
+
+    fn new() -> Self {
+        Self {
+            letters: [0; Self::CAPACITY],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, letter: char) {
+        self.letters[self.len] = letter as u8;
+        self.len += 1;
+    }
+
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.letters[..self.len]).expect("history holds ascii letters")
+    }
+}
+
+impl std::ops::Deref for History {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for History {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::fmt::Debug for History {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+/// Betting of a limit game hand. Rounds are written out as action letters
+/// in the history, separated by slashes
+#[derive(Clone, Debug)]
+struct LimitBetting {
+    rules: &'static LimitRules,
+    round: usize,
+    /// Chips each player put in this hand
+    bets: [u32; 2],
+    /// Bets and raises made this round
+    n_bets: u32,
+    /// Actions taken this round
+    n_actions: usize,
+    to_act: usize,
+    history: History,
+    folded: Option<usize>,
+    /// Whether the round closed and the next one waits for its cards
+    awaiting_cards: bool,
+    showdown: bool,
+}
+
+impl LimitBetting {
+    fn new(rules: &'static LimitRules) -> Self {
+        Self {
+            rules,
+            round: 0,
+            bets: rules.blinds,
+            n_bets: (rules.blinds[0] != rules.blinds[1]) as u32,
+            n_actions: 0,
+            to_act: rules.first_to_act[0],
+            history: History::new(),
+            folded: None,
+            awaiting_cards: false,
+            showdown: false,
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.folded.is_some() || self.showdown
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = match self.bets[0] == self.bets[1] {
+            true => vec![Action::Call],
+            false => vec![Action::Fold, Action::Call],
+        };
+        if self.n_bets < self.rules.max_bets {
+            actions.push(Action::Raise);
+        }
+        actions
+    }
+
+    fn apply(&mut self, action: Action) {
+        let player = self.to_act;
+        let bet_base = self.bets[0].max(self.bets[1]);
+        self.history.push(action.into());
+        self.n_actions += 1;
+        match action {
+            Action::Fold => self.folded = Some(player),
+            Action::Call => {
+                self.bets[player] = bet_base;
+                // Everyone acted and the bets are matched
+                if self.n_actions >= 2 {
+                    self.close_round();
+                    return;
+                }
+            }
+            Action::Raise => {
+                self.bets[player] = bet_base + self.rules.bet_sizes[self.round];
+                self.n_bets += 1;
+            }
+        }
+        self.to_act = 1 - player;
+    }
+
+    fn close_round(&mut self) {
+        if self.round + 1 == self.rules.bet_sizes.len() {
+            self.showdown = true;
+            return;
+        }
+        self.round += 1;
+        self.n_bets = 0;
+        self.n_actions = 0;
+        self.to_act = self.rules.first_to_act[self.round];
+        self.history.push('/');
+        self.awaiting_cards = true;
+    }
+
+    /// First player's result once the hand is over: the winner takes what
+    /// the loser put in. `showdown` is greater when the first player holds
+    /// the better hand
+    fn payoff(&self, showdown: Ordering) -> f64 {
+        match (self.folded, showdown) {
+            (Some(0), _) | (None, Ordering::Less) => -(self.bets[0] as f64),
+            (Some(_), _) | (None, Ordering::Greater) => self.bets[1] as f64,
+            (None, Ordering::Equal) => 0.0,
+        }
+    }
+}
+
+/// Variant of the regret minimization the solver runs
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CfrAlgorithm {
+    /// Vanilla regret matching with a uniform average
+    Cfr,
+    /// Regrets floored at zero and later iterations weighing more in the
+    /// average, which converges much faster
+    CfrPlus,
+}
+
+impl FromStr for CfrAlgorithm {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "cfr" => Ok(Self::Cfr),
+            "cfr+" | "cfr-plus" => Ok(Self::CfrPlus),
+            _ => Err("unknown algorithm, expected cfr or cfr+"),
+        }
+    }
+}
+
+impl Display for CfrAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cfr => write!(f, "cfr"),
+            Self::CfrPlus => write!(f, "cfr+"),
+        }
+    }
+}
+
+struct InfoSetNode {
+    regrets: Vec<f64>,
+    /// Regrets of the traversal running, added in once it is over so the
+    /// strategy stays the same through it
+    new_regrets: Vec<f64>,
+    strategy_sum: Vec<f64>,
+}
+
+impl InfoSetNode {
+    /// Regret matching: actions played in proportion to their positive
+    /// regret, uniformly when none has any
+    fn strategy(&self) -> Vec<f64> {
+        let positive: Vec<f64> = self.regrets.iter().map(|regret| regret.max(0.0)).collect();
+        normalized(&positive)
+    }
+}
+
+fn normalized(weights: &[f64]) -> Vec<f64> {
+    let total: f64 = weights.iter().sum();
+    match total > 0.0 {
+        true => weights.iter().map(|weight| weight / total).collect(),
+        false => vec![1.0 / weights.len() as f64; weights.len()],
+    }
+}
+
+/// Counterfactual regret minimization. Each traversal walks every deal and
+/// every action of both players when the game can list its deals. Games too
+/// large for that deal one hand and sample the opponent's actions instead
+/// (external sampling)
+pub struct CfrSolver {
+    algorithm: CfrAlgorithm,
+    iterations: u64,
+    /// Whether traversals sample deals and opponent actions
+    sampling: bool,
+    nodes: Vec<InfoSetNode>,
+    /// Index in `nodes` of each information set
+    index: HashMap<String, usize>,
+    /// Information sets with regrets waiting to be added in
+    updated: Vec<usize>,
+    /// Key of the information set being looked up
+    key: String,
+}
+
+impl CfrSolver {
+    pub fn new(algorithm: CfrAlgorithm) -> Self {
+        Self {
+            algorithm,
+            iterations: 0,
+            sampling: false,
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            updated: Vec::new(),
+            key: String::new(),
+        }
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    pub fn n_info_sets(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Runs `iterations` more iterations, each updating one player and then
+    /// the other. Deals are drawn from `seed`
+    pub fn train<G: CfrGame>(&mut self, game: &G, iterations: u64, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(self.iterations));
+        self.sampling = game.chance_outcomes(&game.root()).is_none();
+        for _ in 0..iterations {
+            self.iterations += 1;
+            for player in 0..2 {
+                self.walk(game, &game.root(), player, [1.0, 1.0], 1.0, &mut rng);
+                self.add_regrets();
+            }
+        }
+    }
+
+    /// Node of the information set of `state`, created the first time it is
+    /// reached. Its key is only copied then, every visit looks it up
+    fn node_idx<G: CfrGame>(&mut self, game: &G, state: &G::State, n_actions: usize) -> usize {
+        self.key.clear();
+        game.write_info_set(state, &mut self.key);
+        if let Some(&idx) = self.index.get(self.key.as_str()) {
+            return idx;
+        }
+        self.nodes.push(InfoSetNode {
+            regrets: vec![0.0; n_actions],
+            new_regrets: vec![0.0; n_actions],
+            strategy_sum: vec![0.0; n_actions],
+        });
+        self.index.insert(self.key.clone(), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// CFR+ floors the regrets once the whole traversal is added in
+    fn add_regrets(&mut self) {
+        for idx in self.updated.drain(..) {
+            let node = &mut self.nodes[idx];
+            for (regret, new_regret) in node.regrets.iter_mut().zip(node.new_regrets.iter_mut()) {
+                *regret += std::mem::take(new_regret);
+                if self.algorithm == CfrAlgorithm::CfrPlus {
+                    *regret = regret.max(0.0);
+                }
+            }
+        }
+    }
+
+    /// Expected result of `traverser` from `state`, updating the regrets of
+    /// their information sets on the way. `reach` is how likely each player
+    /// plays to the state and `chance` how likely the deals walked are
+    fn walk<G: CfrGame>(
+        &mut self,
+        game: &G,
+        state: &G::State,
+        traverser: usize,
+        reach: [f64; 2],
+        chance: f64,
+        rng: &mut StdRng,
+    ) -> f64 {
+        let player = match game.turn(state) {
+            Turn::Terminal => {
+                let payoff = game.payoff(state);
+                return if traverser == 0 { payoff } else { -payoff };
+            }
+            Turn::Chance => {
+                let Some(outcomes) = game.chance_outcomes(state) else {
+                    let state = game.sample_chance(state, rng);
+                    return self.walk(game, &state, traverser, reach, chance, rng);
+                };
+                return outcomes
+                    .iter()
+                    .map(|(probability, state)| {
+                        let chance = chance * probability;
+                        probability * self.walk(game, state, traverser, reach, chance, rng)
+                    })
+                    .sum();
+            }
+            Turn::Player(player) => player,
+        };
+
+        let actions = game.actions(state);
+        let node_idx = self.node_idx(game, state, actions.len());
+        let strategy = self.nodes[node_idx].strategy();
+        let weight = match self.algorithm {
+            CfrAlgorithm::Cfr => 1.0,
+            CfrAlgorithm::CfrPlus => self.iterations as f64,
+        };
+
+        // Sampled opponent actions already come in proportion to how likely
+        // they are, so their strategy is averaged as it is played
+        if self.sampling && player != traverser {
+            let node = &mut self.nodes[node_idx];
+            for (sum, probability) in node.strategy_sum.iter_mut().zip(strategy.iter()) {
+                *sum += weight * probability;
+            }
+            let action = actions[sample_index(&strategy, rng)];
+            return self.walk(
+                game,
+                &game.apply(state, action),
+                traverser,
+                reach,
+                chance,
+                rng,
+            );
+        }
+
+        let mut utilities = vec![0.0; actions.len()];
+        let mut value = 0.0;
+        for (idx, &action) in actions.iter().enumerate() {
+            let mut child_reach = reach;
+            child_reach[player] *= strategy[idx];
+            let child = game.apply(state, action);
+            utilities[idx] = self.walk(game, &child, traverser, child_reach, chance, rng);
+            value += strategy[idx] * utilities[idx];
+        }
+        if player != traverser {
+            return value;
+        }
+
+        let node = &mut self.nodes[node_idx];
+        if node.new_regrets.iter().all(|&regret| regret == 0.0) {
+            self.updated.push(node_idx);
+        }
+        for idx in 0..actions.len() {
+            node.new_regrets[idx] += chance * reach[1 - player] * (utilities[idx] - value);
+            if !self.sampling {
+                node.strategy_sum[idx] += weight * reach[player] * strategy[idx];
+            }
+        }
+        value
+    }
+
+    /// Average strategy of every information set reached so far, which is
+    /// what converges to an equilibrium. Sets never played to are left out
+    /// and play uniformly
+    pub fn policy<G: CfrGame>(&self, game: &G) -> Policy {
+        Policy {
+            game: game.name(),
+            algorithm: self.algorithm,
+            iterations: self.iterations,
+            strategy: self
+                .index
+                .iter()
+                .map(|(key, &idx)| (key, &self.nodes[idx]))
+                .filter(|(_, node)| node.strategy_sum.iter().any(|&sum| sum > 0.0))
+                .map(|(key, node)| (key.clone(), normalized(&node.strategy_sum)))
+                .collect(),
+        }
+    }
+}
+
+/// Strategy a solver trained, as saved to disk and played by bots
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Policy {
+    pub game: String,
+    pub algorithm: CfrAlgorithm,
+    pub iterations: u64,
+    /// Probability of each legal action by information set, in the order
+    /// the game lists the actions
+    pub strategy: BTreeMap<String, Vec<f64>>,
+}
+
+impl Policy {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("policies serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, &'static str> {
+        serde_json::from_str(text).map_err(|_| "invalid policy file")
+    }
+
+    /// Action probabilities at an information set, uniform when the policy
+    /// never reached it
+    pub fn probabilities(&self, info_set: &str, n_actions: usize) -> Vec<f64> {
+        match self.strategy.get(info_set) {
+            Some(probabilities) if probabilities.len() == n_actions => probabilities.clone(),
+            _ => vec![1.0 / n_actions as f64; n_actions],
+        }
+    }
+
+    /// Action drawn at an information set
+    pub fn sample(&self, info_set: &str, actions: &[Action], rng: &mut StdRng) -> Action {
+        let probabilities = self.probabilities(info_set, actions.len());
+        actions[sample_index(&probabilities, rng)]
+    }
+}
+
+fn sample_index(probabilities: &[f64], rng: &mut StdRng) -> usize {
+    let mut target: f64 = rng.random();
+    for (idx, probability) in probabilities.iter().enumerate() {
+        if target < *probability {
+            return idx;
+        }
+        target -= probability;
+    }
+    probabilities.len() - 1
+}
+
+/// First player's expected result when both players follow `policy`
+pub fn expected_value<G: CfrGame>(game: &G, policy: &Policy) -> Result<f64, &'static str> {
+    policy_value(game, policy, &game.root())
+}
+
+fn policy_value<G: CfrGame>(
+    game: &G,
+    policy: &Policy,
+    state: &G::State,
+) -> Result<f64, &'static str> {
+    match game.turn(state) {
+        Turn::Terminal => Ok(game.payoff(state)),
+        Turn::Chance => chance_outcomes(game, state)?
+            .iter()
+            .map(|(probability, state)| Ok(probability * policy_value(game, policy, state)?))
+            .sum(),
+        Turn::Player(_) => {
+            let actions = game.actions(state);
+            let probabilities = policy.probabilities(&game.info_set(state), actions.len());
+            actions
+                .iter()
+                .zip(probabilities)
+                .map(|(&action, probability)| {
+                    Ok(probability * policy_value(game, policy, &game.apply(state, action))?)
+                })
+                .sum()
+        }
+    }
+}
+
+fn chance_outcomes<G: CfrGame>(
+    game: &G,
+    state: &G::State,
+) -> Result<Vec<(f64, G::State)>, &'static str> {
+    game.chance_outcomes(state)
+        .ok_or("game has too many deals to walk them all")
+}
+
+/// Chips per hand a best response wins against `policy` on average over
+/// both seats, zero at an equilibrium. Only games whose deals can all be
+/// walked have one
+pub fn exploitability<G: CfrGame>(game: &G, policy: &Policy) -> Result<f64, &'static str> {
+    let first = BestResponse::new(game, policy, 0)?.value(&game.root());
+    let second = BestResponse::new(game, policy, 1)?.value(&game.root());
+    Ok((first + second) / 2.0)
+}
+
+/// Strategy of `player` that wins the most against `policy`. Every
+/// information set picks the action worth most over the states it holds,
+/// each weighed by how likely the deals and the opponent lead there
+struct BestResponse<'a, G: CfrGame> {
+    game: &'a G,
+    policy: &'a Policy,
+    player: usize,
+    states: HashMap<String, Vec<(f64, G::State)>>,
+    best_actions: HashMap<String, Action>,
+}
+
+impl<'a, G: CfrGame> BestResponse<'a, G> {
+    fn new(game: &'a G, policy: &'a Policy, player: usize) -> Result<Self, &'static str> {
+        let mut response = Self {
+            game,
+            policy,
+            player,
+            states: HashMap::new(),
+            best_actions: HashMap::new(),
+        };
+        response.collect(&game.root(), 1.0)?;
+        Ok(response)
+    }
+
+    fn collect(&mut self, state: &G::State, weight: f64) -> Result<(), &'static str> {
+        match self.game.turn(state) {
+            Turn::Terminal => {}
+            Turn::Chance => {
+                for (probability, state) in chance_outcomes(self.game, state)? {
+                    self.collect(&state, weight * probability)?;
+                }
+            }
+            Turn::Player(player) => {
+                let actions = self.game.actions(state);
+                let key = self.game.info_set(state);
+                let probabilities = match player == self.player {
+                    true => vec![1.0; actions.len()],
+                    false => self.policy.probabilities(&key, actions.len()),
+                };
+                if player == self.player {
+                    self.states
+                        .entry(key)
+                        .or_default()
+                        .push((weight, state.clone()));
+                }
+                for (&action, probability) in actions.iter().zip(probabilities) {
+                    self.collect(&self.game.apply(state, action), weight * probability)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Responder's result from `state`, chance and opponent reach left out
+    fn value(&mut self, state: &G::State) -> f64 {
+        match self.game.turn(state) {
+            Turn::Terminal => {
+                let payoff = self.game.payoff(state);
+                if self.player == 0 {
+                    payoff
+                } else {
+                    -payoff
+                }
+            }
+            Turn::Chance => chance_outcomes(self.game, state)
+                .expect("deals were walked when collecting")
+                .iter()
+                .map(|(probability, state)| probability * self.value(state))
+                .sum(),
+            Turn::Player(player) if player == self.player => {
+                let action = self.best_action(&self.game.info_set(state), state);
+                self.value(&self.game.apply(state, action))
+            }
+            Turn::Player(_) => {
+                let actions = self.game.actions(state);
+                let probabilities = self
+                    .policy
+                    .probabilities(&self.game.info_set(state), actions.len());
+                actions
+                    .iter()
+                    .zip(probabilities)
+                    .map(|(&action, probability)| {
+                        probability * self.value(&self.game.apply(state, action))
+                    })
+                    .sum()
+            }
+        }
+    }
+
+    fn best_action(&mut self, key: &str, state: &G::State) -> Action {
+        if let Some(&action) = self.best_actions.get(key) {
+            return action;
+        }
+        let states = self.states.get(key).cloned().unwrap_or_default();
+        let action = self
+            .game
+            .actions(state)
+            .into_iter()
+            .map(|action| {
+                let value: f64 = states
+                    .iter()
+                    .map(|(weight, state)| weight * self.value(&self.game.apply(state, action)))
+                    .sum();
+                (value, action)
+            })
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, action)| action)
+            .expect("players always have an action");
+        self.best_actions.insert(key.to_string(), action);
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::kuhn::Kuhn;
+    use super::leduc::Leduc;
+    use super::*;
+
+    #[test]
+    fn kuhn_converges_to_its_game_value() {
+        let mut solver = CfrSolver::new(CfrAlgorithm::CfrPlus);
+        solver.train(&Kuhn, 1_000, 0);
+        assert_eq!(solver.n_info_sets(), 12);
+
+        let policy = solver.policy(&Kuhn);
+        let value = expected_value(&Kuhn, &policy).unwrap();
+        assert!((value + 1.0 / 18.0).abs() < 1e-3, "game value {value}");
+        let exploitability = exploitability(&Kuhn, &policy).unwrap();
+        assert!(exploitability < 1e-3, "exploitability {exploitability}");
+    }
+
+    #[test]
+    fn leduc_reaches_every_info_set() {
+        let mut solver = CfrSolver::new(CfrAlgorithm::Cfr);
+        solver.train(&Leduc, 1, 0);
+        assert_eq!(solver.n_info_sets(), 288);
+        assert_eq!(solver.policy(&Leduc).strategy.len(), 288);
+    }
+
+    #[test]
+    fn history_separates_rounds() {
+        let mut betting = LimitBetting::new(&LimitRules {
+            blinds: [1, 1],
+            bet_sizes: &[2, 4],
+            max_bets: 2,
+            first_to_act: &[0, 0],
+        });
+        for action in [Action::Raise, Action::Call, Action::Call, Action::Raise] {
+            betting.apply(action);
+        }
+        assert_eq!(&*betting.history, "rc/cr");
+        assert_eq!(
+            betting.actions(),
+            vec![Action::Fold, Action::Call, Action::Raise]
+        );
+    }
+}
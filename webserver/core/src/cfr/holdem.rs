@@ -0,0 +1,196 @@
+use super::{Action, CfrGame, LimitBetting, LimitRules, Policy, Turn};
+use crate::card::{Card, Owner, DECK};
+use crate::equity::{hand_strength, score_hands};
+use crate::game::GameType;
+use crate::variant::TexasHoldem;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+/// Blinds of one and two chips, small bets of two chips before the turn and
+/// big bets of four after it, four bets a round. The small blind acts first
+/// before the flop and last after it
+const RULES: LimitRules = LimitRules {
+    blinds: [1, 2],
+    bet_sizes: &[2, 2, 4, 4],
+    max_bets: 4,
+    first_to_act: &[0, 1, 1, 1],
+};
+
+/// Board cards seen on each betting round
+const BOARD_SIZES: [usize; 4] = [0, 3, 4, 5];
+/// Hands dealt to estimate the strength a bucket is picked by
+const BUCKET_SAMPLES: u64 = 64;
+/// Buckets when none are given
+pub const DEFAULT_BUCKETS: u32 = 8;
+
+/// Heads-up limit hold'em, abstracted: players never see their cards, only
+/// the strength bucket the hand falls in on the round being played
+pub struct LimitHoldem {
+    pub buckets: u32,
+}
+
+/// Cards of a hand and the buckets they fall in on each round
+#[derive(Clone, Copy, Debug)]
+struct HoldemDeal {
+    hole_cards: [[Card; 2]; 2],
+    board: [Card; 5],
+    buckets: [[u32; 4]; 2],
+}
+
+#[derive(Clone, Debug)]
+pub struct HoldemState {
+    deal: Option<HoldemDeal>,
+    betting: LimitBetting,
+}
+
+impl LimitHoldem {
+    /// Game a policy was trained on, from the name it was saved under
+    pub fn from_name(name: &str) -> Option<Self> {
+        let buckets = name.strip_prefix("limit-holdem-")?.strip_suffix('b')?;
+        Some(Self {
+            buckets: buckets.parse().ok().filter(|&buckets| buckets > 0)?,
+        })
+    }
+
+    /// Strength bucket of hole cards on a board, 0 the weakest: the share of
+    /// the pot they win against a random hand, cut into equal slices
+    pub fn bucket(&self, hole_cards: &[Card], board: &[Card], seed: u64) -> u32 {
+        let strength = hand_strength(
+            &TexasHoldem,
+            hole_cards,
+            board,
+            &[],
+            1,
+            BUCKET_SAMPLES,
+            seed,
+        )
+        .unwrap_or(0.5);
+        ((strength * self.buckets as f64) as u32).min(self.buckets - 1)
+    }
+}
+
+/// Appends the information set of a hand's bucket and the betting so far
+fn write_info_set(bucket: u32, history: &str, key: &mut String) {
+    _ = write!(key, "{bucket}:{history}");
+}
+
+impl CfrGame for LimitHoldem {
+    type State = HoldemState;
+
+    fn name(&self) -> String {
+        format!("limit-holdem-{}b", self.buckets)
+    }
+
+    fn root(&self) -> HoldemState {
+        HoldemState {
+            deal: None,
+            betting: LimitBetting::new(&RULES),
+        }
+    }
+
+    fn turn(&self, state: &HoldemState) -> Turn {
+        match state.deal {
+            None => Turn::Chance,
+            Some(_) if state.betting.is_over() => Turn::Terminal,
+            Some(_) => Turn::Player(state.betting.to_act),
+        }
+    }
+
+    fn payoff(&self, state: &HoldemState) -> f64 {
+        let deal = state.deal.expect("cards are dealt before the hand ends");
+        let holes: Vec<Vec<Card>> = deal.hole_cards.iter().map(|hole| hole.to_vec()).collect();
+        let scores = score_hands(GameType::TexasHoldemPoker, &holes, &deal.board);
+        state.betting.payoff(scores[0].cmp(&scores[1]))
+    }
+
+    fn chance_outcomes(&self, _state: &HoldemState) -> Option<Vec<(f64, HoldemState)>> {
+        None
+    }
+
+    /// The whole hand is dealt at once, each round only showing its part
+    fn sample_chance(&self, state: &HoldemState, rng: &mut StdRng) -> HoldemState {
+        let mut deck = DECK;
+        let (cards, _) = deck.partial_shuffle(rng, 9);
+        let with_owner = |card: Card, owner| Card { owner, ..card };
+        let hole_cards = [
+            [cards[0], cards[1]].map(|card| with_owner(card, Owner::Player)),
+            [cards[2], cards[3]].map(|card| with_owner(card, Owner::Player)),
+        ];
+        let board: [Card; 5] =
+            std::array::from_fn(|idx| with_owner(cards[4 + idx], Owner::Community));
+        let buckets = hole_cards.map(|hole| {
+            BOARD_SIZES.map(|board_size| self.bucket(&hole, &board[..board_size], rng.random()))
+        });
+        HoldemState {
+            deal: Some(HoldemDeal {
+                hole_cards,
+                board,
+                buckets,
+            }),
+            betting: state.betting.clone(),
+        }
+    }
+
+    fn actions(&self, state: &HoldemState) -> Vec<Action> {
+        state.betting.actions()
+    }
+
+    fn apply(&self, state: &HoldemState, action: Action) -> HoldemState {
+        let mut state = state.clone();
+        state.betting.apply(action);
+        state
+    }
+
+    fn write_info_set(&self, state: &HoldemState, key: &mut String) {
+        let deal = state.deal.expect("players act once the cards are dealt");
+        let bucket = deal.buckets[state.betting.to_act][state.betting.round];
+        write_info_set(bucket, &state.betting.history, key);
+    }
+}
+
+static BOT_POLICY: OnceLock<(LimitHoldem, Policy)> = OnceLock::new();
+
+/// Policy bots playing heads-up limit hold'em follow, set once per process
+pub fn set_bot_policy(policy: Policy) -> Result<(), &'static str> {
+    let game = LimitHoldem::from_name(&policy.game).ok_or("policy is not for limit hold'em")?;
+    BOT_POLICY
+        .set((game, policy))
+        .map_err(|_| "bot policy is already set")
+}
+
+/// Action the bot policy picks with `hole_cards` on `board` after the
+/// betting in `history`, one list of actions per round. None without a
+/// policy or when the betting is not one the game allows
+pub fn bot_policy_action(
+    hole_cards: &[Card],
+    board: &[Card],
+    history: &[Vec<Action>],
+    betting_round: usize,
+    rng: &mut StdRng,
+) -> Option<Action> {
+    let (game, policy) = BOT_POLICY.get()?;
+    if hole_cards.len() != 2 || board.len() != *BOARD_SIZES.get(betting_round)? {
+        return None;
+    }
+    let mut betting = LimitBetting::new(&RULES);
+    for (round, actions) in history.iter().enumerate().take(betting_round + 1) {
+        for &action in actions {
+            if betting.round != round || betting.is_over() || !betting.actions().contains(&action) {
+                return None;
+            }
+            betting.apply(action);
+        }
+    }
+    if betting.round != betting_round || betting.is_over() {
+        return None;
+    }
+
+    let bucket = game.bucket(hole_cards, board, rng.random());
+    let actions = betting.actions();
+    let mut info_set = String::new();
+    write_info_set(bucket, &betting.history, &mut info_set);
+    Some(policy.sample(&info_set, &actions, rng))
+}
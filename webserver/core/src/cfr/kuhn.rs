@@ -0,0 +1,119 @@
+use super::{Action, CfrGame, LimitBetting, LimitRules, Turn};
+use crate::card::{Card, Owner, Rank, Suit};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+/// Each player antes one chip and a single bet of one chip is allowed
+const RULES: LimitRules = LimitRules {
+    blinds: [1, 1],
+    bet_sizes: &[1],
+    max_bets: 1,
+    first_to_act: &[0],
+};
+
+const DECK: [Card; 3] = [
+    Card {
+        rank: Rank::Jack,
+        suit: Suit::Spades,
+        owner: Owner::Player,
+    },
+    Card {
+        rank: Rank::Queen,
+        suit: Suit::Spades,
+        owner: Owner::Player,
+    },
+    Card {
+        rank: Rank::King,
+        suit: Suit::Spades,
+        owner: Owner::Player,
+    },
+];
+
+/// Kuhn poker: one card each from a jack, queen and king, one betting round
+/// and the higher card wins. The first player loses 1/18 of a chip a hand
+/// at equilibrium
+pub struct Kuhn;
+
+#[derive(Clone, Debug)]
+pub struct KuhnState {
+    cards: Option<[Card; 2]>,
+    betting: LimitBetting,
+}
+
+impl CfrGame for Kuhn {
+    type State = KuhnState;
+
+    fn name(&self) -> String {
+        "kuhn".to_string()
+    }
+
+    fn root(&self) -> KuhnState {
+        KuhnState {
+            cards: None,
+            betting: LimitBetting::new(&RULES),
+        }
+    }
+
+    fn turn(&self, state: &KuhnState) -> Turn {
+        match state.cards {
+            None => Turn::Chance,
+            Some(_) if state.betting.is_over() => Turn::Terminal,
+            Some(_) => Turn::Player(state.betting.to_act),
+        }
+    }
+
+    fn payoff(&self, state: &KuhnState) -> f64 {
+        let cards = state.cards.expect("cards are dealt before the hand ends");
+        state.betting.payoff(cards[0].rank.cmp(&cards[1].rank))
+    }
+
+    fn chance_outcomes(&self, state: &KuhnState) -> Option<Vec<(f64, KuhnState)>> {
+        let deals: Vec<[Card; 2]> = DECK
+            .iter()
+            .flat_map(|&first| {
+                DECK.iter()
+                    .filter(move |&&second| second != first)
+                    .map(move |&second| [first, second])
+            })
+            .collect();
+        let probability = 1.0 / deals.len() as f64;
+        Some(
+            deals
+                .into_iter()
+                .map(|cards| {
+                    let state = KuhnState {
+                        cards: Some(cards),
+                        ..state.clone()
+                    };
+                    (probability, state)
+                })
+                .collect(),
+        )
+    }
+
+    fn sample_chance(&self, state: &KuhnState, rng: &mut StdRng) -> KuhnState {
+        let mut deck = DECK;
+        deck.shuffle(rng);
+        KuhnState {
+            cards: Some([deck[0], deck[1]]),
+            ..state.clone()
+        }
+    }
+
+    fn actions(&self, state: &KuhnState) -> Vec<Action> {
+        state.betting.actions()
+    }
+
+    fn apply(&self, state: &KuhnState, action: Action) -> KuhnState {
+        let mut state = state.clone();
+        state.betting.apply(action);
+        state
+    }
+
+    fn write_info_set(&self, state: &KuhnState, key: &mut String) {
+        let cards = state.cards.expect("players act once the cards are dealt");
+        key.push(char::from(cards[state.betting.to_act].rank));
+        key.push(':');
+        key.push_str(&state.betting.history);
+    }
+}
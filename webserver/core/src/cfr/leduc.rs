@@ -0,0 +1,190 @@
+use super::{Action, CfrGame, LimitBetting, LimitRules, Turn};
+use crate::card::{Card, Owner, Rank, Suit};
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+
+/// Each player antes one chip, bets are two chips before the board card and
+/// four after, with at most a bet and a raise a round
+const RULES: LimitRules = LimitRules {
+    blinds: [1, 1],
+    bet_sizes: &[2, 4],
+    max_bets: 2,
+    first_to_act: &[0, 0],
+};
+
+const DECK: [Card; 6] = [
+    Card {
+        rank: Rank::Jack,
+        suit: Suit::Spades,
+        owner: Owner::Player,
+    },
+    Card {
+        rank: Rank::Jack,
+        suit: Suit::Hearts,
+        owner: Owner::Player,
+    },
+    Card {
+        rank: Rank::Queen,
+        suit: Suit::Spades,
+        owner: Owner::Player,
+    },
+    Card {
+        rank: Rank::Queen,
+        suit: Suit::Hearts,
+        owner: Owner::Player,
+    },
+    Card {
+        rank: Rank::King,
+        suit: Suit::Spades,
+        owner: Owner::Player,
+    },
+    Card {
+        rank: Rank::King,
+        suit: Suit::Hearts,
+        owner: Owner::Player,
+    },
+];
+
+/// Leduc hold'em: one card each from two suits of jacks, queens and kings,
+/// a betting round, a board card and another betting round. Pairing the
+/// board wins, the higher card otherwise
+pub struct Leduc;
+
+#[derive(Clone, Debug)]
+pub struct LeducState {
+    hole_cards: Option<[Card; 2]>,
+    board: Option<Card>,
+    betting: LimitBetting,
+}
+
+impl LeducState {
+    fn remaining(&self) -> Vec<Card> {
+        let dealt: &[Card] = match &self.hole_cards {
+            Some(hole_cards) => hole_cards,
+            None => &[],
+        };
+        DECK.iter()
+            .filter(|card| !dealt.contains(card))
+            .copied()
+            .collect()
+    }
+}
+
+/// Pairs beat every other hand, ranks decide otherwise
+fn strength(hole_card: Card, board: Card) -> (bool, Rank) {
+    (hole_card.rank == board.rank, hole_card.rank)
+}
+
+impl CfrGame for Leduc {
+    type State = LeducState;
+
+    fn name(&self) -> String {
+        "leduc".to_string()
+    }
+
+    fn root(&self) -> LeducState {
+        LeducState {
+            hole_cards: None,
+            board: None,
+            betting: LimitBetting::new(&RULES),
+        }
+    }
+
+    fn turn(&self, state: &LeducState) -> Turn {
+        if state.betting.is_over() {
+            Turn::Terminal
+        } else if state.hole_cards.is_none() || state.betting.awaiting_cards {
+            Turn::Chance
+        } else {
+            Turn::Player(state.betting.to_act)
+        }
+    }
+
+    fn payoff(&self, state: &LeducState) -> f64 {
+        let showdown = match (state.hole_cards, state.board) {
+            (Some([first, second]), Some(board)) => {
+                strength(first, board).cmp(&strength(second, board))
+            }
+            // Hands that end before the board end on a fold
+            _ => std::cmp::Ordering::Equal,
+        };
+        state.betting.payoff(showdown)
+    }
+
+    fn chance_outcomes(&self, state: &LeducState) -> Option<Vec<(f64, LeducState)>> {
+        let remaining = state.remaining();
+        if state.hole_cards.is_some() {
+            let probability = 1.0 / remaining.len() as f64;
+            return Some(
+                remaining
+                    .into_iter()
+                    .map(|board| (probability, deal_board(state, board)))
+                    .collect(),
+            );
+        }
+
+        let deals: Vec<[Card; 2]> = DECK
+            .iter()
+            .flat_map(|&first| {
+                DECK.iter()
+                    .filter(move |&&second| second != first)
+                    .map(move |&second| [first, second])
+            })
+            .collect();
+        let probability = 1.0 / deals.len() as f64;
+        Some(
+            deals
+                .into_iter()
+                .map(|hole_cards| {
+                    let state = LeducState {
+                        hole_cards: Some(hole_cards),
+                        ..state.clone()
+                    };
+                    (probability, state)
+                })
+                .collect(),
+        )
+    }
+
+    fn sample_chance(&self, state: &LeducState, rng: &mut StdRng) -> LeducState {
+        if state.hole_cards.is_some() {
+            let board = *state.remaining().choose(rng).expect("cards are left");
+            return deal_board(state, board);
+        }
+        let mut deck = DECK;
+        deck.shuffle(rng);
+        LeducState {
+            hole_cards: Some([deck[0], deck[1]]),
+            ..state.clone()
+        }
+    }
+
+    fn actions(&self, state: &LeducState) -> Vec<Action> {
+        state.betting.actions()
+    }
+
+    fn apply(&self, state: &LeducState, action: Action) -> LeducState {
+        let mut state = state.clone();
+        state.betting.apply(action);
+        state
+    }
+
+    fn write_info_set(&self, state: &LeducState, key: &mut String) {
+        let hole_cards = state
+            .hole_cards
+            .expect("players act once the cards are dealt");
+        key.push(char::from(hole_cards[state.betting.to_act].rank));
+        if let Some(board) = state.board {
+            key.push(char::from(board.rank));
+        }
+        key.push(':');
+        key.push_str(&state.betting.history);
+    }
+}
+
+fn deal_board(state: &LeducState, board: Card) -> LeducState {
+    let mut state = state.clone();
+    state.board = Some(board);
+    state.betting.awaiting_cards = false;
+    state
+}
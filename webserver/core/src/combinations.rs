@@ -1,11 +1,17 @@
-use crate::core::card::Card;
+use crate::card::Card;
 
-fn factorial(n: usize) -> usize {
-    if n == 0 {
-        1
-    } else {
-        n * factorial(n - 1)
+/// Binomial coefficient, computed multiplicatively so it does not overflow for
+/// deck sized inputs
+pub fn n_choose_k(n: usize, k: usize) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    let mut result: u64 = 1;
+    for i in 0..k {
+        result = result * (n - i) as u64 / (i + 1) as u64;
     }
+    result
 }
 
 pub fn combinations(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
@@ -13,7 +19,7 @@ pub fn combinations(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
     if k > n {
         return vec![];
     }
-    let n_comb = factorial(n) / (factorial(n - k) * factorial(k));
+    let n_comb = n_choose_k(n, k) as usize;
     let mut result = Vec::with_capacity(n_comb);
     let mut indices: Vec<usize> = (0..k).collect();
 
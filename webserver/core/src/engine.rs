@@ -0,0 +1,848 @@
+use crate::card::{Card, Owner};
+use crate::game::PokerStep;
+use crate::pot::{award_pot, side_pots, uncalled_bet, Pot, PotAward};
+use crate::rake::Rake;
+use crate::strategy::Decision;
+use crate::variant::{
+    AnteType, BettingStructure, ForcedBets, GameVariant, RoundRaises, ShowdownHand, Straddle,
+    FIXED_LIMIT_MAX_BETS,
+};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// A player's part in a hand
+#[derive(Clone, Debug, Default)]
+pub struct HandSeat {
+    /// Dealt into the hand and not folded
+    pub is_betting: bool,
+    pub dealt_cards: Vec<Card>,
+    /// Whether each dealt card is shown to the other players
+    pub face_up: Vec<bool>,
+    /// Chips put in this hand
+    pub bet: u32,
+    /// Chips left behind
+    pub funds: u32,
+}
+
+impl HandSeat {
+    pub fn up_cards(&self) -> Vec<Card> {
+        self.dealt_cards
+            .iter()
+            .zip(self.face_up.iter())
+            .filter_map(|(&card, &face_up)| face_up.then_some(card))
+            .collect()
+    }
+
+    /// In the hand with chips left to bet
+    pub fn can_act(&self) -> bool {
+        self.is_betting && self.funds > 0
+    }
+
+    /// Puts chips in, all of them when short. Returns the chips put in
+    fn put_in(&mut self, amount: u32) -> u32 {
+        let amount = amount.min(self.funds);
+        self.funds -= amount;
+        self.bet += amount;
+        amount
+    }
+}
+
+/// Whatever holds a seat of the hand, a room's player or a simulated one
+pub trait Seated {
+    fn seat(&self) -> &HandSeat;
+    fn seat_mut(&mut self) -> &mut HandSeat;
+}
+
+impl Seated for HandSeat {
+    fn seat(&self) -> &HandSeat {
+        self
+    }
+
+    fn seat_mut(&mut self) -> &mut HandSeat {
+        self
+    }
+}
+
+/// Who posted the forced bets of a hand
+#[derive(Clone, Copy, Debug)]
+pub struct Blinds {
+    pub small_blind_idx: usize,
+    pub big_blind_idx: usize,
+    pub button_idx: usize,
+    /// Seat that paid the whole table's ante, none when everyone antes
+    pub ante_idx: Option<usize>,
+    pub straddle_idx: Option<usize>,
+    pub straddle_amount: u32,
+}
+
+/// Cards dealt on a stud street
+#[derive(Clone, Debug, Default)]
+pub struct StudStreet {
+    /// Cards of each seat dealt in, with whether they are face up
+    pub dealt: Vec<(usize, Vec<(Card, bool)>)>,
+    /// Single card dealt face up to the board when the stub cannot go around
+    pub board: Vec<Card>,
+    /// Seat that brought it in and the chips it posted
+    pub bring_in: Option<(usize, u32)>,
+}
+
+/// How the pots of a hand were paid
+#[derive(Clone, Debug)]
+pub struct Settlement {
+    /// Main pot first, after the rake
+    pub pots: Vec<Pot>,
+    /// Hand of each seat on every board, none for folded players and for
+    /// the winner of an uncontested pot
+    pub hands: Vec<Vec<Option<ShowdownHand>>>,
+    /// Share of each pot every board won, with the board's index
+    pub awards: Vec<(usize, PotAward)>,
+    /// Chips each seat won
+    pub prizes: Vec<u32>,
+    pub rake: u32,
+    /// Chips each seat put in the pots, dead money included
+    pub contributions: Vec<u32>,
+    /// More than one player was left to show their hand
+    pub showdown: bool,
+}
+
+/// Table side of a hand: the deck, the board and the betting. Every step
+/// takes the seats, so the game rooms and the simulator deal and bet by the
+/// same rules
+#[derive(Clone, Debug, Default)]
+pub struct Hand {
+    pub deck: Vec<Card>,
+    pub dealt_card_offset: usize,
+    /// Cards thrown away on draws, shuffled back in when the stub runs out
+    pub discards: Vec<Card>,
+    pub community_cards: Vec<Card>,
+    pub big_blind_idx: usize,
+    /// Last to act after the first betting round
+    pub button_idx: usize,
+    pub bet_base: u32,
+    pub betting_round: usize,
+    /// Seat that opens each betting round
+    pub first_to_act: usize,
+    /// Raise that completes a stud bring-in to a full bet, until someone makes it
+    pub completion: Option<u32>,
+    /// Raising done in the betting round being played
+    pub raises: RoundRaises,
+    /// Big blind or button ante, which goes to the main pot without
+    /// counting towards the poster's bet
+    pub dead_money: u32,
+    /// Seat that posted the dead money
+    pub dead_money_idx: Option<usize>,
+    /// More than one player was left after the first betting round
+    pub saw_flop: bool,
+    /// Decisions taken in each betting round, blinds and antes left out
+    pub actions: Vec<Vec<Decision>>,
+    /// Seats that acted in the betting round being played
+    acted: Vec<bool>,
+    /// Seat the action is on, none before the round's first decision
+    turn: Option<usize>,
+}
+
+impl Hand {
+    /// Starts a hand on a shuffled deck. The blinds and the button move on
+    /// from where the last hand left them
+    pub fn start(&mut self, deck: Vec<Card>) {
+        *self = Self {
+            deck,
+            big_blind_idx: self.big_blind_idx,
+            button_idx: self.button_idx,
+            ..Self::default()
+        };
+    }
+
+    /// Every chip put in this hand, dead money included
+    pub fn pot<S: Seated>(&self, seats: &[S]) -> u32 {
+        self.dead_money + seats.iter().map(|seat| seat.seat().bet).sum::<u32>()
+    }
+
+    pub fn to_call<S: Seated>(&self, seats: &[S], idx: usize) -> u32 {
+        self.bet_base.saturating_sub(seats[idx].seat().bet)
+    }
+
+    pub fn stub_size(&self) -> usize {
+        self.deck.len() - self.dealt_card_offset
+    }
+
+    /// Deals `n_cards` from the stub, shuffling the discards back in when it
+    /// runs short. May deal fewer cards once both are exhausted
+    pub fn deal_cards<R: Rng + ?Sized>(
+        &mut self,
+        n_cards: usize,
+        owner: Owner,
+        rng: &mut R,
+    ) -> Vec<Card> {
+        if self.stub_size() < n_cards {
+            self.discards.shuffle(rng);
+            self.deck.append(&mut self.discards);
+        }
+        let n_cards = n_cards.min(self.stub_size());
+        let offset = self.dealt_card_offset;
+        self.dealt_card_offset += n_cards;
+        self.deck[offset..offset + n_cards]
+            .iter()
+            .map(|&card| Card { owner, ..card })
+            .collect()
+    }
+
+    /// Every player dealt in posts the ante, which counts as part of their bet
+    pub fn post_antes<S: Seated>(&mut self, seats: &mut [S], ante: u32) {
+        self.bet_base = ante;
+        for seat in seats.iter_mut().map(Seated::seat_mut) {
+            if seat.is_betting {
+                seat.put_in(ante);
+            }
+        }
+    }
+
+    /// First seat from `from` on, wrapping around, with a player dealt in
+    pub fn next_betting_seat<S: Seated>(seats: &[S], from: usize) -> usize {
+        let n_seats = seats.len();
+        (0..n_seats)
+            .map(|offset| (from + offset) % n_seats)
+            .find(|&idx| seats[idx].seat().is_betting)
+            .unwrap_or(from % n_seats)
+    }
+
+    /// Posts the blinds, moved on to the next players dealt in, with the
+    /// table's antes and the straddle of a player in `straddling` who sits in
+    /// the straddle seat. Short stacks post what they have left
+    pub fn post_blinds<S: Seated>(
+        &mut self,
+        seats: &mut [S],
+        betting: &BettingStructure,
+        forced_bets: &ForcedBets,
+        straddling: &[bool],
+    ) -> Blinds {
+        let n_seats = seats.len();
+        let small_blind_idx = Self::next_betting_seat(seats, self.big_blind_idx);
+        let big_blind_idx = Self::next_betting_seat(seats, small_blind_idx + 1);
+        let button_idx = (1..=n_seats)
+            .map(|offset| (small_blind_idx + n_seats - offset) % n_seats)
+            .find(|&idx| seats[idx].seat().is_betting)
+            .unwrap_or(small_blind_idx);
+        self.big_blind_idx = big_blind_idx;
+        self.button_idx = button_idx;
+
+        if forced_bets.ante_type == AnteType::EveryPlayer {
+            self.post_antes(seats, betting.ante);
+        }
+        self.bet_base += betting.big_blind;
+        self.raises = RoundRaises::blinds(betting.big_blind);
+        seats[small_blind_idx]
+            .seat_mut()
+            .put_in(betting.small_blind);
+        seats[big_blind_idx].seat_mut().put_in(betting.big_blind);
+
+        let ante_idx = match forced_bets.ante_type {
+            AnteType::EveryPlayer => None,
+            AnteType::BigBlind => Some(big_blind_idx),
+            AnteType::Button => Some(button_idx),
+        }
+        .filter(|_| betting.ante > 0);
+        if let Some(ante_idx) = ante_idx {
+            let seat = seats[ante_idx].seat_mut();
+            let amount = betting.ante.min(seat.funds);
+            seat.funds -= amount;
+            self.dead_money += amount;
+            self.dead_money_idx = Some(ante_idx);
+        }
+
+        // Straddles are voluntary, the seat only posts one when its player
+        // opted in
+        let straddle_idx = forced_bets
+            .straddle
+            .map(|straddle| match straddle {
+                Straddle::UnderTheGun => (big_blind_idx + 1) % n_seats,
+                Straddle::Mississippi => button_idx,
+            })
+            .filter(|&idx| {
+                idx != small_blind_idx
+                    && idx != big_blind_idx
+                    && seats[idx].seat().is_betting
+                    && straddling.get(idx).copied().unwrap_or(false)
+            });
+        let mut straddle_amount = 0;
+        if let Some(straddle_idx) = straddle_idx {
+            let seat = seats[straddle_idx].seat_mut();
+            straddle_amount = seat.put_in(2 * betting.big_blind);
+            // The straddle raises the big blind
+            if seat.bet > self.bet_base {
+                self.raises.record(seat.bet - self.bet_base);
+                self.bet_base = seat.bet;
+            }
+        }
+
+        let last_blind_idx = match straddle_idx {
+            Some(straddle_idx) if forced_bets.straddle_acts_last => straddle_idx,
+            _ => big_blind_idx,
+        };
+        self.first_to_act = (last_blind_idx + 1) % n_seats;
+        Blinds {
+            small_blind_idx,
+            big_blind_idx,
+            button_idx,
+            ante_idx,
+            straddle_idx,
+            straddle_amount,
+        }
+    }
+
+    /// Deals `n_cards` face down to every player dealt in
+    pub fn deal_hole_cards<S: Seated, R: Rng + ?Sized>(
+        &mut self,
+        seats: &mut [S],
+        n_cards: usize,
+        rng: &mut R,
+    ) {
+        for seat in seats.iter_mut().map(Seated::seat_mut) {
+            if !seat.is_betting {
+                continue;
+            }
+            let cards = self.deal_cards(n_cards, Owner::Player, rng);
+            seat.face_up.extend(cards.iter().map(|_| false));
+            seat.dealt_cards.extend(cards);
+        }
+    }
+
+    /// Deals `n_cards` to the board, returning them
+    pub fn deal_community_cards<R: Rng + ?Sized>(
+        &mut self,
+        n_cards: usize,
+        rng: &mut R,
+    ) -> Vec<Card> {
+        let cards = self.deal_cards(n_cards, Owner::Community, rng);
+        self.community_cards.extend(cards.iter().copied());
+        cards
+    }
+
+    /// Deals a stud street to the players left. When the stub cannot go
+    /// around, a single card is dealt face up to the board and plays in every
+    /// hand. On third street the weakest door card posts the bring-in and the
+    /// action starts on its left, on later streets the strongest cards
+    /// showing act first
+    pub fn deal_stud_street<S: Seated, R: Rng + ?Sized>(
+        &mut self,
+        seats: &mut [S],
+        variant: &dyn GameVariant,
+        step: PokerStep,
+        betting: &BettingStructure,
+        rng: &mut R,
+    ) -> StudStreet {
+        let faces = step.stud_cards();
+        let n_dealt = seats.iter().filter(|seat| seat.seat().is_betting).count();
+        let mut street = StudStreet::default();
+        if n_dealt * faces.len() > self.stub_size() {
+            street.board = self.deal_community_cards(1, rng);
+        } else {
+            for (idx, seat) in seats.iter_mut().map(Seated::seat_mut).enumerate() {
+                if !seat.is_betting {
+                    continue;
+                }
+                let cards: Vec<(Card, bool)> = self
+                    .deal_cards(faces.len(), Owner::Player, rng)
+                    .into_iter()
+                    .zip(faces.iter().copied())
+                    .collect();
+                for &(card, face_up) in cards.iter() {
+                    seat.dealt_cards.push(card);
+                    seat.face_up.push(face_up);
+                }
+                street.dealt.push((idx, cards));
+            }
+        }
+
+        let live = || {
+            seats
+                .iter()
+                .enumerate()
+                .filter(|(_, seat)| seat.seat().is_betting)
+        };
+        if step == PokerStep::ThirdStreet {
+            let bring_in_idx = live()
+                .filter_map(|(idx, seat)| {
+                    let door_card = seat.seat().up_cards().first().copied()?;
+                    variant
+                        .door_card_strength(&door_card)
+                        .map(|strength| (strength, idx))
+                })
+                .min()
+                .map(|(_, idx)| idx);
+            if let Some(bring_in_idx) = bring_in_idx {
+                let amount = seats[bring_in_idx].seat_mut().put_in(betting.bring_in);
+                self.bet_base = self.bet_base.max(seats[bring_in_idx].seat().bet);
+                self.completion = (betting.bring_in < betting.big_blind)
+                    .then_some(betting.big_blind - betting.bring_in);
+                self.first_to_act = (bring_in_idx + 1) % seats.len();
+                street.bring_in = Some((bring_in_idx, amount));
+            }
+        } else if let Some((idx, _)) = live().max_by_key(|(idx, seat)| {
+            (
+                variant.visible_strength(&seat.seat().up_cards()),
+                std::cmp::Reverse(*idx),
+            )
+        }) {
+            self.first_to_act = idx;
+        }
+        street
+    }
+
+    /// Throws away the `discards` a player holds and deals as many new cards,
+    /// returning them. Out of cards, the last picks are kept
+    pub fn draw<S: Seated, R: Rng + ?Sized>(
+        &mut self,
+        seats: &mut [S],
+        idx: usize,
+        mut discards: Vec<Card>,
+        rng: &mut R,
+    ) -> Vec<Card> {
+        let seat = seats[idx].seat_mut();
+        let mut picked = Vec::with_capacity(discards.len());
+        discards.retain(|discard| {
+            let in_hand = seat
+                .dealt_cards
+                .iter()
+                .any(|card| card.index() == discard.index());
+            let first_pick = !picked.contains(&discard.index());
+            picked.push(discard.index());
+            in_hand && first_pick
+        });
+        let drawn = self.deal_cards(discards.len(), Owner::Player, rng);
+        discards.truncate(drawn.len());
+        for (discard, &card) in discards.iter().zip(drawn.iter()) {
+            if let Some(position) = seat
+                .dealt_cards
+                .iter()
+                .position(|dealt| dealt.index() == discard.index())
+            {
+                seat.dealt_cards[position] = card;
+            }
+        }
+        // Thrown away only now, so a player never draws their own discards
+        self.discards.extend(discards);
+        drawn
+    }
+
+    /// Smallest and largest raise the player at `idx` can make, see
+    /// `BettingStructure::raise_bounds`. A stud bring-in is completed to a
+    /// full bet before anyone can raise
+    pub fn raise_bounds<S: Seated>(
+        &self,
+        seats: &[S],
+        idx: usize,
+        betting: &BettingStructure,
+    ) -> Option<(u32, Option<u32>)> {
+        match self.completion {
+            Some(completion) => Some((completion, Some(completion))),
+            None => betting.raise_bounds(
+                self.pot(seats),
+                self.to_call(seats, idx),
+                self.betting_round,
+                self.raises,
+            ),
+        }
+    }
+
+    /// Takes the decision of the player at `idx`, or says why the table
+    /// refuses it. A raise short of the minimum is only taken all in
+    pub fn apply<S: Seated>(
+        &mut self,
+        seats: &mut [S],
+        idx: usize,
+        decision: Decision,
+        betting: &BettingStructure,
+    ) -> Result<(), String> {
+        let to_call = self.to_call(seats, idx);
+        match decision {
+            Decision::Fold => seats[idx].seat_mut().is_betting = false,
+            Decision::Check if to_call > 0 => return Err("Cannot check".to_string()),
+            Decision::Check => {}
+            Decision::Call => {
+                // Calling with less than the bet puts the player all in, the
+                // rest of the action goes to side pots
+                seats[idx].seat_mut().put_in(to_call);
+            }
+            Decision::Raise(raise) => {
+                let funds = seats[idx].seat().funds;
+                let all_in = to_call + raise == funds;
+                match self.raise_bounds(seats, idx, betting) {
+                    None => {
+                        return Err(format!(
+                            "{} betting is capped at {} bets a round",
+                            betting.limit, FIXED_LIMIT_MAX_BETS
+                        ))
+                    }
+                    Some((min_raise, max_raise))
+                        if (raise < min_raise && !all_in)
+                            || max_raise.is_some_and(|max| raise > max) =>
+                    {
+                        let max_label =
+                            max_raise.map_or("all in".to_string(), |max| max.to_string());
+                        return Err(format!(
+                            "{} raise must be between {} and {}",
+                            betting.limit, min_raise, max_label
+                        ));
+                    }
+                    _ if to_call + raise > funds => return Err("Not enough funds".to_string()),
+                    _ => {}
+                }
+                seats[idx].seat_mut().put_in(to_call + raise);
+                self.bet_base += raise;
+                if raise > 0 {
+                    self.completion = None;
+                    self.raises.record(raise);
+                }
+            }
+        }
+
+        if self.actions.len() <= self.betting_round {
+            self.actions.resize(self.betting_round + 1, Vec::new());
+        }
+        self.actions[self.betting_round].push(decision);
+        if self.acted.len() < seats.len() {
+            self.acted.resize(seats.len(), false);
+        }
+        self.acted[idx] = true;
+        self.turn = Some((idx + 1) % seats.len());
+        Ok(())
+    }
+
+    /// The closest decision to `decision` the table takes: a check facing a
+    /// bet folds, a raise the round has no room for calls, and a raise is
+    /// kept within the table's bounds, going all in when the stack is short
+    pub fn legalize<S: Seated>(
+        &self,
+        seats: &[S],
+        idx: usize,
+        decision: Decision,
+        betting: &BettingStructure,
+    ) -> Decision {
+        let to_call = self.to_call(seats, idx);
+        let funds = seats[idx].seat().funds;
+        match (decision, self.raise_bounds(seats, idx, betting)) {
+            (Decision::Check, _) if to_call > 0 => Decision::Fold,
+            (Decision::Raise(_), None) => Decision::Call,
+            (Decision::Raise(_), _) if funds <= to_call => Decision::Call,
+            (Decision::Raise(raise), Some((min_raise, max_raise))) => {
+                let raise = raise.max(min_raise);
+                let raise = max_raise.map_or(raise, |max| raise.min(max));
+                Decision::Raise(raise.min(funds - to_call))
+            }
+            (decision, _) => decision,
+        }
+    }
+
+    /// Seat the action is on, none once the betting round is over: everyone
+    /// who can still bet has acted since the last raise, or nobody is left
+    /// to bet against
+    pub fn next_to_act<S: Seated>(&self, seats: &[S]) -> Option<usize> {
+        let n_seats = seats.len();
+        let n_live = seats.iter().filter(|seat| seat.seat().is_betting).count();
+        let n_acting = seats.iter().filter(|seat| seat.seat().can_act()).count();
+        let matched = seats
+            .iter()
+            .map(Seated::seat)
+            .filter(|seat| seat.can_act())
+            .all(|seat| seat.bet == self.bet_base);
+        let done = |idx: usize| {
+            let seat = seats[idx].seat();
+            !seat.can_act()
+                || (self.acted.get(idx).copied().unwrap_or(false) && seat.bet == self.bet_base)
+        };
+        if n_live <= 1 || (n_acting <= 1 && matched) {
+            return None;
+        }
+        let from = self.turn.unwrap_or(self.first_to_act);
+        (0..n_seats)
+            .map(|offset| (from + offset) % n_seats)
+            .find(|&idx| !done(idx))
+    }
+
+    /// Moves on to the next betting round. Once the blinds are played,
+    /// rounds open left of the button
+    pub fn end_betting_round<S: Seated>(&mut self, seats: &[S], variant: &dyn GameVariant) {
+        if self.betting_round == 0 {
+            self.saw_flop = seats.iter().filter(|seat| seat.seat().is_betting).count() > 1;
+        }
+        self.betting_round += 1;
+        self.completion = None;
+        self.raises = RoundRaises::default();
+        self.acted.clear();
+        self.turn = None;
+        if variant.steps().contains(&PokerStep::Blind) {
+            self.first_to_act = (self.button_idx + 1) % seats.len();
+        }
+    }
+
+    /// Pays the pots, every board winning an even share of each, after the
+    /// uncalled bet goes back and the house takes its rake. `boards` holds
+    /// each runout, or just the community cards. A hand whose showdown fails
+    /// is refunded instead
+    pub fn settle<S: Seated>(
+        &mut self,
+        seats: &mut [S],
+        variant: &dyn GameVariant,
+        boards: &[Vec<Card>],
+        rake: Option<&Rake>,
+    ) -> Result<Settlement, &'static str> {
+        let no_board = [Vec::new()];
+        let boards = match boards.is_empty() {
+            true => &no_board[..],
+            false => boards,
+        };
+        let n_seats = seats.len();
+        let live: Vec<usize> = (0..n_seats)
+            .filter(|&idx| seats[idx].seat().is_betting)
+            .collect();
+        let showdown = live.len() > 1;
+        let mut board_hands: Vec<Vec<Option<ShowdownHand>>> = Vec::with_capacity(boards.len());
+        for board in boards.iter() {
+            let mut showdown_hands = vec![None; n_seats];
+            if showdown {
+                let hands: Vec<Vec<Card>> = live
+                    .iter()
+                    .map(|&idx| {
+                        let seat = seats[idx].seat();
+                        seat.dealt_cards
+                            .iter()
+                            .chain(board.iter())
+                            .copied()
+                            .collect()
+                    })
+                    .collect();
+                let showdown = match variant.showdown(&hands) {
+                    Ok(showdown) => showdown,
+                    Err(err) => {
+                        self.refund(seats);
+                        return Err(err);
+                    }
+                };
+                for (&idx, hand) in live.iter().zip(showdown) {
+                    showdown_hands[idx] = Some(hand);
+                }
+            }
+            board_hands.push(showdown_hands);
+        }
+
+        let mut bets: Vec<u32> = seats.iter().map(|seat| seat.seat().bet).collect();
+        if let Some((idx, amount)) = uncalled_bet(&bets) {
+            seats[idx].seat_mut().funds += amount;
+            bets[idx] -= amount;
+        }
+        let mut contributions = bets.clone();
+        if let Some(idx) = self.dead_money_idx {
+            contributions[idx] += self.dead_money;
+        }
+        let folded: Vec<bool> = seats.iter().map(|seat| !seat.seat().is_betting).collect();
+        let mut pots = side_pots(&bets, &folded);
+        match pots.first_mut() {
+            Some(main_pot) => main_pot.amount += self.dead_money,
+            None if self.dead_money > 0 => pots.push(Pot {
+                amount: self.dead_money,
+                eligible: live.clone(),
+            }),
+            None => {}
+        }
+        // The house takes its cut before the pots are paid out
+        let n_dealt = seats
+            .iter()
+            .filter(|seat| !seat.seat().dealt_cards.is_empty())
+            .count();
+        let rake: u32 = rake.map_or(0, |rake| {
+            rake.take(&mut pots, n_dealt, self.saw_flop).iter().sum()
+        });
+
+        // Odd chips go to the first winners left of the button, and to the
+        // first boards
+        let first_seat = (self.button_idx + 1) % n_seats;
+        let n_boards = board_hands.len().max(1) as u32;
+        let mut awards: Vec<(usize, PotAward)> = Vec::new();
+        for (runout, hands) in board_hands.iter().enumerate() {
+            for pot in pots.iter() {
+                let share = Pot {
+                    amount: pot.amount / n_boards
+                        + ((runout as u32) < pot.amount % n_boards) as u32,
+                    eligible: pot.eligible.clone(),
+                };
+                let award = match showdown {
+                    true => award_pot(&share, hands, first_seat),
+                    false => uncontested(&share, &live, n_seats),
+                };
+                awards.push((runout, award));
+            }
+        }
+        let mut prizes = vec![0; n_seats];
+        for (_, award) in awards.iter() {
+            for (total, prize) in prizes.iter_mut().zip(award.prizes.iter()) {
+                *total += prize;
+            }
+        }
+        for (seat, &prize) in seats.iter_mut().zip(prizes.iter()) {
+            seat.seat_mut().funds += prize;
+        }
+        self.bet_base = 0;
+
+        Ok(Settlement {
+            pots,
+            hands: board_hands,
+            awards,
+            prizes,
+            rake,
+            contributions,
+            showdown,
+        })
+    }
+
+    /// Gives every chip put in the hand back to the player who put it in,
+    /// for a hand that cannot be settled
+    pub fn refund<S: Seated>(&mut self, seats: &mut [S]) {
+        for seat in seats.iter_mut().map(Seated::seat_mut) {
+            seat.funds += std::mem::take(&mut seat.bet);
+        }
+        let dead_money = std::mem::take(&mut self.dead_money);
+        if let Some(idx) = self.dead_money_idx.take() {
+            seats[idx].seat_mut().funds += dead_money;
+        }
+        self.bet_base = 0;
+    }
+}
+
+/// A pot left to the only player who did not fold
+fn uncontested(pot: &Pot, live: &[usize], n_seats: usize) -> PotAward {
+    let mut prizes = vec![0; n_seats];
+    if let Some(&winner) = live.first() {
+        prizes[winner] = pot.amount;
+    }
+    PotAward {
+        amount: pot.amount,
+        high_winners: live.to_vec(),
+        low_winners: Vec::new(),
+        prizes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+    use crate::variant::{Omaha, TexasHoldem};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn seats(funds: &[u32]) -> Vec<HandSeat> {
+        funds
+            .iter()
+            .map(|&funds| HandSeat {
+                is_betting: true,
+                funds,
+                ..HandSeat::default()
+            })
+            .collect()
+    }
+
+    fn holdem_hand(seats: &mut [HandSeat]) -> (Hand, BettingStructure) {
+        let betting = TexasHoldem.betting_structure();
+        let mut hand = Hand::default();
+        hand.start(TexasHoldem.deck());
+        hand.post_blinds(seats, &betting, &ForcedBets::default(), &[]);
+        (hand, betting)
+    }
+
+    #[test]
+    fn the_big_blind_closes_the_first_round() {
+        let mut seats = seats(&[1_000, 1_000, 1_000]);
+        let (mut hand, betting) = holdem_hand(&mut seats);
+        let mut order = Vec::new();
+        while let Some(idx) = hand.next_to_act(&seats) {
+            order.push(idx);
+            let decision = match hand.to_call(&seats, idx) {
+                0 => Decision::Check,
+                _ => Decision::Call,
+            };
+            hand.apply(&mut seats, idx, decision, &betting).unwrap();
+        }
+        // Blinds in seats 0 and 1, the big blind checks its option
+        assert_eq!(order, vec![2, 0, 1]);
+        assert_eq!(hand.actions[0].last(), Some(&Decision::Check));
+
+        hand.end_betting_round(&seats, &TexasHoldem);
+        assert_eq!(hand.next_to_act(&seats), Some(0));
+        hand.apply(&mut seats, 0, Decision::Raise(40), &betting)
+            .unwrap();
+        assert_eq!(hand.next_to_act(&seats), Some(1));
+        hand.apply(&mut seats, 1, Decision::Fold, &betting).unwrap();
+        hand.apply(&mut seats, 2, Decision::Call, &betting).unwrap();
+        assert_eq!(hand.next_to_act(&seats), None);
+    }
+
+    #[test]
+    fn refused_decisions_leave_the_hand_as_it_was() {
+        let mut seats = seats(&[1_000, 1_000, 100]);
+        let (mut hand, betting) = holdem_hand(&mut seats);
+        assert!(hand
+            .apply(&mut seats, 2, Decision::Check, &betting)
+            .is_err());
+        assert!(hand
+            .apply(&mut seats, 2, Decision::Raise(5), &betting)
+            .is_err());
+        assert!(hand
+            .apply(&mut seats, 2, Decision::Raise(200), &betting)
+            .is_err());
+        assert_eq!(hand.next_to_act(&seats), Some(2));
+        assert_eq!(hand.pot(&seats), 30);
+
+        // More than the stack goes all in
+        assert_eq!(
+            hand.legalize(&seats, 2, Decision::Raise(500), &betting),
+            Decision::Raise(80)
+        );
+        assert!(hand
+            .apply(&mut seats, 2, Decision::Raise(80), &betting)
+            .is_ok());
+        assert_eq!(seats[2].funds, 0);
+        assert_eq!(
+            hand.legalize(&seats, 0, Decision::Check, &betting),
+            Decision::Fold
+        );
+    }
+
+    #[test]
+    fn a_hand_that_cannot_be_shown_down_is_refunded() {
+        let mut seats = seats(&[1_000, 1_000]);
+        let (mut hand, _) = holdem_hand(&mut seats);
+        hand.dead_money = 15;
+        hand.dead_money_idx = Some(1);
+        seats[1].funds -= 15;
+        // Omaha hands need three cards of the board
+        seats[0].dealt_cards = parse_cards("AsAhKdQc", Owner::Player).unwrap();
+        seats[1].dealt_cards = parse_cards("KsKh7c8c", Owner::Player).unwrap();
+        let omaha = Omaha { hole_cards: 4 };
+        let result = hand.settle(&mut seats, &omaha, &[Vec::new()], None);
+        assert!(result.is_err());
+        assert_eq!(seats[0].funds, 1_000);
+        assert_eq!(seats[1].funds, 1_000);
+        assert_eq!(hand.pot(&seats), 0);
+    }
+
+    #[test]
+    fn an_uncontested_pot_goes_to_the_last_player_in() {
+        let mut seats = seats(&[1_000, 1_000, 1_000]);
+        let (mut hand, betting) = holdem_hand(&mut seats);
+        let mut rng = StdRng::seed_from_u64(1);
+        hand.deal_hole_cards(&mut seats, 2, &mut rng);
+        hand.apply(&mut seats, 2, Decision::Raise(60), &betting)
+            .unwrap();
+        hand.apply(&mut seats, 0, Decision::Fold, &betting).unwrap();
+        hand.apply(&mut seats, 1, Decision::Fold, &betting).unwrap();
+        assert_eq!(hand.next_to_act(&seats), None);
+
+        // The raise nobody called goes back before the blinds are won
+        let settlement = hand.settle(&mut seats, &TexasHoldem, &[], None).unwrap();
+        assert!(!settlement.showdown);
+        assert_eq!(settlement.prizes, vec![0, 0, 50]);
+        let funds: Vec<u32> = seats.iter().map(|seat| seat.funds).collect();
+        assert_eq!(funds, vec![990, 980, 1_030]);
+    }
+}
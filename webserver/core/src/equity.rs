@@ -0,0 +1,765 @@
+use crate::card::{Card, Owner, DECK};
+use crate::combinations::{combinations, n_choose_k};
+use crate::game::GameType;
+use crate::hand::{evaluate_best_hand, hand_score};
+use crate::variant::{GameVariant, ShowdownHand};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::fmt::{Display, Formatter};
+use std::thread;
+
+const BOARD_SIZE: usize = 5;
+const SAMPLES_PER_BATCH: u64 = 4_096;
+const Z_SCORE_95: f64 = 1.96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquityMethod {
+    Exact,
+    MonteCarlo,
+}
+
+impl Display for EquityMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exact => write!(f, "Exact"),
+            Self::MonteCarlo => write!(f, "Monte Carlo"),
+        }
+    }
+}
+
+pub struct EquityConfig {
+    pub game_type: GameType,
+    /// Known hole cards per player, missing ones are dealt from the stub
+    pub hands: Vec<Vec<Card>>,
+    pub board: Vec<Card>,
+    pub dead_cards: Vec<Card>,
+    /// Largest number of runouts enumerated before switching to Monte Carlo
+    pub exact_limit: u64,
+    pub iterations: u64,
+    pub seed: u64,
+    /// Worker threads, 0 uses every available core
+    pub threads: usize,
+}
+
+impl EquityConfig {
+    pub fn new(game_type: GameType, hands: Vec<Vec<Card>>, board: Vec<Card>) -> Self {
+        Self {
+            game_type,
+            hands,
+            board,
+            dead_cards: Vec::new(),
+            exact_limit: 250_000,
+            iterations: 100_000,
+            seed: 0,
+            threads: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayerEquity {
+    pub win: f64,
+    pub tie: f64,
+    pub equity: f64,
+    /// Half width of the 95% confidence interval of `equity`, zero when exact
+    pub margin: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EquityResult {
+    pub method: EquityMethod,
+    pub samples: u64,
+    /// Monte Carlo samples given up on because no deal without clashing
+    /// cards was found, not counted in `samples`
+    pub dropped: u64,
+    pub players: Vec<PlayerEquity>,
+}
+
+/// Weighted win/tie/equity sums, merged across worker threads
+#[derive(Clone)]
+pub(crate) struct Tally {
+    samples: u64,
+    dropped: u64,
+    weight: f64,
+    wins: Vec<f64>,
+    ties: Vec<f64>,
+    equity: Vec<f64>,
+    equity_squared: Vec<f64>,
+}
+
+impl Tally {
+    pub(crate) fn new(n_players: usize) -> Self {
+        Self {
+            samples: 0,
+            dropped: 0,
+            weight: 0.0,
+            wins: vec![0.0; n_players],
+            ties: vec![0.0; n_players],
+            equity: vec![0.0; n_players],
+            equity_squared: vec![0.0; n_players],
+        }
+    }
+
+    pub(crate) fn record(&mut self, scores: &[u32], weight: f64) {
+        let max_score = scores.iter().copied().max().unwrap_or(0);
+        let n_winners = scores.iter().filter(|&&score| score == max_score).count();
+        let share = 1.0 / n_winners as f64;
+
+        self.samples += 1;
+        self.weight += weight;
+        for (idx, &score) in scores.iter().enumerate() {
+            if score != max_score {
+                continue;
+            }
+            if n_winners == 1 {
+                self.wins[idx] += weight;
+            } else {
+                self.ties[idx] += weight;
+            }
+            self.equity[idx] += share * weight;
+            self.equity_squared[idx] += share * share * weight;
+        }
+    }
+
+    /// Records the share of the pot each player won on one sample
+    pub(crate) fn record_shares(&mut self, shares: &[f64], weight: f64) {
+        self.samples += 1;
+        self.weight += weight;
+        for (idx, &share) in shares.iter().enumerate() {
+            if share <= 0.0 {
+                continue;
+            }
+            if share >= 1.0 {
+                self.wins[idx] += weight;
+            } else {
+                self.ties[idx] += weight;
+            }
+            self.equity[idx] += share * weight;
+            self.equity_squared[idx] += share * share * weight;
+        }
+    }
+
+    /// Counts a sample that could not be dealt or scored
+    pub(crate) fn drop_sample(&mut self) {
+        self.dropped += 1;
+    }
+
+    pub(crate) fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    fn merge(&mut self, other: Tally) {
+        self.samples += other.samples;
+        self.dropped += other.dropped;
+        self.weight += other.weight;
+        for idx in 0..self.wins.len() {
+            self.wins[idx] += other.wins[idx];
+            self.ties[idx] += other.ties[idx];
+            self.equity[idx] += other.equity[idx];
+            self.equity_squared[idx] += other.equity_squared[idx];
+        }
+    }
+
+    pub(crate) fn into_result(self, method: EquityMethod) -> EquityResult {
+        let weight = if self.weight > 0.0 { self.weight } else { 1.0 };
+        let players = (0..self.wins.len())
+            .map(|idx| {
+                let equity = self.equity[idx] / weight;
+                let margin = match method {
+                    EquityMethod::Exact => 0.0,
+                    EquityMethod::MonteCarlo => {
+                        let variance = (self.equity_squared[idx] / weight - equity * equity).max(0.0);
+                        Z_SCORE_95 * (variance / self.samples.max(1) as f64).sqrt()
+                    }
+                };
+                PlayerEquity {
+                    win: self.wins[idx] / weight,
+                    tie: self.ties[idx] / weight,
+                    equity,
+                    margin,
+                }
+            })
+            .collect();
+
+        EquityResult {
+            method,
+            samples: self.samples,
+            dropped: self.dropped,
+            players,
+        }
+    }
+}
+
+/// Validated equity problem: the cards still to be dealt are split into
+/// slots, one per player followed by one for the board
+struct EquitySetup {
+    game_type: GameType,
+    hands: Vec<Vec<Card>>,
+    board: Vec<Card>,
+    stub: Vec<Card>,
+    slots: Vec<usize>,
+}
+
+impl EquitySetup {
+    fn new(config: &EquityConfig) -> Result<Self, &'static str> {
+        if config.hands.len() < 2 {
+            return Err("equity needs at least two players");
+        }
+        if config.board.len() > BOARD_SIZE {
+            return Err("board cannot have more than 5 cards");
+        }
+
+        let hole_count = config.game_type.hole_card_count();
+        let mut used = [false; DECK.len()];
+        for card in config
+            .hands
+            .iter()
+            .flatten()
+            .chain(config.board.iter())
+            .chain(config.dead_cards.iter())
+        {
+            if used[card.index()] {
+                return Err("card dealt more than once");
+            }
+            used[card.index()] = true;
+        }
+
+        let mut slots = Vec::with_capacity(config.hands.len() + 1);
+        for hand in config.hands.iter() {
+            if hand.len() > hole_count {
+                return Err("too many hole cards for game type");
+            }
+            slots.push(hole_count - hand.len());
+        }
+        slots.push(BOARD_SIZE - config.board.len());
+
+        let stub: Vec<Card> = DECK
+            .iter()
+            .filter(|card| !used[card.index()])
+            .copied()
+            .collect();
+        if slots.iter().sum::<usize>() > stub.len() {
+            return Err("not enough cards left in the deck");
+        }
+
+        Ok(Self {
+            game_type: config.game_type,
+            hands: config
+                .hands
+                .iter()
+                .map(|hand| with_owner(hand, Owner::Player))
+                .collect(),
+            board: with_owner(&config.board, Owner::Community),
+            stub,
+            slots,
+        })
+    }
+
+    fn runout_count(&self) -> u64 {
+        let mut remaining = self.stub.len();
+        let mut count: u64 = 1;
+        for &slot in self.slots.iter() {
+            count = count.saturating_mul(n_choose_k(remaining, slot));
+            remaining -= slot;
+        }
+        count
+    }
+
+    /// Scores every player given the cards dealt into the slots, in slot order
+    fn score_runout(&self, dealt: &[Card]) -> Vec<u32> {
+        let n_players = self.hands.len();
+        let mut offset = 0;
+        let mut holes: Vec<Vec<Card>> = Vec::with_capacity(n_players);
+        for (hand, &slot) in self.hands.iter().zip(self.slots.iter()) {
+            let mut hole = hand.clone();
+            hole.extend(with_owner(&dealt[offset..offset + slot], Owner::Player));
+            holes.push(hole);
+            offset += slot;
+        }
+        let mut board = self.board.clone();
+        board.extend(with_owner(&dealt[offset..], Owner::Community));
+
+        score_hands(self.game_type, &holes, &board)
+    }
+
+    fn enumerate(&self, slot: usize, available: &[Card], dealt: &mut Vec<Card>, tally: &mut Tally) {
+        if slot == self.slots.len() {
+            tally.record(&self.score_runout(dealt), 1.0);
+            return;
+        }
+        if self.slots[slot] == 0 {
+            self.enumerate(slot + 1, available, dealt, tally);
+            return;
+        }
+
+        for combo in combinations(available, self.slots[slot]) {
+            self.enumerate_with(slot, available, &combo, dealt, tally);
+        }
+    }
+
+    fn enumerate_with(
+        &self,
+        slot: usize,
+        available: &[Card],
+        combo: &[Card],
+        dealt: &mut Vec<Card>,
+        tally: &mut Tally,
+    ) {
+        let remaining: Vec<Card> = available
+            .iter()
+            .filter(|card| !combo.contains(card))
+            .copied()
+            .collect();
+        dealt.extend_from_slice(combo);
+        self.enumerate(slot + 1, &remaining, dealt, tally);
+        dealt.truncate(dealt.len() - combo.len());
+    }
+
+    fn exact(&self, threads: usize) -> Tally {
+        let n_players = self.hands.len();
+        // Work is split across threads on the combinations of the first slot
+        // that still needs cards
+        let Some(first_slot) = self.slots.iter().position(|&slot| slot > 0) else {
+            let mut tally = Tally::new(n_players);
+            tally.record(&self.score_runout(&[]), 1.0);
+            return tally;
+        };
+        let first_combos = combinations(&self.stub, self.slots[first_slot]);
+
+        tally_parallel(&first_combos, threads, n_players, |combo, tally| {
+            let mut dealt = Vec::with_capacity(self.stub.len());
+            self.enumerate_with(first_slot, &self.stub, combo, &mut dealt, tally);
+        })
+    }
+
+    fn monte_carlo(&self, iterations: u64, seed: u64, threads: usize) -> Tally {
+        let n_dealt: usize = self.slots.iter().sum();
+        let n_players = self.hands.len();
+        tally_batches(
+            iterations,
+            seed,
+            threads,
+            n_players,
+            &self.stub,
+            |stub, rng, tally| {
+                let (dealt, _) = stub.partial_shuffle(rng, n_dealt);
+                tally.record(&self.score_runout(dealt), 1.0);
+            },
+        )
+    }
+}
+
+/// Scores each player's hole cards against the board, higher is better
+pub(crate) fn score_hands(game_type: GameType, holes: &[Vec<Card>], board: &[Card]) -> Vec<u32> {
+    holes
+        .iter()
+        .map(|hole| {
+            let cards: Vec<Card> = hole.iter().chain(board.iter()).copied().collect();
+            evaluate_best_hand(&cards, game_type)
+                .map_or(0, |(hand_type, ranks)| hand_score(hand_type, &ranks))
+        })
+        .collect()
+}
+
+pub(crate) fn worker_threads(requested: usize) -> usize {
+    match requested {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Splits `items` round-robin across worker threads and merges their tallies
+pub(crate) fn tally_parallel<T, F>(items: &[T], threads: usize, n_players: usize, work: F) -> Tally
+where
+    T: Sync,
+    F: Fn(&T, &mut Tally) + Sync,
+{
+    run_workers(threads, n_players, |worker| {
+        let mut tally = Tally::new(n_players);
+        for item in items.iter().skip(worker).step_by(threads) {
+            work(item, &mut tally);
+        }
+        tally
+    })
+}
+
+/// Runs `iterations` samples across worker threads. Each batch owns its own
+/// seeded generator, and its own copy of `scratch` reset from the original,
+/// so the result does not depend on the thread count. Workers reuse one
+/// scratch buffer across their batches
+pub(crate) fn tally_batches<S, F>(
+    iterations: u64,
+    seed: u64,
+    threads: usize,
+    n_players: usize,
+    scratch: &S,
+    sample: F,
+) -> Tally
+where
+    S: Clone + Sync,
+    F: Fn(&mut S, &mut StdRng, &mut Tally) + Sync,
+{
+    let n_batches = iterations.div_ceil(SAMPLES_PER_BATCH);
+
+    run_workers(threads, n_players, |worker| {
+        let mut tally = Tally::new(n_players);
+        let mut buffer = scratch.clone();
+        for batch in (worker as u64..n_batches).step_by(threads) {
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(batch));
+            buffer.clone_from(scratch);
+            let batch_size = SAMPLES_PER_BATCH.min(iterations - batch * SAMPLES_PER_BATCH);
+            for _ in 0..batch_size {
+                sample(&mut buffer, &mut rng, &mut tally);
+            }
+        }
+        tally
+    })
+}
+
+/// Runs `job` once per worker and merges the tallies. A single worker runs on
+/// the calling thread, which is also what targets without threads (wasm) need
+fn run_workers<F>(threads: usize, n_players: usize, job: F) -> Tally
+where
+    F: Fn(usize) -> Tally + Sync,
+{
+    if threads <= 1 {
+        return job(0);
+    }
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|worker| {
+                let job = &job;
+                scope.spawn(move || job(worker))
+            })
+            .collect();
+
+        let mut tally = Tally::new(n_players);
+        for worker in workers {
+            tally.merge(worker.join().expect("equity worker panicked"));
+        }
+        tally
+    })
+}
+
+pub(crate) fn with_owner(cards: &[Card], owner: Owner) -> Vec<Card> {
+    cards.iter().map(|&card| Card { owner, ..card }).collect()
+}
+
+pub fn calculate_equity(config: &EquityConfig) -> Result<EquityResult, &'static str> {
+    let setup = EquitySetup::new(config)?;
+    let threads = worker_threads(config.threads);
+
+    if setup.runout_count() <= config.exact_limit {
+        Ok(setup.exact(threads).into_result(EquityMethod::Exact))
+    } else {
+        if config.iterations == 0 {
+            return Err("monte carlo equity needs at least one iteration");
+        }
+        Ok(setup
+            .monte_carlo(config.iterations, config.seed, threads)
+            .into_result(EquityMethod::MonteCarlo))
+    }
+}
+
+/// Share of a pot each hand wins: the best high hands split it, or half of
+/// it when a qualifying low takes the other half
+fn pot_shares(hands: &[ShowdownHand]) -> Vec<f64> {
+    let best_high = hands.iter().map(|hand| hand.high).max();
+    let best_low = hands.iter().filter_map(|hand| hand.low).min();
+    let n_high = hands
+        .iter()
+        .filter(|hand| Some(hand.high) == best_high)
+        .count();
+    let n_low = hands
+        .iter()
+        .filter(|hand| hand.low.is_some() && hand.low == best_low)
+        .count();
+    let high_share = if n_low > 0 { 0.5 } else { 1.0 };
+
+    hands
+        .iter()
+        .map(|hand| {
+            let mut share = 0.0;
+            if Some(hand.high) == best_high {
+                share += high_share / n_high as f64;
+            }
+            if hand.low.is_some() && hand.low == best_low {
+                share += (1.0 - high_share) / n_low as f64;
+            }
+            share
+        })
+        .collect()
+}
+
+/// Equity of complete hands in any board game, e.g. once players are all in.
+/// Split pot games count the low half. Every remaining board is enumerated
+/// when there are at most `samples` of them, otherwise `samples` random
+/// boards are dealt
+pub fn runout_equity(
+    variant: &dyn GameVariant,
+    hands: &[Vec<Card>],
+    board: &[Card],
+    dead_cards: &[Card],
+    samples: u64,
+    seed: u64,
+) -> Result<EquityResult, &'static str> {
+    if hands.len() < 2 {
+        return Err("equity needs at least two players");
+    }
+    let board_size = variant.board_card_count();
+    if board.len() > board_size {
+        return Err("board has too many cards for the game");
+    }
+
+    let mut used = [false; DECK.len()];
+    for card in hands.iter().flatten().chain(board).chain(dead_cards) {
+        if used[card.index()] {
+            return Err("card dealt more than once");
+        }
+        used[card.index()] = true;
+    }
+    let stub: Vec<Card> = variant
+        .deck()
+        .into_iter()
+        .filter(|card| !used[card.index()])
+        .collect();
+    let n_cards = board_size - board.len();
+    if n_cards > stub.len() {
+        return Err("not enough cards left in the deck");
+    }
+
+    let holes: Vec<Vec<Card>> = hands
+        .iter()
+        .map(|hand| with_owner(hand, Owner::Player))
+        .collect();
+    let board = with_owner(board, Owner::Community);
+    let deal = |dealt: &[Card]| -> Vec<Vec<Card>> {
+        holes
+            .iter()
+            .map(|hole| {
+                hole.iter()
+                    .chain(board.iter())
+                    .copied()
+                    .chain(with_owner(dealt, Owner::Community))
+                    .collect()
+            })
+            .collect()
+    };
+    // Hands the game cannot evaluate fail on any runout, so one is enough
+    // to tell
+    variant.showdown(&deal(&stub[..n_cards]))?;
+    let score = |dealt: &[Card], tally: &mut Tally| match variant.showdown(&deal(dealt)) {
+        Ok(showdown) => tally.record_shares(&pot_shares(&showdown), 1.0),
+        Err(_) => tally.drop_sample(),
+    };
+
+    let threads = worker_threads(0);
+    if n_choose_k(stub.len(), n_cards) <= samples {
+        let runouts = combinations(&stub, n_cards);
+        let tally = tally_parallel(&runouts, threads, hands.len(), |dealt, tally| {
+            score(dealt, tally)
+        });
+        Ok(tally.into_result(EquityMethod::Exact))
+    } else {
+        let tally = tally_batches(
+            samples,
+            seed,
+            threads,
+            hands.len(),
+            &stub,
+            |stub, rng, tally| {
+                let (dealt, _) = stub.partial_shuffle(rng, n_cards);
+                score(dealt, tally);
+            },
+        );
+        Ok(tally.into_result(EquityMethod::MonteCarlo))
+    }
+}
+
+/// Share of the pot a partial hand wins on average against `opponents`
+/// random hands. Its missing hole cards, the opponents' hands and the rest
+/// of the board are dealt `samples` times, so draws count what the cards
+/// kept can still make. Cheap enough for a bot to call on every decision
+pub fn hand_strength(
+    variant: &dyn GameVariant,
+    hole_cards: &[Card],
+    board: &[Card],
+    dead_cards: &[Card],
+    opponents: usize,
+    samples: u64,
+    seed: u64,
+) -> Result<f64, &'static str> {
+    if samples == 0 {
+        return Err("hand strength needs at least one sample");
+    }
+    let n_hole = variant.hole_card_count();
+    let board_size = variant.board_card_count();
+    if hole_cards.len() > n_hole || board.len() > board_size {
+        return Err("too many cards for the game");
+    }
+    let mut used = [false; DECK.len()];
+    for card in hole_cards.iter().chain(board).chain(dead_cards) {
+        if used[card.index()] {
+            return Err("card dealt more than once");
+        }
+        used[card.index()] = true;
+    }
+    if opponents == 0 {
+        return Ok(1.0);
+    }
+    let mut stub: Vec<Card> = variant
+        .deck()
+        .into_iter()
+        .filter(|card| !used[card.index()])
+        .collect();
+    let n_own = n_hole - hole_cards.len();
+    let n_board = board_size - board.len();
+    let n_cards = n_own + n_board + opponents * n_hole;
+    if n_cards > stub.len() {
+        return Err("not enough cards left in the deck");
+    }
+
+    let hole_cards = with_owner(hole_cards, Owner::Player);
+    let board = with_owner(board, Owner::Community);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut won = 0.0;
+    for _ in 0..samples {
+        let (dealt, _) = stub.partial_shuffle(&mut rng, n_cards);
+        let (own, dealt) = dealt.split_at(n_own);
+        let (board_dealt, opponent_cards) = dealt.split_at(n_board);
+        let full_board: Vec<Card> = board
+            .iter()
+            .copied()
+            .chain(with_owner(board_dealt, Owner::Community))
+            .collect();
+        let own_hand: Vec<Card> = hole_cards
+            .iter()
+            .copied()
+            .chain(with_owner(own, Owner::Player))
+            .collect();
+        let hands: Vec<Vec<Card>> = std::iter::once(own_hand)
+            .chain(
+                opponent_cards
+                    .chunks(n_hole)
+                    .map(|hole| with_owner(hole, Owner::Player)),
+            )
+            .map(|hole| hole.into_iter().chain(full_board.iter().copied()).collect())
+            .collect();
+        won += pot_shares(&variant.showdown(&hands)?)[0];
+    }
+    Ok(won / samples as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+    use crate::variant::TexasHoldem;
+
+    fn cards(text: &str) -> Vec<Card> {
+        parse_cards(text, Owner::Player).unwrap()
+    }
+
+    fn holdem(hands: &[&str], board: &str) -> EquityConfig {
+        EquityConfig::new(
+            GameType::TexasHoldemPoker,
+            hands.iter().map(|hand| cards(hand)).collect(),
+            cards(board),
+        )
+    }
+
+    #[test]
+    fn aces_are_about_four_to_one_against_kings() {
+        let config = EquityConfig {
+            iterations: 50_000,
+            seed: 7,
+            threads: 1,
+            ..holdem(&["AsAh", "KsKh"], "")
+        };
+        let result = calculate_equity(&config).unwrap();
+        assert_eq!(result.method, EquityMethod::MonteCarlo);
+        assert!((result.players[0].equity - 0.82).abs() < 0.01);
+        assert!((result.players[1].equity - 0.18).abs() < 0.01);
+    }
+
+    #[test]
+    fn flopped_set_is_ahead_of_the_flush_draw() {
+        let result = calculate_equity(&holdem(&["7s7d", "AhKh"], "7c8h2h")).unwrap();
+        assert_eq!(result.method, EquityMethod::Exact);
+        assert_eq!(result.samples, 990);
+        let set = &result.players[0];
+        assert!(set.equity > 0.70 && set.equity < 0.80, "{}", set.equity);
+        assert_eq!(set.margin, 0.0);
+        let total: f64 = result.players.iter().map(|player| player.equity).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn monte_carlo_agrees_with_the_exact_equity() {
+        let exact = calculate_equity(&holdem(&["AsKs", "QdQc"], "Ts7s2d")).unwrap();
+        let config = EquityConfig {
+            exact_limit: 0,
+            iterations: 40_000,
+            seed: 3,
+            threads: 1,
+            ..holdem(&["AsKs", "QdQc"], "Ts7s2d")
+        };
+        let sampled = calculate_equity(&config).unwrap();
+        assert_eq!(exact.method, EquityMethod::Exact);
+        assert_eq!(sampled.method, EquityMethod::MonteCarlo);
+        for (exact, sampled) in exact.players.iter().zip(sampled.players.iter()) {
+            assert!(sampled.margin > 0.0);
+            assert!((exact.equity - sampled.equity).abs() < sampled.margin);
+        }
+    }
+
+    #[test]
+    fn seeded_monte_carlo_does_not_depend_on_the_thread_count() {
+        let run = |threads| {
+            let config = EquityConfig {
+                iterations: 20_000,
+                seed: 11,
+                threads,
+                ..holdem(&["AsKs", "QdQc", "8h8c"], "")
+            };
+            calculate_equity(&config).unwrap()
+        };
+        let single = run(1);
+        let parallel = run(3);
+        assert_eq!(single.samples, parallel.samples);
+        for (single, parallel) in single.players.iter().zip(parallel.players.iter()) {
+            assert!((single.equity - parallel.equity).abs() < 1e-12);
+            assert!((single.win - parallel.win).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn clashing_and_misplaced_cards_are_errors() {
+        assert!(calculate_equity(&holdem(&["AsAh", "AsKh"], "")).is_err());
+        assert!(calculate_equity(&holdem(&["AsAh", "KsKh"], "Ah2c3d")).is_err());
+        assert!(calculate_equity(&holdem(&["AsAhKd", "KsKh"], "")).is_err());
+        assert!(calculate_equity(&holdem(&["AsAh", "KsKh"], "2c3c4c5c6c7c")).is_err());
+        assert!(calculate_equity(&holdem(&["AsAh"], "")).is_err());
+        assert!(parse_cards("AsXx", Owner::Player).is_err());
+        let dead = EquityConfig {
+            dead_cards: cards("Kh"),
+            ..holdem(&["AsAh", "KsKh"], "")
+        };
+        assert!(calculate_equity(&dead).is_err());
+    }
+
+    #[test]
+    fn hand_strength_checks_the_cards_without_opponents() {
+        let hole = cards("AsAh");
+        assert_eq!(
+            hand_strength(&TexasHoldem, &hole, &[], &[], 0, 10, 0),
+            Ok(1.0)
+        );
+        let clash = hand_strength(&TexasHoldem, &hole, &cards("As"), &[], 0, 10, 0);
+        assert!(clash.is_err());
+        let too_many = hand_strength(&TexasHoldem, &cards("AsAhKd"), &[], &[], 0, 10, 0);
+        assert!(too_many.is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy)]
+pub enum GameType {
+    TexasHoldemPoker = 0,
+    OmahaPoker
+}
+
+
+impl Display for GameType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result{
+        match self {
+            Self::TexasHoldemPoker => write!(f, "Texas Holdem Poker"),
+            Self::OmahaPoker => write!(f, "Omaha Poker")
+        }
+    }
+}
+
+impl GameType {
+    pub fn hole_card_count(&self) -> usize {
+        match self {
+            Self::TexasHoldemPoker => 2,
+            Self::OmahaPoker => 4,
+        }
+    }
+}
+
+impl FromStr for GameType {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "holdem" | "texas-holdem" => Ok(Self::TexasHoldemPoker),
+            "omaha" => Ok(Self::OmahaPoker),
+            _ => Err("unknown game, expected holdem or omaha"),
+        }
+    }
+}
+
+/// One stage of a hand, played in the order given by a variant's street
+/// sequence
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PokerStep {
+    Blind,
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+    Showdown,
+    BettingRound,
+    Ante,
+    ThirdStreet,
+    FourthStreet,
+    FifthStreet,
+    SixthStreet,
+    SeventhStreet,
+    /// Players discard and are dealt replacements, in turn
+    Draw,
+}
+
+impl PokerStep {
+    /// Face-up (`true`) and face-down cards each player gets on a stud street
+    pub fn stud_cards(&self) -> &'static [bool] {
+        match self {
+            Self::ThirdStreet => &[false, false, true],
+            Self::FourthStreet | Self::FifthStreet | Self::SixthStreet => &[true],
+            Self::SeventhStreet => &[false],
+            _ => &[],
+        }
+    }
+}
+
+pub const STANDARD_POKER_STEPS: [PokerStep; 10] = [
+    PokerStep::Blind,
+    PokerStep::PreFlop,
+    PokerStep::BettingRound,
+    PokerStep::Flop,
+    PokerStep::BettingRound,
+    PokerStep::Turn,
+    PokerStep::BettingRound,
+    PokerStep::River,
+    PokerStep::BettingRound,
+    PokerStep::Showdown,
+];
+
+pub const STUD_POKER_STEPS: [PokerStep; 12] = [
+    PokerStep::Ante,
+    PokerStep::ThirdStreet,
+    PokerStep::BettingRound,
+    PokerStep::FourthStreet,
+    PokerStep::BettingRound,
+    PokerStep::FifthStreet,
+    PokerStep::BettingRound,
+    PokerStep::SixthStreet,
+    PokerStep::BettingRound,
+    PokerStep::SeventhStreet,
+    PokerStep::BettingRound,
+    PokerStep::Showdown,
+];
+
+pub const FIVE_CARD_DRAW_STEPS: [PokerStep; 6] = [
+    PokerStep::Blind,
+    PokerStep::PreFlop,
+    PokerStep::BettingRound,
+    PokerStep::Draw,
+    PokerStep::BettingRound,
+    PokerStep::Showdown,
+];
+
+pub const TRIPLE_DRAW_STEPS: [PokerStep; 10] = [
+    PokerStep::Blind,
+    PokerStep::PreFlop,
+    PokerStep::BettingRound,
+    PokerStep::Draw,
+    PokerStep::BettingRound,
+    PokerStep::Draw,
+    PokerStep::BettingRound,
+    PokerStep::Draw,
+    PokerStep::BettingRound,
+    PokerStep::Showdown,
+];
@@ -0,0 +1,729 @@
+use crate::card::{Card, Owner, Rank};
+use crate::combinations::combinations;
+use crate::game::GameType;
+use std::cmp::{max, min};
+use std::fmt::{Display, Formatter};
+use std::result::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandType {
+    HighCard = 0,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    RoyalFlush,
+}
+
+impl Display for HandType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HighCard => write!(f, "High Card"),
+            Self::Pair => write!(f, "Pair"),
+            Self::TwoPair => write!(f, "Two Pair"),
+            Self::ThreeOfAKind => write!(f, "Three of a Kind"),
+            Self::Straight => write!(f, "Straight"),
+            Self::Flush => write!(f, "Flush"),
+            Self::FullHouse => write!(f, "Full House"),
+            Self::FourOfAKind => write!(f, "Four of a Kind"),
+            Self::StraightFlush => write!(f, "Straight Flush"),
+            Self::RoyalFlush => write!(f, "Royal Flush"),
+        }
+    }
+}
+
+/// Evaluation rules that change with the deck
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandRules {
+    /// Lowest rank in the deck, the ace plays right below it in the wheel
+    pub lowest_rank: Rank,
+    pub flush_beats_full_house: bool,
+    /// Whether the ace can play low in a straight (the wheel)
+    pub wheel: bool,
+}
+
+pub const STANDARD_RULES: HandRules = HandRules {
+    lowest_rank: Rank::Two,
+    flush_beats_full_house: false,
+    wheel: true,
+};
+
+/// Short Deck (6+), where A-6-7-8-9 is a straight and a flush is rarer than
+/// a full house
+pub const SHORT_DECK_RULES: HandRules = HandRules {
+    lowest_rank: Rank::Six,
+    flush_beats_full_house: true,
+    wheel: true,
+};
+
+/// Deuce-to-seven lowball, where the ace only plays high so A-2-3-4-5 is no
+/// straight. Hands are ranked as usual and the worst one wins
+pub const DEUCE_TO_SEVEN_RULES: HandRules = HandRules {
+    lowest_rank: Rank::Two,
+    flush_beats_full_house: false,
+    wheel: false,
+};
+
+impl HandRules {
+    /// Position of a hand type in this ruleset's ordering, higher is better
+    pub fn category(&self, hand_type: HandType) -> u32 {
+        match hand_type {
+            HandType::Flush if self.flush_beats_full_house => HandType::FullHouse as u32,
+            HandType::FullHouse if self.flush_beats_full_house => HandType::Flush as u32,
+            _ => hand_type as u32,
+        }
+    }
+
+    /// Single integer ordering of an evaluated hand, higher is better
+    pub fn score(&self, hand_type: HandType, sorted_ranks: &[u8; 5]) -> u32 {
+        const POW_BASE: u32 = 13;
+
+        let mut sum: u32 = 0;
+        for (j, rank) in sorted_ranks.iter().enumerate() {
+            sum += POW_BASE.pow((sorted_ranks.len() - j - 1) as u32) * *rank as u32;
+        }
+        sum + POW_BASE.pow(sorted_ranks.len() as u32) * self.category(hand_type)
+    }
+}
+
+fn get_straight(ranks: &Vec<u8>, rules: &HandRules) -> Option<Vec<u8>> {
+    let lowest_rank = rules.lowest_rank as u8;
+    let n: usize = ranks.len();
+    if n < 5 {
+        return None;
+    }
+    let mut continuity_count = 0;
+
+    for i in 0..n - 1 {
+        let delta = ranks[i] - ranks[i + 1];
+        if delta == 0 {
+            continue;
+        } else if delta == 1 {
+            continuity_count += 1;
+        } else {
+            continuity_count = 0;
+        }
+
+        if continuity_count == 4 {
+            let mut scale = Vec::with_capacity(5);
+            let base = ranks[i + 1] + 4;
+            for j in 0..5 {
+                scale.push(base - j);
+            }
+            return Some(scale);
+        }
+    }
+    // Wheel, the ace plays low so it goes last
+    if rules.wheel && ranks[0] == 12 && ranks[n - 1] == lowest_rank && continuity_count == 3 {
+        let mut scale = Vec::with_capacity(5);
+        for j in 0..4 {
+            scale.push(lowest_rank + 3 - j);
+        }
+        scale.push(12);
+        return Some(scale);
+    }
+
+    return None;
+}
+
+pub fn evaluate_hand(hand: &mut Vec<Card>) -> Result<(HandType, [u8; 5]), &'static str> {
+    evaluate_hand_with_rules(hand, &STANDARD_RULES)
+}
+
+pub fn evaluate_hand_with_rules(
+    hand: &mut Vec<Card>,
+    rules: &HandRules,
+) -> Result<(HandType, [u8; 5]), &'static str> {
+    hand.sort();
+    hand.reverse();
+
+    let mut sorted_card_rank = [0u8; 5];
+
+    let mut is_flush = false;
+    let mut is_straight = false;
+    let mut suit_count = [0u8; 4];
+
+    // Flush check
+    for i in 0..hand.len() {
+        let card = hand[i];
+        suit_count[card.suit as usize] += 1;
+
+        if suit_count[card.suit as usize] == 5 {
+            is_flush = true;
+
+            let mut filtered_hand: Vec<u8> = hand
+                .iter()
+                .filter(|_card| _card.suit == card.suit)
+                .map(|_card| _card.rank as u8)
+                .collect();
+            // Flush + scale check (royal/straight)
+            match get_straight(&mut filtered_hand, rules) {
+                Some(straight) => {
+                    for i in 0..5 {
+                        sorted_card_rank[i] = straight[i];
+                    }
+                    is_straight = true;
+                }
+                None => {
+                    for i in 0..5 {
+                        sorted_card_rank[i] = filtered_hand[i];
+                    }
+                }
+            }
+            break;
+        }
+    }
+
+    if is_flush && is_straight && sorted_card_rank[0] == Rank::Ace as u8 {
+        return Ok((HandType::RoyalFlush, sorted_card_rank));
+    } else if is_flush && is_straight {
+        return Ok((HandType::StraightFlush, sorted_card_rank));
+    }
+
+    let mut card_count = [0u8; 13];
+    for card in hand.iter() {
+        card_count[card.rank as usize] += 1;
+    }
+
+    // Rank groups, highest rank first
+    let mut quad_rank: Option<u8> = None;
+    let mut trip_ranks: Vec<u8> = Vec::new();
+    let mut pair_ranks: Vec<u8> = Vec::new();
+    for i in (0..card_count.len()).rev() {
+        match card_count[i] {
+            0 | 1 => {}
+            2 => pair_ranks.push(i as u8),
+            3 => trip_ranks.push(i as u8),
+            _ => {
+                if quad_rank.is_none() {
+                    quad_rank = Some(i as u8);
+                }
+            }
+        }
+    }
+
+    // Highest ranks of the cards left out of the made hand, padded with 0
+    // when fewer than 5 cards were supplied
+    let kickers = |excluded: &[u8], n_kickers: usize| -> Vec<u8> {
+        let mut ranks: Vec<u8> = hand
+            .iter()
+            .map(|card| card.rank as u8)
+            .filter(|rank| !excluded.contains(rank))
+            .take(n_kickers)
+            .collect();
+        ranks.resize(n_kickers, 0);
+        ranks
+    };
+
+    if let Some(quad) = quad_rank {
+        let kicker = kickers(&[quad], 1);
+        return Ok((HandType::FourOfAKind, [quad, quad, quad, quad, kicker[0]]));
+    }
+
+    if is_flush && rules.flush_beats_full_house {
+        return Ok((HandType::Flush, sorted_card_rank));
+    }
+
+    if !trip_ranks.is_empty() && (trip_ranks.len() > 1 || !pair_ranks.is_empty()) {
+        let three_rank = trip_ranks[0];
+        let pair_rank = max(
+            trip_ranks.get(1).copied().unwrap_or(0),
+            pair_ranks.first().copied().unwrap_or(0),
+        );
+        return Ok((
+            HandType::FullHouse,
+            [three_rank, three_rank, three_rank, pair_rank, pair_rank],
+        ));
+    }
+
+    if is_flush {
+        return Ok((HandType::Flush, sorted_card_rank));
+    }
+
+    // Straight check
+    let ranks = hand.iter().map(|card| card.rank as u8).collect();
+    if let Some(straight) = get_straight(&ranks, rules) {
+        sorted_card_rank.copy_from_slice(&straight);
+        return Ok((HandType::Straight, sorted_card_rank));
+    }
+
+    if let Some(&three_rank) = trip_ranks.first() {
+        let kicker = kickers(&[three_rank], 2);
+        return Ok((
+            HandType::ThreeOfAKind,
+            [three_rank, three_rank, three_rank, kicker[0], kicker[1]],
+        ));
+    }
+
+    if pair_ranks.len() >= 2 {
+        let (high, low) = (pair_ranks[0], pair_ranks[1]);
+        let kicker = kickers(&[high, low], 1);
+        return Ok((HandType::TwoPair, [high, high, low, low, kicker[0]]));
+    }
+
+    if let Some(&pair_rank) = pair_ranks.first() {
+        let kicker = kickers(&[pair_rank], 3);
+        return Ok((
+            HandType::Pair,
+            [pair_rank, pair_rank, kicker[0], kicker[1], kicker[2]],
+        ));
+    }
+
+    let kicker = kickers(&[], 5);
+    sorted_card_rank.copy_from_slice(&kicker);
+    Ok((HandType::HighCard, sorted_card_rank))
+}
+
+pub fn evaluate_hand_comb(
+    hand: &[Card],
+    n_cards: usize,
+) -> Result<(HandType, [u8; 5]), &'static str> {
+    combinations(hand, n_cards)
+        .into_iter()
+        .filter_map(|mut five_card_hand| evaluate_hand(&mut five_card_hand).ok())
+        .max_by_key(|(hand_type, ranks)| (*hand_type as u8, ranks.clone()))
+        .ok_or("no valid hand found")
+}
+
+/// Every five card hand an Omaha player may show: exactly two hole cards and
+/// three community cards
+fn omaha_candidates(hand: &[Card]) -> Result<Vec<Vec<Card>>, &'static str> {
+    let hole: Vec<Card> = hand
+        .iter()
+        .filter(|card| card.owner == Owner::Player)
+        .copied()
+        .collect();
+    let board: Vec<Card> = hand
+        .iter()
+        .filter(|card| card.owner == Owner::Community)
+        .copied()
+        .collect();
+    if hole.len() < 2 || board.len() < 3 {
+        return Err("omaha hand needs at least 2 hole cards and 3 community cards");
+    }
+
+    let board_combinations = combinations(&board, 3);
+    Ok(combinations(&hole, 2)
+        .iter()
+        .flat_map(|hole_pair| {
+            board_combinations
+                .iter()
+                .map(move |board_triple| hole_pair.iter().chain(board_triple.iter()).copied().collect())
+        })
+        .collect())
+}
+
+pub fn evaluate_omaha_hand(hand: &[Card]) -> Result<(HandType, [u8; 5]), &'static str> {
+    omaha_candidates(hand)?
+        .into_iter()
+        .filter_map(|mut five_card_hand| evaluate_hand(&mut five_card_hand).ok())
+        .max_by_key(|(hand_type, ranks)| (*hand_type, *ranks))
+        .ok_or("no valid hand found")
+}
+
+pub fn evaluate_best_hand(
+    hand: &[Card],
+    game_type: GameType,
+) -> Result<(HandType, [u8; 5]), &'static str> {
+    match game_type {
+        GameType::TexasHoldemPoker => evaluate_hand_comb(hand, min(hand.len(), 7)),
+        GameType::OmahaPoker => evaluate_omaha_hand(hand),
+    }
+}
+
+/// Ace-to-five low of exactly five cards with the 8-or-better qualifier.
+/// Ranks go from 0 for an ace up to 7 for an eight, highest first, so a
+/// smaller array is a better low. Straights and flushes do not count against
+/// the hand, pairs disqualify it
+pub fn evaluate_low_hand(hand: &[Card]) -> Option<[u8; 5]> {
+    if hand.len() != 5 {
+        return None;
+    }
+    let mut ranks = [0u8; 5];
+    for (low_rank, card) in ranks.iter_mut().zip(hand.iter()) {
+        *low_rank = (card.rank as u8 + 1) % 13;
+    }
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+    let qualifies = ranks[0] <= 7 && ranks.windows(2).all(|pair| pair[0] != pair[1]);
+    qualifies.then_some(ranks)
+}
+
+/// Best qualifying Omaha low using exactly two hole cards and three
+/// community cards, `None` when there is no 8-or-better low
+pub fn evaluate_omaha_low_hand(hand: &[Card]) -> Result<Option<[u8; 5]>, &'static str> {
+    Ok(omaha_candidates(hand)?
+        .iter()
+        .filter_map(|five_card_hand| evaluate_low_hand(five_card_hand))
+        .min())
+}
+
+/// Best qualifying 8-or-better low out of any five cards, as played in
+/// Stud Hi-Lo
+pub fn evaluate_stud_low_hand(hand: &[Card]) -> Option<[u8; 5]> {
+    combinations(hand, 5)
+        .iter()
+        .filter_map(|five_card_hand| evaluate_low_hand(five_card_hand))
+        .min()
+}
+
+/// Ace-to-five low of up to five cards where pairs count against the hand,
+/// as played in Razz. Returns a score, smaller is better, and the low ranks
+/// (ace as 0) with paired cards first
+pub fn razz_low(hand: &[Card]) -> (u32, [u8; 5]) {
+    const POW_BASE: u32 = 13;
+
+    let mut rank_count = [0u8; 13];
+    for card in hand.iter().take(5) {
+        rank_count[(card.rank as usize + 1) % 13] += 1;
+    }
+
+    // Bigger groups first, then higher ranks, so both compare worst first
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .filter(|&rank| rank_count[rank as usize] > 0)
+        .map(|rank| (rank_count[rank as usize], rank))
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut low_ranks = [0u8; 5];
+    let ranks = groups
+        .iter()
+        .flat_map(|&(count, rank)| std::iter::repeat_n(rank, count as usize));
+    for (low_rank, rank) in low_ranks.iter_mut().zip(ranks) {
+        *low_rank = rank;
+    }
+
+    // No pair, pair, two pair, trips, full house, quads
+    let category = match (groups.first(), groups.get(1)) {
+        (Some((4, _)), _) => 5,
+        (Some((3, _)), Some((2, _))) => 4,
+        (Some((3, _)), _) => 3,
+        (Some((2, _)), Some((2, _))) => 2,
+        (Some((2, _)), _) => 1,
+        _ => 0,
+    };
+    let mut score = category;
+    for rank in low_ranks.iter() {
+        score = score * POW_BASE + *rank as u32;
+    }
+    (score, low_ranks)
+}
+
+/// Best Razz low out of any number of cards
+pub fn evaluate_razz_hand(hand: &[Card]) -> Result<(u32, [u8; 5]), &'static str> {
+    if hand.len() <= 5 {
+        return Ok(razz_low(hand));
+    }
+    combinations(hand, 5)
+        .iter()
+        .map(|five_card_hand| razz_low(five_card_hand))
+        .min()
+        .ok_or("no valid hand found")
+}
+
+/// Low hand written highest card first, e.g. `8-6-4-2-A`
+pub fn low_hand_name(low_ranks: &[u8; 5]) -> String {
+    const LOW_RANK_CHARS: &str = "A23456789TJQK";
+
+    low_ranks
+        .iter()
+        .filter_map(|&rank| LOW_RANK_CHARS.chars().nth(rank as usize))
+        .map(String::from)
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// Best deuce-to-seven low out of any number of cards. Returns a score,
+/// smaller is better, and the hand as ranked by `DEUCE_TO_SEVEN_RULES`
+pub fn evaluate_deuce_to_seven_hand(
+    hand: &[Card],
+) -> Result<(u32, HandType, [u8; 5]), &'static str> {
+    combinations(hand, min(hand.len(), 5))
+        .into_iter()
+        .filter_map(|mut five_card_hand| {
+            evaluate_hand_with_rules(&mut five_card_hand, &DEUCE_TO_SEVEN_RULES).ok()
+        })
+        .map(|(hand_type, sorted_ranks)| {
+            (
+                DEUCE_TO_SEVEN_RULES.score(hand_type, &sorted_ranks),
+                hand_type,
+                sorted_ranks,
+            )
+        })
+        .min_by_key(|(score, _, _)| *score)
+        .ok_or("no valid hand found")
+}
+
+/// Single integer ordering of an evaluated hand, higher is better
+pub fn hand_score(hand_type: HandType, sorted_ranks: &[u8; 5]) -> u32 {
+    STANDARD_RULES.score(hand_type, sorted_ranks)
+}
+
+/// Best Short Deck hand out of up to 7 cards
+pub fn evaluate_short_deck_hand(hand: &[Card]) -> Result<(HandType, [u8; 5]), &'static str> {
+    evaluate_hand_with_rules(&mut hand.to_vec(), &SHORT_DECK_RULES)
+}
+
+pub fn compare_hands(
+    hands: Vec<Vec<Card>>,
+    game_type: GameType,
+) -> Result<Vec<usize>, &'static str> {
+    let mut hand_scores: Vec<u32> = Vec::with_capacity(hands.len());
+    for hand in hands.iter() {
+        let (hand_type, sorted_ranks) = evaluate_best_hand(hand, game_type)?;
+        hand_scores.push(hand_score(hand_type, &sorted_ranks));
+    }
+
+    let mut max_score = 0;
+    let mut max_score_count = 0;
+    let mut last_winner_index = 0;
+
+    for index in 0..hand_scores.len() {
+        let score = hand_scores[index];
+        if score < max_score {
+            continue;
+        }
+        if score == max_score {
+            max_score_count += 1;
+        } else {
+            max_score_count = 1;
+            max_score = score;
+            last_winner_index = index;
+        }
+    }
+
+    if max_score_count == 1 {
+        return Ok(vec![last_winner_index]);
+    }
+
+    let mut tied_indexes: Vec<usize> = Vec::with_capacity(max_score_count);
+    for index in 0..hand_scores.len() {
+        if hand_scores[index] != max_score {
+            continue;
+        }
+        tied_indexes.push(index);
+    }
+
+    Ok(tied_indexes)
+}
+
+pub struct BestHand {
+    pub hand_type: HandType,
+    pub sorted_ranks: [u8; 5],
+    /// The cards making up the hand, fewer than five for a partial hand
+    pub cards: Vec<Card>,
+}
+
+/// Like `evaluate_best_hand`, but also reports which cards make the hand.
+/// Partial hands (before the river, or an Omaha hand before the flop) are
+/// evaluated with the cards available so far
+pub fn best_hand(hand: &[Card], game_type: GameType) -> Result<BestHand, &'static str> {
+    let n_board = hand
+        .iter()
+        .filter(|card| card.owner == Owner::Community)
+        .count();
+    let candidates = match game_type {
+        GameType::TexasHoldemPoker if hand.len() <= 5 => vec![hand.to_vec()],
+        GameType::TexasHoldemPoker => combinations(hand, 5),
+        GameType::OmahaPoker if n_board < 3 => {
+            // Still at most two hole cards, plus whatever board there is
+            let (hole, board): (Vec<Card>, Vec<Card>) =
+                hand.iter().partition(|card| card.owner == Owner::Player);
+            combinations(&hole, min(hole.len(), 2))
+                .into_iter()
+                .map(|mut cards| {
+                    cards.extend_from_slice(&board);
+                    cards
+                })
+                .collect()
+        }
+        GameType::OmahaPoker => omaha_candidates(hand)?,
+    };
+
+    candidates
+        .into_iter()
+        .filter_map(|mut cards| {
+            evaluate_hand(&mut cards)
+                .ok()
+                .map(|(hand_type, sorted_ranks)| BestHand {
+                    hand_type,
+                    sorted_ranks,
+                    cards,
+                })
+        })
+        .max_by_key(|best| (best.hand_type, best.sorted_ranks))
+        .ok_or("no valid hand found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    fn cards(text: &str) -> Vec<Card> {
+        parse_cards(text, Owner::Player).unwrap()
+    }
+
+    fn omaha(hole: &str, board: &str) -> Vec<Card> {
+        let mut hand = cards(hole);
+        hand.extend(parse_cards(board, Owner::Community).unwrap());
+        hand
+    }
+
+    fn score(text: &str) -> u32 {
+        let (hand_type, ranks) =
+            evaluate_best_hand(&cards(text), GameType::TexasHoldemPoker).unwrap();
+        hand_score(hand_type, &ranks)
+    }
+
+    #[test]
+    fn categories_rank_in_order() {
+        let hands = [
+            ("Kd9s7c4h2d", HandType::HighCard),
+            ("9d9s7c4h2d", HandType::Pair),
+            ("9d9s7c7h2d", HandType::TwoPair),
+            ("9d9s9c4h2d", HandType::ThreeOfAKind),
+            ("Td9s8c7h6d", HandType::Straight),
+            ("Kd9d7d4d2d", HandType::Flush),
+            ("9d9s9c4h4d", HandType::FullHouse),
+            ("9d9s9c9h2d", HandType::FourOfAKind),
+            ("Td9d8d7d6d", HandType::StraightFlush),
+            ("AdKdQdJdTd", HandType::RoyalFlush),
+        ];
+        for window in hands.windows(2) {
+            assert!(
+                score(window[0].0) < score(window[1].0),
+                "{} vs {}",
+                window[0].0,
+                window[1].0
+            );
+        }
+        for (hand, hand_type) in hands {
+            assert_eq!(
+                evaluate_hand(&mut cards(hand)).unwrap().0,
+                hand_type,
+                "{hand}"
+            );
+        }
+    }
+
+    #[test]
+    fn wheel_is_the_lowest_straight() {
+        let (hand_type, ranks) = evaluate_hand(&mut cards("As2d3c4h5s")).unwrap();
+        assert_eq!(hand_type, HandType::Straight);
+        assert_eq!(ranks, [3, 2, 1, 0, 12]);
+        assert!(score("As2d3c4h5s") < score("2d3c4h5s6s"));
+        assert!(score("As2d3c4h5s") > score("AsAd3c4h5s"));
+
+        let (hand_type, _) = evaluate_hand(&mut cards("As2s3s4s5s")).unwrap();
+        assert_eq!(hand_type, HandType::StraightFlush);
+    }
+
+    #[test]
+    fn kickers_break_ties() {
+        let cases = [
+            ("AsAdKc7h2s", "AhAcQd7c2d"),
+            ("KsKd5c5h9s", "KhKc5d5s8d"),
+            ("7s7d7cAh3s", "7h7d7cKh3c"),
+            ("AsQd9c7h3s", "AhQc9d7c2d"),
+        ];
+        for (better, worse) in cases {
+            assert!(score(better) > score(worse), "{better} vs {worse}");
+        }
+        // Seven cards play the best five, the two lowest kickers do not count
+        assert_eq!(score("AsAdKc7h2s3c4d"), score("AhAcKd7c4s"));
+    }
+
+    #[test]
+    fn omaha_plays_exactly_two_hole_cards() {
+        // One heart in hand cannot complete the four heart board
+        let hand = omaha("AhKcQcJd", "ThTs9h8h2h");
+        assert_eq!(evaluate_omaha_hand(&hand).unwrap().0, HandType::Straight);
+        let holdem = evaluate_best_hand(&hand, GameType::TexasHoldemPoker).unwrap();
+        assert_eq!(holdem.0, HandType::Flush);
+
+        // Four aces in hand only ever play as a pair of them
+        let hand = omaha("AsAhAdAc", "KsKh2c3d4h");
+        assert_eq!(
+            evaluate_omaha_hand(&hand).unwrap(),
+            (HandType::TwoPair, [12, 12, 11, 11, 2])
+        );
+
+        assert!(evaluate_omaha_hand(&omaha("AsAh", "KsKh2c")).is_ok());
+        assert!(evaluate_omaha_hand(&omaha("AsAhKdQc", "KsKh")).is_err());
+    }
+
+    #[test]
+    fn short_deck_ranks_flush_over_full_house() {
+        let short_deck = |text: &str| {
+            let (hand_type, ranks) = evaluate_short_deck_hand(&cards(text)).unwrap();
+            SHORT_DECK_RULES.score(hand_type, &ranks)
+        };
+        assert_eq!(
+            evaluate_short_deck_hand(&cards("Kd9d7d6dJd")).unwrap().0,
+            HandType::Flush
+        );
+        assert!(short_deck("Kd9d7d6dJd") > short_deck("9d9s9c6h6d"));
+        assert!(short_deck("9d9s9c9h6d") > short_deck("Kd9d7d6dJd"));
+
+        // The ace plays below the six
+        let (hand_type, ranks) = evaluate_short_deck_hand(&cards("As6d7c8h9s")).unwrap();
+        assert_eq!((hand_type, ranks), (HandType::Straight, [7, 6, 5, 4, 12]));
+        assert!(short_deck("As6d7c8h9s") < short_deck("6d7c8h9sTs"));
+    }
+
+    #[test]
+    fn razz_plays_the_best_low() {
+        let razz = |text: &str| evaluate_razz_hand(&cards(text)).unwrap().0;
+        let order = [
+            "As2d3c4h5s",
+            "As2d3c4h6s",
+            "As2d3c4hKs",
+            "AsAd3c4h5s",
+            "AsAd3c3h5s",
+        ];
+        for window in order.windows(2) {
+            assert!(
+                razz(window[0]) < razz(window[1]),
+                "{} vs {}",
+                window[0],
+                window[1]
+            );
+        }
+        // Straights and flushes do not count, seven cards play the best five
+        assert_eq!(razz("As2s3s4s5s"), razz("As2d3c4h5s"));
+        assert_eq!(razz("KsKdAs2d3c4h5s"), razz("As2d3c4h5s"));
+        assert_eq!(
+            low_hand_name(&evaluate_razz_hand(&cards("8s6d4cAh2s")).unwrap().1),
+            "8-6-4-2-A"
+        );
+    }
+
+    #[test]
+    fn deuce_to_seven_plays_the_ace_high() {
+        let low = |text: &str| evaluate_deuce_to_seven_hand(&cards(text)).unwrap().0;
+        let order = [
+            "7d5c4h3s2d",
+            "8d6c4h3s2d",
+            "Ks9c7h5s2d",
+            "As2d3c4h5s",
+            "2d2c4h5s7d",
+            "2d3c4h5s6d",
+            "9d7d5d4d2d",
+        ];
+        for window in order.windows(2) {
+            assert!(
+                low(window[0]) < low(window[1]),
+                "{} vs {}",
+                window[0],
+                window[1]
+            );
+        }
+        let (_, hand_type, _) = evaluate_deuce_to_seven_hand(&cards("As2d3c4h5s")).unwrap();
+        assert_eq!(hand_type, HandType::HighCard);
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Most players ICM is computed for, the work doubles with each one
+pub const MAX_ICM_PLAYERS: usize = 20;
+
+/// How the players left in a tournament split the remaining prizes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DealMethod {
+    /// Independent Chip Model equity
+    Icm,
+    /// Everyone locks up the last remaining prize, the rest goes by chip count
+    ChipChop,
+}
+
+impl fmt::Display for DealMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Icm => write!(f, "ICM"),
+            Self::ChipChop => write!(f, "Chip chop"),
+        }
+    }
+}
+
+impl FromStr for DealMethod {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "icm" => Ok(Self::Icm),
+            "chip-chop" | "chip_chop" | "chop" => Ok(Self::ChipChop),
+            _ => Err("unknown deal, expected icm or chip-chop"),
+        }
+    }
+}
+
+fn validate(stacks: &[u32]) -> Result<u64, &'static str> {
+    if stacks.is_empty() {
+        return Err("No stacks given");
+    }
+    let total: u64 = stacks.iter().map(|&stack| stack as u64).sum();
+    if total == 0 {
+        return Err("Stacks hold no chips");
+    }
+    Ok(total)
+}
+
+/// Prize each player can expect under the Malmuth-Harville model, where the
+/// chance of finishing in the best place left is proportional to the stack.
+/// `payouts` holds the prize of each place, first place first
+pub fn icm_equity(stacks: &[u32], payouts: &[u32]) -> Result<Vec<f64>, &'static str> {
+    let total = validate(stacks)?;
+    if stacks.len() > MAX_ICM_PLAYERS {
+        return Err("ICM supports at most 20 players");
+    }
+
+    // Chance that the players of each set took the best places, in any order
+    let n_players = stacks.len();
+    let n_places = payouts.len().min(n_players);
+    let mut chance = vec![0.0; 1 << n_players];
+    chance[0] = 1.0;
+    let mut equity = vec![0.0; n_players];
+    for placed in 0..chance.len() {
+        let place = (placed as u32).count_ones() as usize;
+        if chance[placed] == 0.0 || place >= n_places {
+            continue;
+        }
+        let chips_left = total
+            - (0..n_players)
+                .filter(|&player| placed & (1 << player) != 0)
+                .map(|player| stacks[player] as u64)
+                .sum::<u64>();
+        if chips_left == 0 {
+            continue;
+        }
+        for player in (0..n_players).filter(|&player| placed & (1 << player) == 0) {
+            let finishes_next = chance[placed] * stacks[player] as f64 / chips_left as f64;
+            equity[player] += finishes_next * payouts[place] as f64;
+            chance[placed | (1 << player)] += finishes_next;
+        }
+    }
+    Ok(equity)
+}
+
+/// Every player gets the smallest prize left, the rest of the prize pool is
+/// shared in proportion to the stacks
+pub fn chip_chop(stacks: &[u32], payouts: &[u32]) -> Result<Vec<f64>, &'static str> {
+    let total = validate(stacks)?;
+    let prizes = &payouts[..payouts.len().min(stacks.len())];
+    let locked_up = match prizes.len() == stacks.len() {
+        true => prizes.last().copied().unwrap_or(0),
+        false => 0,
+    } as f64;
+    let rest =
+        prizes.iter().map(|&prize| prize as f64).sum::<f64>() - locked_up * stacks.len() as f64;
+    Ok(stacks
+        .iter()
+        .map(|&stack| locked_up + rest * stack as f64 / total as f64)
+        .collect())
+}
+
+/// Whole prizes of a deal, adding up to the prizes left. Chips lost to
+/// rounding go to the largest remainders
+pub fn deal_prizes(
+    method: DealMethod,
+    stacks: &[u32],
+    payouts: &[u32],
+) -> Result<Vec<u32>, &'static str> {
+    let shares = match method {
+        DealMethod::Icm => icm_equity(stacks, payouts)?,
+        DealMethod::ChipChop => chip_chop(stacks, payouts)?,
+    };
+    let prize_pool: u32 = payouts.iter().take(stacks.len()).sum();
+    let mut prizes: Vec<u32> = shares.iter().map(|share| share.floor() as u32).collect();
+    let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+    by_remainder.sort_by(|&a, &b| {
+        (shares[b] - shares[b].floor()).total_cmp(&(shares[a] - shares[a].floor()))
+    });
+    let leftover = prize_pool.saturating_sub(prizes.iter().sum()) as usize;
+    for &player in by_remainder.iter().cycle().take(leftover) {
+        prizes[player] += 1;
+    }
+    Ok(prizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYOUTS: [u32; 3] = [500, 300, 200];
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected.iter()) {
+            assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn icm_equity_sums_to_the_prize_pool() {
+        let equity = icm_equity(&[5000, 3000, 1500, 500], &PAYOUTS).unwrap();
+        assert!((equity.iter().sum::<f64>() - 1000.0).abs() < 1e-9);
+        assert!(equity.windows(2).all(|pair| pair[0] > pair[1]));
+        // The chip leader cannot expect more than first place
+        assert!(equity[0] < 500.0);
+    }
+
+    #[test]
+    fn equal_stacks_get_equal_shares() {
+        let equity = icm_equity(&[1000; 4], &PAYOUTS).unwrap();
+        assert_close(&equity, &[250.0; 4]);
+    }
+
+    #[test]
+    fn chip_chop_locks_up_the_last_prize() {
+        let shares = chip_chop(&[6000, 3000, 1000], &PAYOUTS).unwrap();
+        assert_close(&shares, &[440.0, 320.0, 240.0]);
+        // Nothing is locked up while more players are left than prizes
+        let shares = chip_chop(&[5000, 3000, 1000, 1000], &PAYOUTS).unwrap();
+        assert_close(&shares, &[500.0, 300.0, 100.0, 100.0]);
+    }
+
+    #[test]
+    fn deal_prizes_spend_every_chip_once() {
+        let stacks = [3333, 3333, 3334];
+        for method in [DealMethod::Icm, DealMethod::ChipChop] {
+            let prizes = deal_prizes(method, &stacks, &[501, 300, 200]).unwrap();
+            assert_eq!(prizes.iter().sum::<u32>(), 1001);
+        }
+        let prizes = deal_prizes(DealMethod::ChipChop, &[1, 1, 1], &[100, 0, 0]).unwrap();
+        assert_eq!(prizes.iter().sum::<u32>(), 100);
+        assert!(prizes.iter().all(|&prize| prize == 33 || prize == 34));
+    }
+}
@@ -0,0 +1,17 @@
+pub mod card;
+pub mod cfr;
+pub mod combinations;
+pub mod engine;
+pub mod equity;
+pub mod game;
+pub mod hand;
+pub mod icm;
+pub mod pot;
+pub mod pushfold;
+pub mod rake;
+pub mod range;
+pub mod rotation;
+pub mod simulate;
+pub mod strategy;
+pub mod tournament;
+pub mod variant;
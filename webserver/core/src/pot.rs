@@ -0,0 +1,285 @@
+use crate::variant::ShowdownHand;
+
+/// Main or side pot and the players who can win it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u32,
+    pub eligible: Vec<usize>,
+}
+
+/// How a pot was split at showdown
+#[derive(Clone, Debug)]
+pub struct PotAward {
+    pub amount: u32,
+    pub high_winners: Vec<usize>,
+    /// Empty when no eligible hand made a qualifying low
+    pub low_winners: Vec<usize>,
+    /// Chips won from this pot, indexed like the players
+    pub prizes: Vec<u32>,
+}
+
+/// Part of the largest bet nobody matched, to be returned to its owner
+pub fn uncalled_bet(bets: &[u32]) -> Option<(usize, u32)> {
+    let (top_idx, &top_bet) = bets.iter().enumerate().max_by_key(|(_, &bet)| bet)?;
+    let second_bet = bets
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _)| idx != top_idx)
+        .map(|(_, &bet)| bet)
+        .max()
+        .unwrap_or(0);
+
+    (top_bet > second_bet).then_some((top_idx, top_bet - second_bet))
+}
+
+/// Splits the total bets into a main pot followed by side pots. Folded
+/// players' chips stay in the pots, but they cannot win them
+pub fn side_pots(bets: &[u32], folded: &[bool]) -> Vec<Pot> {
+    let mut levels: Vec<u32> = bets.iter().copied().filter(|&bet| bet > 0).collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<Pot> = Vec::new();
+    let mut previous_level = 0;
+    for level in levels {
+        let amount: u32 = bets
+            .iter()
+            .map(|&bet| bet.min(level) - bet.min(previous_level))
+            .sum();
+        let eligible: Vec<usize> = (0..bets.len())
+            .filter(|&idx| bets[idx] >= level && !folded[idx])
+            .collect();
+        previous_level = level;
+
+        match pots.last_mut() {
+            // Chips above the last live player's bet, or a level with the
+            // same contenders, belong to the previous pot
+            Some(pot) if eligible.is_empty() || pot.eligible == eligible => {
+                pot.amount += amount;
+            }
+            _ => pots.push(Pot { amount, eligible }),
+        }
+    }
+    pots
+}
+
+/// Splits `amount` evenly, odd chips going one each to the first winners
+/// clockwise from `first_seat`
+fn share(amount: u32, winners: &[usize], first_seat: usize, prizes: &mut [u32]) {
+    if winners.is_empty() {
+        return;
+    }
+    let n_seats = prizes.len();
+    let mut ordered = winners.to_vec();
+    ordered.sort_by_key(|&idx| (idx + n_seats - first_seat % n_seats) % n_seats);
+
+    let base = amount / ordered.len() as u32;
+    let odd_chips = (amount % ordered.len() as u32) as usize;
+    for (position, &idx) in ordered.iter().enumerate() {
+        prizes[idx] += base + (position < odd_chips) as u32;
+    }
+}
+
+/// Awards a pot to the best high hand, or half to the best high and half to
+/// the best qualifying low when there is one. Ties split their half, so a
+/// player can be quartered; the odd chip of a split goes to the high half.
+/// `hands` is indexed like the players, `None` for folded players
+pub fn award_pot(pot: &Pot, hands: &[Option<ShowdownHand>], first_seat: usize) -> PotAward {
+    let contenders: Vec<(usize, &ShowdownHand)> = pot
+        .eligible
+        .iter()
+        .filter_map(|&idx| hands[idx].as_ref().map(|hand| (idx, hand)))
+        .collect();
+
+    let best_high = contenders.iter().map(|(_, hand)| hand.high).max();
+    let high_winners: Vec<usize> = contenders
+        .iter()
+        .filter(|(_, hand)| Some(hand.high) == best_high)
+        .map(|&(idx, _)| idx)
+        .collect();
+
+    let best_low = contenders.iter().filter_map(|(_, hand)| hand.low).min();
+    let low_winners: Vec<usize> = match best_low {
+        Some(best_low) => contenders
+            .iter()
+            .filter(|(_, hand)| hand.low == Some(best_low))
+            .map(|&(idx, _)| idx)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let low_amount = if low_winners.is_empty() {
+        0
+    } else {
+        pot.amount / 2
+    };
+    let mut prizes = vec![0; hands.len()];
+    share(
+        pot.amount - low_amount,
+        &high_winners,
+        first_seat,
+        &mut prizes,
+    );
+    share(low_amount, &low_winners, first_seat, &mut prizes);
+
+    PotAward {
+        amount: pot.amount,
+        high_winners,
+        low_winners,
+        prizes,
+    }
+}
+
+/// Chips each player could expect from the pots given the equity of the
+/// hands when they were all in. `equities` holds the share of a pot each
+/// player eligible to win it had, keyed by those players; pots without
+/// one are split between their contenders
+pub fn all_in_ev(n_players: usize, pots: &[Pot], equities: &[(Vec<usize>, Vec<f64>)]) -> Vec<f64> {
+    let mut ev = vec![0.0; n_players];
+    for pot in pots.iter() {
+        match equities
+            .iter()
+            .find(|(eligible, _)| *eligible == pot.eligible)
+        {
+            Some((_, shares)) => {
+                for (&idx, share) in pot.eligible.iter().zip(shares.iter()) {
+                    ev[idx] += pot.amount as f64 * share;
+                }
+            }
+            // Uncontested pot
+            None => {
+                for &idx in pot.eligible.iter() {
+                    ev[idx] += pot.amount as f64 / pot.eligible.len() as f64;
+                }
+            }
+        }
+    }
+    ev
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{parse_cards, Card, Owner};
+    use crate::equity::runout_equity;
+    use crate::hand::HandType;
+    use crate::variant::TexasHoldem;
+
+    fn hand(high: u32, low: Option<[u8; 5]>) -> Option<ShowdownHand> {
+        Some(ShowdownHand {
+            hand_type: HandType::HighCard,
+            name: String::new(),
+            high,
+            low,
+        })
+    }
+
+    fn pot(amount: u32, eligible: &[usize]) -> Pot {
+        Pot {
+            amount,
+            eligible: eligible.to_vec(),
+        }
+    }
+
+    const WHEEL: [u8; 5] = [4, 3, 2, 1, 0];
+    const EIGHT_LOW: [u8; 5] = [7, 5, 3, 1, 0];
+
+    #[test]
+    fn high_takes_the_pot_without_a_qualifying_low() {
+        let hands = [hand(10, None), hand(20, None)];
+        let award = award_pot(&pot(100, &[0, 1]), &hands, 0);
+        assert_eq!(award.prizes, vec![0, 100]);
+        assert!(award.low_winners.is_empty());
+    }
+
+    #[test]
+    fn tied_low_is_quartered() {
+        let hands = [
+            hand(30, Some(WHEEL)),
+            hand(10, Some(WHEEL)),
+            hand(20, Some(EIGHT_LOW)),
+        ];
+        let award = award_pot(&pot(120, &[0, 1, 2]), &hands, 0);
+        assert_eq!(award.high_winners, vec![0]);
+        assert_eq!(award.low_winners, vec![0, 1]);
+        assert_eq!(award.prizes, vec![90, 30, 0]);
+    }
+
+    #[test]
+    fn odd_chips_go_high_then_clockwise() {
+        let hands = [hand(20, None), hand(10, Some(WHEEL))];
+        assert_eq!(
+            award_pot(&pot(101, &[0, 1]), &hands, 0).prizes,
+            vec![51, 50]
+        );
+
+        let hands = [None, hand(20, None), None, hand(20, None)];
+        assert_eq!(
+            award_pot(&pot(101, &[1, 3]), &hands, 2).prizes,
+            vec![0, 50, 0, 51]
+        );
+        assert_eq!(
+            award_pot(&pot(101, &[1, 3]), &hands, 0).prizes,
+            vec![0, 51, 0, 50]
+        );
+    }
+
+    #[test]
+    fn folded_chips_stay_in_pots_they_cannot_win() {
+        let pots = side_pots(&[50, 100, 100], &[false, true, false]);
+        assert_eq!(pots, vec![pot(150, &[0, 2]), pot(100, &[2])]);
+
+        // The folded player's hand would have won
+        let hands = [hand(20, None), None, hand(10, None)];
+        let prizes: Vec<Vec<u32>> = pots
+            .iter()
+            .map(|pot| award_pot(pot, &hands, 0).prizes)
+            .collect();
+        assert_eq!(prizes, vec![vec![150, 0, 0], vec![0, 0, 100]]);
+    }
+
+    #[test]
+    fn all_in_ev_pays_each_pot_by_the_equity_at_the_all_in() {
+        // The short stack is all in on the flop against two bigger stacks
+        let hands: Vec<Vec<Card>> = ["AsAh", "KsKh", "7c8c"]
+            .iter()
+            .map(|hand| parse_cards(hand, Owner::Player).unwrap())
+            .collect();
+        let board = parse_cards("Kd9c2c", Owner::Community).unwrap();
+        let pots = side_pots(&[100, 300, 300], &[false; 3]);
+        assert_eq!(pots, vec![pot(300, &[0, 1, 2]), pot(400, &[1, 2])]);
+
+        let equities: Vec<(Vec<usize>, Vec<f64>)> = pots
+            .iter()
+            .map(|pot| {
+                let contenders: Vec<Vec<Card>> =
+                    pot.eligible.iter().map(|&idx| hands[idx].clone()).collect();
+                let dead_cards: Vec<Card> = (0..hands.len())
+                    .filter(|idx| !pot.eligible.contains(idx))
+                    .flat_map(|idx| hands[idx].clone())
+                    .collect();
+                let equity =
+                    runout_equity(&TexasHoldem, &contenders, &board, &dead_cards, 10_000, 0)
+                        .unwrap();
+                let shares = equity.players.iter().map(|player| player.equity).collect();
+                (pot.eligible.clone(), shares)
+            })
+            .collect();
+        let ev = all_in_ev(3, &pots, &equities);
+
+        let (main, side) = (&equities[0].1, &equities[1].1);
+        assert!((ev[0] - 300.0 * main[0]).abs() < 1e-9);
+        assert!((ev[1] - 300.0 * main[1] - 400.0 * side[0]).abs() < 1e-9);
+        assert!((ev[2] - 300.0 * main[2] - 400.0 * side[1]).abs() < 1e-9);
+        assert!((ev.iter().sum::<f64>() - 700.0).abs() < 1e-9);
+        // Kings made a set, aces are drawing to the two outs left
+        assert!(side[0] > 0.5 && main[0] < 0.1);
+    }
+
+    #[test]
+    fn all_in_ev_splits_uncontested_pots() {
+        let pots = vec![pot(200, &[0, 1]), pot(50, &[1])];
+        let equities = vec![(vec![0, 1], vec![0.25, 0.75])];
+        assert_eq!(all_in_ev(2, &pots, &equities), vec![50.0, 200.0]);
+    }
+}
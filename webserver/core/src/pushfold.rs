@@ -0,0 +1,524 @@
+use crate::card::{Card, Owner, Rank, CARD_RANKS, CARD_SUITS, DECK};
+use crate::equity::{score_hands, worker_threads};
+use crate::game::GameType;
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::SeedableRng;
+use serde_json::{json, Map, Value};
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+use std::thread;
+
+/// Rows and columns of a starting hand chart
+pub const CHART_SIZE: usize = 13;
+/// Hold'em starting hand classes, one per chart cell
+pub const N_CLASSES: usize = CHART_SIZE * CHART_SIZE;
+/// Samples per class matchup of the equities bots solve with
+const BOT_EQUITY_SAMPLES: u64 = 200;
+/// Fictitious play rounds when none are given
+pub const DEFAULT_ITERATIONS: usize = 2_000;
+
+/// Hold'em starting hand class, e.g. AKs. Aces come first in both row and
+/// column: pairs sit on the diagonal, suited hands above it and offsuit
+/// hands below
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HandClass {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl HandClass {
+    pub fn from_index(index: usize) -> Self {
+        Self {
+            row: index / CHART_SIZE,
+            col: index % CHART_SIZE,
+        }
+    }
+
+    /// Class of two hole cards
+    pub fn of(cards: &[Card; 2]) -> Self {
+        let (high, low) = match cards[0].rank >= cards[1].rank {
+            true => (cards[0].rank, cards[1].rank),
+            false => (cards[1].rank, cards[0].rank),
+        };
+        let (high, low) = (rank_line(high), rank_line(low));
+        match cards[0].suit == cards[1].suit {
+            true => Self {
+                row: high,
+                col: low,
+            },
+            false => Self {
+                row: low,
+                col: high,
+            },
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.row * CHART_SIZE + self.col
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.row == self.col
+    }
+
+    pub fn is_suited(&self) -> bool {
+        self.row < self.col
+    }
+
+    fn ranks(&self) -> (Rank, Rank) {
+        let high = self.row.min(self.col);
+        let low = self.row.max(self.col);
+        (
+            CARD_RANKS[CHART_SIZE - 1 - high],
+            CARD_RANKS[CHART_SIZE - 1 - low],
+        )
+    }
+
+    /// Every two-card hand of the class: 6 for pairs, 4 suited, 12 offsuit
+    pub fn combos(&self) -> Vec<[Card; 2]> {
+        let (high, low) = self.ranks();
+        let mut combos = Vec::new();
+        for (i, &high_suit) in CARD_SUITS.iter().enumerate() {
+            for (j, &low_suit) in CARD_SUITS.iter().enumerate() {
+                let keep = match (self.is_pair(), self.is_suited()) {
+                    (true, _) => i < j,
+                    (false, true) => i == j,
+                    (false, false) => i != j,
+                };
+                if keep {
+                    combos.push([
+                        Card {
+                            rank: high,
+                            suit: high_suit,
+                            owner: Owner::Player,
+                        },
+                        Card {
+                            rank: low,
+                            suit: low_suit,
+                            owner: Owner::Player,
+                        },
+                    ]);
+                }
+            }
+        }
+        combos
+    }
+
+    pub fn n_combos(&self) -> usize {
+        match (self.is_pair(), self.is_suited()) {
+            (true, _) => 6,
+            (false, true) => 4,
+            (false, false) => 12,
+        }
+    }
+}
+
+impl Display for HandClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (high, low) = self.ranks();
+        let suffix = match (self.is_pair(), self.is_suited()) {
+            (true, _) => "",
+            (false, true) => "s",
+            (false, false) => "o",
+        };
+        write!(f, "{}{}{suffix}", char::from(high), char::from(low))
+    }
+}
+
+/// Chart line of a rank, aces on the first one
+fn rank_line(rank: Rank) -> usize {
+    CHART_SIZE - 1 - rank as usize
+}
+
+/// Preflop all in equity of every hand class against every other. Card
+/// removal is accounted for: matchups weigh as many as the pairs of combos
+/// that share no card
+pub struct PreflopEquities {
+    equity: Vec<f64>,
+    weight: Vec<f64>,
+}
+
+impl PreflopEquities {
+    /// Share of the pot `hero` wins against `villain`
+    pub fn equity(&self, hero: HandClass, villain: HandClass) -> f64 {
+        self.equity[hero.index() * N_CLASSES + villain.index()]
+    }
+
+    /// Pairs of combos of the two classes that can be dealt together
+    pub fn weight(&self, hero: HandClass, villain: HandClass) -> f64 {
+        self.weight[hero.index() * N_CLASSES + villain.index()]
+    }
+}
+
+/// Estimates the equity of every class matchup from `samples` boards each,
+/// run across `threads` workers (0 for one per core). Each matchup owns a
+/// seeded generator so the result does not depend on the thread count
+pub fn preflop_equities(
+    samples: u64,
+    seed: u64,
+    threads: usize,
+) -> Result<PreflopEquities, &'static str> {
+    if samples == 0 {
+        return Err("preflop equities need at least one sample");
+    }
+    let matchups: Vec<(usize, usize)> = (0..N_CLASSES)
+        .flat_map(|hero| (hero..N_CLASSES).map(move |villain| (hero, villain)))
+        .collect();
+    let threads = worker_threads(threads).min(matchups.len());
+    let work = |worker: usize| -> Vec<(usize, usize, f64, f64)> {
+        matchups
+            .iter()
+            .enumerate()
+            .skip(worker)
+            .step_by(threads)
+            .map(|(n, &(hero, villain))| {
+                let rng = StdRng::seed_from_u64(seed.wrapping_add(n as u64));
+                let (equity, weight) = matchup_equity(hero, villain, samples, rng);
+                (hero, villain, equity, weight)
+            })
+            .collect()
+    };
+    let results: Vec<_> = match threads {
+        0 | 1 => work(0),
+        _ => thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let work = &work;
+                    scope.spawn(move || work(worker))
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("equity worker panicked"))
+                .collect()
+        }),
+    };
+
+    let mut equities = PreflopEquities {
+        equity: vec![0.0; N_CLASSES * N_CLASSES],
+        weight: vec![0.0; N_CLASSES * N_CLASSES],
+    };
+    for (hero, villain, equity, weight) in results {
+        equities.equity[hero * N_CLASSES + villain] = equity;
+        equities.equity[villain * N_CLASSES + hero] = 1.0 - equity;
+        equities.weight[hero * N_CLASSES + villain] = weight;
+        equities.weight[villain * N_CLASSES + hero] = weight;
+    }
+    Ok(equities)
+}
+
+/// Equity of `hero` against `villain` and the pairs of combos they can be
+/// dealt as
+fn matchup_equity(hero: usize, villain: usize, samples: u64, mut rng: StdRng) -> (f64, f64) {
+    let villain_combos = HandClass::from_index(villain).combos();
+    let deals: Vec<([Card; 2], [Card; 2])> = HandClass::from_index(hero)
+        .combos()
+        .into_iter()
+        .flat_map(|hero| {
+            villain_combos
+                .iter()
+                .filter(move |villain| villain.iter().all(|card| !hero.contains(card)))
+                .map(move |&villain| (hero, villain))
+        })
+        .collect();
+    if deals.is_empty() {
+        return (0.5, 0.0);
+    }
+
+    let mut won = 0.0;
+    for _ in 0..samples {
+        let (hero, villain) = deals.choose(&mut rng).expect("deals are not empty");
+        let mut stub: Vec<Card> = DECK
+            .iter()
+            .filter(|card| !hero.contains(card) && !villain.contains(card))
+            .copied()
+            .collect();
+        let (board, _) = stub.partial_shuffle(&mut rng, 5);
+        let board: Vec<Card> = board
+            .iter()
+            .map(|&card| Card {
+                owner: Owner::Community,
+                ..card
+            })
+            .collect();
+        let scores = score_hands(
+            GameType::TexasHoldemPoker,
+            &[hero.to_vec(), villain.to_vec()],
+            &board,
+        );
+        won += match scores[0].cmp(&scores[1]) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        };
+    }
+    (won / samples as f64, deals.len() as f64)
+}
+
+/// Equities bots solve charts with, estimated once per process
+pub fn bot_equities() -> &'static PreflopEquities {
+    static EQUITIES: OnceLock<PreflopEquities> = OnceLock::new();
+    EQUITIES.get_or_init(|| {
+        preflop_equities(BOT_EQUITY_SAMPLES, 0, 0).expect("bot equities take samples")
+    })
+}
+
+/// Heads-up spot where the small blind shoves or folds and the big blind
+/// calls or folds
+pub struct PushFoldConfig {
+    /// Effective stack in big blinds, blinds and antes included
+    pub stack: f64,
+    /// Ante each player posts, in big blinds
+    pub ante: f64,
+    /// Fictitious play rounds
+    pub iterations: usize,
+}
+
+/// Equilibrium push/fold ranges. Frequencies are indexed by class
+#[derive(Clone, Debug)]
+pub struct PushFoldChart {
+    pub stack: f64,
+    pub ante: f64,
+    /// How often the small blind shoves each class
+    pub push: Vec<f64>,
+    /// How often the big blind calls the shove with each class
+    pub call: Vec<f64>,
+    /// Small blind's expected result, in big blinds
+    pub sb_ev: f64,
+}
+
+impl PushFoldChart {
+    pub fn push_frequency(&self, class: HandClass) -> f64 {
+        self.push[class.index()]
+    }
+
+    pub fn call_frequency(&self, class: HandClass) -> f64 {
+        self.call[class.index()]
+    }
+
+    /// Share of the 1326 starting hands the small blind shoves
+    pub fn push_share(&self) -> f64 {
+        range_share(&self.push)
+    }
+
+    /// Share of the 1326 starting hands the big blind calls with
+    pub fn call_share(&self) -> f64 {
+        range_share(&self.call)
+    }
+
+    /// Both ranges as 13x13 grids, classes played at least half the time
+    /// written out and the others dotted
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Push/fold at {} bb effective, ante {} bb (small blind EV {:+.3} bb)\n",
+            self.stack, self.ante, self.sb_ev
+        );
+        for (title, range, share) in [
+            ("Small blind shoves", &self.push, self.push_share()),
+            ("Big blind calls", &self.call, self.call_share()),
+        ] {
+            text += &format!("\n{title} {:.1}%\n", share * 100.0);
+            for row in 0..CHART_SIZE {
+                let line: Vec<String> = (0..CHART_SIZE)
+                    .map(|col| {
+                        let class = HandClass { row, col };
+                        match range[class.index()] >= 0.5 {
+                            true => format!("{:<4}", class.to_string()),
+                            false => format!("{:<4}", "."),
+                        }
+                    })
+                    .collect();
+                text += line.concat().trim_end();
+                text += "\n";
+            }
+        }
+        text
+    }
+
+    /// Both ranges keyed by class name, with the spot they were solved for
+    pub fn to_json(&self) -> Value {
+        let range = |frequencies: &[f64]| -> Map<String, Value> {
+            frequencies
+                .iter()
+                .enumerate()
+                .map(|(index, &frequency)| {
+                    (HandClass::from_index(index).to_string(), json!(frequency))
+                })
+                .collect()
+        };
+        json!({
+            "stack": self.stack,
+            "ante": self.ante,
+            "sb_ev": self.sb_ev,
+            "push_share": self.push_share(),
+            "call_share": self.call_share(),
+            "push": range(&self.push),
+            "call": range(&self.call),
+        })
+    }
+}
+
+fn range_share(frequencies: &[f64]) -> f64 {
+    let combos: f64 = frequencies
+        .iter()
+        .enumerate()
+        .map(|(index, frequency)| frequency * HandClass::from_index(index).n_combos() as f64)
+        .sum();
+    combos / 1326.0
+}
+
+/// Solves the heads-up push/fold game by fictitious play: each round both
+/// players best respond to the other's average strategy, and the averages
+/// converge to an equilibrium
+pub fn solve_push_fold(
+    equities: &PreflopEquities,
+    config: &PushFoldConfig,
+) -> Result<PushFoldChart, &'static str> {
+    if config.ante < 0.0 {
+        return Err("ante cannot be negative");
+    }
+    if config.stack <= 1.0 + config.ante {
+        return Err("stack must cover the big blind and the ante");
+    }
+    if config.iterations == 0 {
+        return Err("solver needs at least one iteration");
+    }
+
+    let stack = config.stack;
+    let ante = config.ante;
+    // Results in big blinds against the stack each player started with
+    let showdown = |equity: f64| equity * 2.0 * stack - stack;
+    let sb_fold = -0.5 - ante;
+    let bb_fold = -1.0 - ante;
+    let classes: Vec<HandClass> = (0..N_CLASSES).map(HandClass::from_index).collect();
+
+    let push_ev = |call: &[f64], hero: HandClass| -> f64 {
+        let (mut ev, mut weight) = (0.0, 0.0);
+        for &villain in classes.iter() {
+            let w = equities.weight(hero, villain);
+            let called = call[villain.index()];
+            let showdown = showdown(equities.equity(hero, villain));
+            ev += w * (called * showdown + (1.0 - called) * -bb_fold);
+            weight += w;
+        }
+        ev / weight
+    };
+    // None when the small blind never shoves anything the hand can face
+    let call_ev = |push: &[f64], hero: HandClass| -> Option<f64> {
+        let (mut ev, mut weight) = (0.0, 0.0);
+        for &villain in classes.iter() {
+            let w = equities.weight(hero, villain) * push[villain.index()];
+            ev += w * showdown(equities.equity(hero, villain));
+            weight += w;
+        }
+        (weight > 0.0).then(|| ev / weight)
+    };
+
+    let mut push = vec![1.0; N_CLASSES];
+    let mut call = vec![0.0; N_CLASSES];
+    for round in 1..=config.iterations {
+        let push_response: Vec<f64> = classes
+            .iter()
+            .map(|&class| f64::from(push_ev(&call, class) > sb_fold))
+            .collect();
+        let call_response: Vec<f64> = classes
+            .iter()
+            .map(|&class| f64::from(call_ev(&push, class).is_some_and(|ev| ev > bb_fold)))
+            .collect();
+        let step = 1.0 / round as f64;
+        for index in 0..N_CLASSES {
+            push[index] += (push_response[index] - push[index]) * step;
+            call[index] += (call_response[index] - call[index]) * step;
+        }
+    }
+
+    let total: f64 = classes.iter().map(|class| class.n_combos() as f64).sum();
+    let sb_ev = classes
+        .iter()
+        .map(|&class| {
+            let ev =
+                push[class.index()] * push_ev(&call, class) + (1.0 - push[class.index()]) * sb_fold;
+            ev * class.n_combos() as f64
+        })
+        .sum::<f64>()
+        / total;
+    Ok(PushFoldChart {
+        stack,
+        ante,
+        push,
+        call,
+        sb_ev,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    /// Rough equities, enough to tell the ends of the ranges apart
+    fn equities() -> &'static PreflopEquities {
+        static EQUITIES: OnceLock<PreflopEquities> = OnceLock::new();
+        EQUITIES.get_or_init(|| preflop_equities(20, 1, 0).unwrap())
+    }
+
+    fn chart(stack: f64, ante: f64) -> PushFoldChart {
+        let config = PushFoldConfig {
+            stack,
+            ante,
+            iterations: 300,
+        };
+        solve_push_fold(equities(), &config).unwrap()
+    }
+
+    fn class(cards: &str) -> HandClass {
+        let cards = parse_cards(cards, Owner::Player).unwrap();
+        HandClass::of(&[cards[0], cards[1]])
+    }
+
+    #[test]
+    fn aces_go_all_in_and_seven_deuce_folds_at_ten_big_blinds() {
+        let chart = chart(10.0, 0.0);
+        let (aces, trash) = (class("AsAh"), class("7c2d"));
+        assert_eq!(aces.to_string(), "AA");
+        assert_eq!(trash.to_string(), "72o");
+        assert!(chart.push_frequency(aces) > 0.95);
+        assert!(chart.call_frequency(aces) > 0.95);
+        assert!(chart.push_frequency(trash) < 0.05);
+        assert!(chart.call_frequency(trash) < 0.05);
+    }
+
+    #[test]
+    fn antes_widen_both_ranges() {
+        let without = chart(10.0, 0.0);
+        let with = chart(10.0, 0.125);
+        assert!(with.push_share() > without.push_share());
+        assert!(with.call_share() > without.call_share());
+    }
+
+    #[test]
+    fn charts_list_every_class() {
+        let chart = chart(10.0, 0.0);
+        let json = chart.to_json();
+        assert_eq!(json["stack"], 10.0);
+        assert_eq!(json["push"].as_object().unwrap().len(), N_CLASSES);
+        assert_eq!(json["call"].as_object().unwrap().len(), N_CLASSES);
+        assert_eq!(json["push"]["AA"], chart.push_frequency(class("AsAh")));
+
+        // A title line, then a title and a grid for each range
+        let text = chart.to_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1 + 2 * (2 + CHART_SIZE));
+        assert!(lines[0].starts_with("Push/fold at 10 bb effective"));
+        assert!(lines[2].starts_with("Small blind shoves"));
+        assert!(lines[3].starts_with("AA  AKs"));
+        assert!(lines[17].starts_with("Big blind calls"));
+        for grid in [&lines[3..16], &lines[18..31]] {
+            for line in grid.iter() {
+                assert_eq!(line.split_whitespace().count(), CHART_SIZE);
+            }
+        }
+        assert!(lines[30].ends_with("22"));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::pot::Pot;
+
+/// House rake of a cash game
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rake {
+    /// Share of each pot taken, in percent
+    pub percentage: f64,
+    /// Most taken from a hand as `(players, cap)` pairs, each cap applying
+    /// to hands dealt to at least that many players. Uncapped when no pair
+    /// applies
+    pub caps: Vec<(usize, u32)>,
+    /// Hands that end in their first betting round are not raked
+    pub no_flop_no_drop: bool,
+}
+
+impl Rake {
+    /// Most taken from a hand dealt to `n_players`, none when uncapped
+    pub fn cap(&self, n_players: usize) -> Option<u32> {
+        self.caps
+            .iter()
+            .filter(|&&(players, _)| players <= n_players)
+            .max_by_key(|&&(players, _)| players)
+            .map(|&(_, cap)| cap)
+    }
+
+    /// Takes the rake out of every pot, main pot first, until the cap of the
+    /// hand is reached. Returns the rake taken from each pot
+    pub fn take(&self, pots: &mut [Pot], n_players: usize, saw_flop: bool) -> Vec<u32> {
+        let mut left = match self.no_flop_no_drop && !saw_flop {
+            true => 0,
+            false => self.cap(n_players).unwrap_or(u32::MAX),
+        };
+        pots.iter_mut()
+            .map(|pot| {
+                let rake = ((pot.amount as f64 * self.percentage / 100.0) as u32).min(left);
+                pot.amount -= rake;
+                left -= rake;
+                rake
+            })
+            .collect()
+    }
+}
+
+/// Splits the rake of a hand between the players in proportion to the chips
+/// they put in, indexed like `bets`. Chips lost to rounding go to the
+/// largest remainders
+pub fn attribute_rake(rake: u32, bets: &[u32]) -> Vec<u32> {
+    let total: u64 = bets.iter().map(|&bet| bet as u64).sum();
+    if total == 0 {
+        return vec![0; bets.len()];
+    }
+    let shares: Vec<u64> = bets.iter().map(|&bet| rake as u64 * bet as u64).collect();
+    let mut attributed: Vec<u32> = shares.iter().map(|share| (share / total) as u32).collect();
+    let mut by_remainder: Vec<usize> = (0..bets.len()).collect();
+    by_remainder.sort_by_key(|&idx| std::cmp::Reverse(shares[idx] % total));
+    let leftover = rake.saturating_sub(attributed.iter().sum()) as usize;
+    for &idx in by_remainder.iter().take(leftover) {
+        attributed[idx] += 1;
+    }
+    attributed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pot::{side_pots, uncalled_bet};
+
+    fn pot(amount: u32) -> Pot {
+        Pot {
+            amount,
+            eligible: vec![0, 1],
+        }
+    }
+
+    #[test]
+    fn takes_the_percentage_rounded_down() {
+        let rake = Rake {
+            percentage: 5.0,
+            caps: Vec::new(),
+            no_flop_no_drop: false,
+        };
+        let mut pots = vec![pot(219), pot(19)];
+        assert_eq!(rake.take(&mut pots, 2, true), vec![10, 0]);
+        assert_eq!(pots, vec![pot(209), pot(19)]);
+    }
+
+    #[test]
+    fn the_cap_depends_on_the_players_dealt() {
+        let rake = Rake {
+            percentage: 10.0,
+            caps: vec![(2, 10), (5, 30)],
+            no_flop_no_drop: false,
+        };
+        assert_eq!(rake.cap(1), None);
+        assert_eq!(rake.cap(4), Some(10));
+        assert_eq!(rake.cap(9), Some(30));
+
+        let mut pots = vec![pot(200), pot(200)];
+        assert_eq!(rake.take(&mut pots, 3, true), vec![10, 0]);
+        let mut pots = vec![pot(200), pot(200)];
+        assert_eq!(rake.take(&mut pots, 6, true), vec![20, 10]);
+    }
+
+    #[test]
+    fn no_flop_no_drop() {
+        let rake = Rake {
+            percentage: 5.0,
+            caps: Vec::new(),
+            no_flop_no_drop: true,
+        };
+        let mut pots = vec![pot(300)];
+        assert_eq!(rake.take(&mut pots, 2, false), vec![0]);
+        assert_eq!(rake.take(&mut pots, 2, true), vec![15]);
+    }
+
+    #[test]
+    fn attribution_adds_up_to_the_rake() {
+        let rake = Rake {
+            percentage: 5.0,
+            caps: Vec::new(),
+            no_flop_no_drop: false,
+        };
+        // The last player folded after posting a dead ante
+        let mut bets = vec![100, 400, 250, 0];
+        let dead_money = 15;
+        let (idx, amount) = uncalled_bet(&bets).unwrap();
+        bets[idx] -= amount;
+        let mut pots = side_pots(&bets, &[false, false, false, true]);
+        pots[0].amount += dead_money;
+        let taken: u32 = rake.take(&mut pots, 4, true).iter().sum();
+        let left: u32 = pots.iter().map(|pot| pot.amount).sum();
+        assert_eq!(taken, 30);
+        assert_eq!(taken + left, 615);
+
+        let mut contributions = bets.clone();
+        contributions[3] += dead_money;
+        let attribution = attribute_rake(taken, &contributions);
+        assert_eq!(attribution, vec![5, 12, 12, 1]);
+        assert_eq!(attribution.iter().sum::<u32>(), taken);
+    }
+}
@@ -0,0 +1,543 @@
+use crate::card::{parse_cards, Card, Owner, Rank, CARD_RANKS, CARD_SUITS, DECK};
+use crate::combinations::{combinations, n_choose_k};
+use crate::equity::{
+    calculate_equity, score_hands, tally_batches, tally_parallel, with_owner, worker_threads,
+    EquityConfig, EquityMethod, EquityResult,
+};
+use crate::game::GameType;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+const BOARD_SIZE: usize = 5;
+// Rejection sampling gives up after this many clashing deals in a row
+const MAX_SAMPLE_ATTEMPTS: usize = 1_000;
+
+/// A specific pair of Hold'em hole cards with its weight in a range
+#[derive(Debug, Clone, Copy)]
+pub struct Combo {
+    pub cards: [Card; 2],
+    pub weight: f64,
+}
+
+impl Combo {
+    fn conflicts_with(&self, used: u64) -> bool {
+        self.mask() & used != 0
+    }
+
+    fn mask(&self) -> u64 {
+        (1 << self.cards[0].index()) | (1 << self.cards[1].index())
+    }
+}
+
+impl Display for Combo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.cards[0].notation(), self.cards[1].notation())
+    }
+}
+
+/// Weighted set of Hold'em hole card combos
+#[derive(Debug, Clone, Default)]
+pub struct HandRange {
+    pub combos: Vec<Combo>,
+}
+
+#[derive(PartialEq)]
+enum Suitedness {
+    Suited,
+    Offsuit,
+    Any,
+}
+
+impl HandRange {
+    /// Parses range notation such as "QQ+, AKs, A5s-A2s, KQo, AhKh:0.5".
+    /// A later entry overrides the weight of a combo listed earlier
+    pub fn parse(notation: &str) -> Result<Self, &'static str> {
+        let mut range = HandRange::default();
+        let mut positions: HashMap<u64, usize> = HashMap::new();
+
+        for token in notation.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let (hand, weight) = match token.split_once(':') {
+                Some((hand, weight)) => (
+                    hand.trim(),
+                    weight
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| "invalid range weight")?,
+                ),
+                None => (token, 1.0),
+            };
+            if !(0.0..=1.0).contains(&weight) {
+                return Err("range weight must be between 0 and 1");
+            }
+
+            for cards in parse_range_token(hand)? {
+                let combo = Combo { cards, weight };
+                match positions.get(&combo.mask()) {
+                    Some(&position) => range.combos[position].weight = weight,
+                    None => {
+                        positions.insert(combo.mask(), range.combos.len());
+                        range.combos.push(combo);
+                    }
+                }
+            }
+        }
+
+        range.combos.retain(|combo| combo.weight > 0.0);
+        Ok(range)
+    }
+
+    /// Range holding a single known hand
+    pub fn from_cards(cards: &[Card]) -> Result<Self, &'static str> {
+        if cards.len() != 2 || cards[0].index() == cards[1].index() {
+            return Err("a hand needs two distinct hole cards");
+        }
+        Ok(Self {
+            combos: vec![Combo {
+                cards: [cards[0], cards[1]],
+                weight: 1.0,
+            }],
+        })
+    }
+
+    /// Every possible pair of hole cards
+    pub fn any_two() -> Self {
+        Self {
+            combos: combinations(&DECK, 2)
+                .into_iter()
+                .map(|cards| Combo {
+                    cards: [
+                        Card { owner: Owner::Player, ..cards[0] },
+                        Card { owner: Owner::Player, ..cards[1] },
+                    ],
+                    weight: 1.0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Card removal: drops every combo holding one of `cards`
+    pub fn without_cards(&self, cards: &[Card]) -> Self {
+        let used = card_mask(cards);
+        Self {
+            combos: self
+                .combos
+                .iter()
+                .filter(|combo| !combo.conflicts_with(used))
+                .copied()
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.combos.iter().map(|combo| combo.weight).sum()
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> &Combo {
+        let mut target = rng.random::<f64>() * self.total_weight();
+        for combo in self.combos.iter() {
+            if target < combo.weight {
+                return combo;
+            }
+            target -= combo.weight;
+        }
+        &self.combos[self.combos.len() - 1]
+    }
+}
+
+fn card_mask(cards: &[Card]) -> u64 {
+    cards.iter().fold(0, |mask, card| mask | (1 << card.index()))
+}
+
+fn parse_range_token(token: &str) -> Result<Vec<[Card; 2]>, &'static str> {
+    let chars: Vec<char> = token.chars().collect();
+
+    // Explicit combo, e.g. AhKh
+    if chars.len() == 4 && !chars.contains(&'-') && !chars.contains(&'+') {
+        let cards = parse_cards(token, Owner::Player)?;
+        if cards[0].index() == cards[1].index() {
+            return Err("combo uses the same card twice");
+        }
+        return Ok(vec![[cards[0], cards[1]]]);
+    }
+
+    if let Some((from, to)) = token.split_once('-') {
+        let (high_a, low_a, suited_a) = parse_hand_class(from.trim())?;
+        let (high_b, low_b, suited_b) = parse_hand_class(to.trim())?;
+        let is_pair_span = high_a == low_a && high_b == low_b;
+        let is_kicker_span = high_a == high_b && high_a != low_a && high_b != low_b;
+        if !(is_pair_span || is_kicker_span) || suited_a != suited_b {
+            return Err("range span must share the high card or be pairs");
+        }
+
+        let (from_rank, to_rank) = if is_pair_span { (high_a, high_b) } else { (low_a, low_b) };
+        let (bottom, top) = (from_rank.min(to_rank), from_rank.max(to_rank));
+        let mut combos = Vec::new();
+        for rank in CARD_RANKS.iter().filter(|&&rank| rank >= bottom && rank <= top) {
+            if is_pair_span {
+                combos.extend(class_combos(*rank, *rank, &suited_a));
+            } else {
+                combos.extend(class_combos(high_a, *rank, &suited_a));
+            }
+        }
+        return Ok(combos);
+    }
+
+    let (class, plus) = match token.strip_suffix('+') {
+        Some(class) => (class, true),
+        None => (token, false),
+    };
+    let (high, low, suited) = parse_hand_class(class)?;
+    if !plus {
+        return Ok(class_combos(high, low, &suited));
+    }
+
+    let mut combos = Vec::new();
+    if high == low {
+        // QQ+ is every pair from queens up
+        for rank in CARD_RANKS.iter().filter(|&&rank| rank >= high) {
+            combos.extend(class_combos(*rank, *rank, &suited));
+        }
+    } else {
+        // ATs+ raises the kicker up to just below the high card
+        for rank in CARD_RANKS.iter().filter(|&&rank| rank >= low && rank < high) {
+            combos.extend(class_combos(high, *rank, &suited));
+        }
+    }
+    Ok(combos)
+}
+
+fn parse_hand_class(class: &str) -> Result<(Rank, Rank, Suitedness), &'static str> {
+    let chars: Vec<char> = class.chars().collect();
+    if chars.len() < 2 || chars.len() > 3 {
+        return Err("invalid hand class");
+    }
+    let first = Rank::try_from(chars[0])?;
+    let second = Rank::try_from(chars[1])?;
+    let suited = match chars.get(2).map(|c| c.to_ascii_lowercase()) {
+        None => Suitedness::Any,
+        Some('s') => Suitedness::Suited,
+        Some('o') => Suitedness::Offsuit,
+        Some(_) => return Err("hand class suffix must be s or o"),
+    };
+    if first == second && !matches!(suited, Suitedness::Any) {
+        return Err("pairs cannot be suited or offsuit");
+    }
+    Ok((first.max(second), first.min(second), suited))
+}
+
+fn class_combos(high: Rank, low: Rank, suited: &Suitedness) -> Vec<[Card; 2]> {
+    let mut combos = Vec::new();
+    for (i, &high_suit) in CARD_SUITS.iter().enumerate() {
+        for (j, &low_suit) in CARD_SUITS.iter().enumerate() {
+            let keep = if high == low {
+                i < j
+            } else {
+                match suited {
+                    Suitedness::Suited => i == j,
+                    Suitedness::Offsuit => i != j,
+                    Suitedness::Any => true,
+                }
+            };
+            if keep {
+                combos.push([
+                    Card { rank: high, suit: high_suit, owner: Owner::Player },
+                    Card { rank: low, suit: low_suit, owner: Owner::Player },
+                ]);
+            }
+        }
+    }
+    combos
+}
+
+pub struct RangeEquityConfig {
+    pub ranges: Vec<HandRange>,
+    pub board: Vec<Card>,
+    pub dead_cards: Vec<Card>,
+    /// Largest number of combo matchups times runouts enumerated before
+    /// switching to Monte Carlo
+    pub exact_limit: u64,
+    pub iterations: u64,
+    pub seed: u64,
+    /// Worker threads, 0 uses every available core
+    pub threads: usize,
+}
+
+impl RangeEquityConfig {
+    pub fn new(ranges: Vec<HandRange>, board: Vec<Card>) -> Self {
+        Self {
+            ranges,
+            board,
+            dead_cards: Vec::new(),
+            exact_limit: 250_000,
+            iterations: 100_000,
+            seed: 0,
+            threads: 0,
+        }
+    }
+}
+
+/// Hold'em equity between ranges. A known hand is a range of one combo,
+/// so this covers range vs hand as well as range vs range
+pub fn range_equity(config: &RangeEquityConfig) -> Result<EquityResult, &'static str> {
+    if config.ranges.len() < 2 {
+        return Err("equity needs at least two players");
+    }
+    if config.board.len() > BOARD_SIZE {
+        return Err("board cannot have more than 5 cards");
+    }
+    let known: Vec<Card> = config
+        .board
+        .iter()
+        .chain(config.dead_cards.iter())
+        .copied()
+        .collect();
+    if card_mask(&known).count_ones() as usize != known.len() {
+        return Err("card dealt more than once");
+    }
+
+    let ranges: Vec<HandRange> = config
+        .ranges
+        .iter()
+        .map(|range| range.without_cards(&known))
+        .collect();
+    if ranges.iter().any(|range| range.is_empty()) {
+        return Err("a range has no combos left after card removal");
+    }
+
+    let board = with_owner(&config.board, Owner::Community);
+    let known_mask = card_mask(&known);
+    let n_players = ranges.len();
+    let n_board = BOARD_SIZE - board.len();
+    let threads = worker_threads(config.threads);
+    let runouts = n_choose_k(DECK.len() - known.len() - 2 * n_players, n_board);
+    // Card removal between ranges only lowers the count, so the full
+    // product tells when the matchups are few enough to build
+    let max_matchups = ranges.iter().fold(1u64, |count, range| {
+        count.saturating_mul(range.len() as u64)
+    });
+
+    if max_matchups.saturating_mul(runouts) <= config.exact_limit {
+        let matchups = matchups(&ranges, known_mask);
+        if matchups.is_empty() {
+            return Err("ranges have no compatible combos");
+        }
+        let tally = tally_parallel(&matchups, threads, n_players, |(combos, weight), tally| {
+            let used = known_mask | combos.iter().fold(0, |mask, combo| mask | combo.mask());
+            let stub: Vec<Card> = DECK
+                .iter()
+                .filter(|card| used & (1 << card.index()) == 0)
+                .copied()
+                .collect();
+            let holes: Vec<Vec<Card>> = combos.iter().map(|combo| combo.cards.to_vec()).collect();
+            for runout in combinations(&stub, n_board) {
+                let mut full_board = board.clone();
+                full_board.extend(with_owner(&runout, Owner::Community));
+                tally.record(&score_hands(GameType::TexasHoldemPoker, &holes, &full_board), *weight);
+            }
+        });
+        return Ok(tally.into_result(EquityMethod::Exact));
+    }
+
+    if config.iterations == 0 {
+        return Err("monte carlo equity needs at least one iteration");
+    }
+    if !has_matchup(&ranges, known_mask) {
+        return Err("ranges have no compatible combos");
+    }
+    let tally = tally_batches(
+        config.iterations,
+        config.seed,
+        threads,
+        n_players,
+        &(),
+        |_, rng, tally| match sample_matchup(&ranges, known_mask, rng) {
+            Some((holes, mut used)) => {
+                let mut full_board = board.clone();
+                while full_board.len() < BOARD_SIZE {
+                    let card = DECK[rng.random_range(0..DECK.len())];
+                    if used & (1 << card.index()) == 0 {
+                        used |= 1 << card.index();
+                        full_board.push(Card { owner: Owner::Community, ..card });
+                    }
+                }
+                tally.record(&score_hands(GameType::TexasHoldemPoker, &holes, &full_board), 1.0);
+            }
+            None => tally.drop_sample(),
+        },
+    );
+    if tally.samples() == 0 {
+        return Err("ranges have no compatible combos");
+    }
+    Ok(tally.into_result(EquityMethod::MonteCarlo))
+}
+
+/// Whether one combo per range can be picked without sharing cards,
+/// stopping at the first such pick
+fn has_matchup(ranges: &[HandRange], used: u64) -> bool {
+    let Some((range, rest)) = ranges.split_first() else {
+        return true;
+    };
+    range
+        .combos
+        .iter()
+        .filter(|combo| !combo.conflicts_with(used))
+        .any(|combo| has_matchup(rest, used | combo.mask()))
+}
+
+/// One combo per range drawn by weight, redrawn while they share cards.
+/// Returns the hole cards and the cards in use, none after
+/// `MAX_SAMPLE_ATTEMPTS` clashing draws
+fn sample_matchup(
+    ranges: &[HandRange],
+    known_mask: u64,
+    rng: &mut impl Rng,
+) -> Option<(Vec<Vec<Card>>, u64)> {
+    'attempt: for _ in 0..MAX_SAMPLE_ATTEMPTS {
+        let mut used = known_mask;
+        let mut holes: Vec<Vec<Card>> = Vec::with_capacity(ranges.len());
+        for range in ranges.iter() {
+            let combo = range.sample(rng);
+            if combo.conflicts_with(used) {
+                continue 'attempt;
+            }
+            used |= combo.mask();
+            holes.push(combo.cards.to_vec());
+        }
+        return Some((holes, used));
+    }
+    None
+}
+
+/// Every combination of one combo per range that shares no cards, with the
+/// product of the combo weights
+fn matchups(ranges: &[HandRange], known_mask: u64) -> Vec<(Vec<Combo>, f64)> {
+    let mut result: Vec<(Vec<Combo>, f64, u64)> = vec![(Vec::new(), 1.0, known_mask)];
+    for range in ranges.iter() {
+        let mut next = Vec::with_capacity(result.len() * range.len());
+        for (combos, weight, used) in result.iter() {
+            for combo in range.combos.iter().filter(|combo| !combo.conflicts_with(*used)) {
+                let mut extended = combos.clone();
+                extended.push(*combo);
+                next.push((extended, weight * combo.weight, used | combo.mask()));
+            }
+        }
+        result = next;
+    }
+    result
+        .into_iter()
+        .map(|(combos, weight, _)| (combos, weight))
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct EquityMatrixRow {
+    pub combo: String,
+    pub weight: f64,
+    pub win: f64,
+    pub tie: f64,
+    pub equity: f64,
+}
+
+/// Equity of one hand against every combo of a range
+#[derive(Serialize)]
+pub struct EquityMatrix {
+    pub hand: String,
+    pub board: String,
+    pub rows: Vec<EquityMatrixRow>,
+}
+
+impl EquityMatrix {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("combo,weight,win,tie,equity\n");
+        for row in self.rows.iter() {
+            csv.push_str(&format!(
+                "{},{},{:.6},{:.6},{:.6}\n",
+                row.combo, row.weight, row.win, row.tie, row.equity
+            ));
+        }
+        csv
+    }
+}
+
+/// Runs `hand` against each combo of `range` left after card removal. The
+/// board, dead cards and sampling limits are taken from `template`
+pub fn equity_matrix(
+    hand: &[Card],
+    range: &HandRange,
+    template: &EquityConfig,
+) -> Result<EquityMatrix, &'static str> {
+    let known: Vec<Card> = hand
+        .iter()
+        .chain(template.board.iter())
+        .chain(template.dead_cards.iter())
+        .copied()
+        .collect();
+
+    let mut rows = Vec::new();
+    for combo in range.without_cards(&known).combos.iter() {
+        let config = EquityConfig {
+            game_type: GameType::TexasHoldemPoker,
+            hands: vec![hand.to_vec(), combo.cards.to_vec()],
+            board: template.board.clone(),
+            dead_cards: template.dead_cards.clone(),
+            exact_limit: template.exact_limit,
+            iterations: template.iterations,
+            seed: template.seed,
+            threads: template.threads,
+        };
+        let result = calculate_equity(&config)?;
+        rows.push(EquityMatrixRow {
+            combo: combo.to_string(),
+            weight: combo.weight,
+            win: result.players[0].win,
+            tie: result.players[0].tie,
+            equity: result.players[0].equity,
+        });
+    }
+
+    Ok(EquityMatrix {
+        hand: hand.iter().map(Card::notation).collect(),
+        board: template.board.iter().map(Card::notation).collect(),
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_ranges_sample_without_listing_matchups() {
+        let mut ranges = vec![HandRange::parse("AK").unwrap()];
+        ranges.extend((0..3).map(|_| HandRange::any_two()));
+        let config = RangeEquityConfig {
+            iterations: 2_000,
+            threads: 1,
+            ..RangeEquityConfig::new(ranges, Vec::new())
+        };
+        let result = range_equity(&config).unwrap();
+        assert_eq!(result.method, EquityMethod::MonteCarlo);
+        assert_eq!(result.samples + result.dropped, 2_000);
+    }
+
+    #[test]
+    fn clashing_ranges_are_an_error() {
+        let ranges = (0..3).map(|_| HandRange::parse("AA").unwrap()).collect();
+        let config = RangeEquityConfig::new(ranges, Vec::new());
+        assert!(range_equity(&config).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::variant::{variant, ForcedBets, GameVariant};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// HORSE: Hold'em, Omaha-8, Razz, Stud and Stud-8, the flop games fixed limit
+pub const HORSE: [&str; 5] = [
+    "limit-holdem",
+    "limit-omaha-hi-lo",
+    "razz",
+    "seven-card-stud",
+    "stud-hi-lo",
+];
+
+/// 8-Game: 2-7 Triple Draw, HORSE, No Limit Hold'em and Pot Limit Omaha
+pub const EIGHT_GAME: [&str; 8] = [
+    "2-7-triple-draw",
+    "limit-holdem",
+    "limit-omaha-hi-lo",
+    "razz",
+    "seven-card-stud",
+    "stud-hi-lo",
+    "texas-holdem",
+    "omaha",
+];
+
+pub const MIXED_GAME_NAMES: [&str; 2] = ["horse", "8-game"];
+
+/// When a mixed game table moves on to its next game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationPeriod {
+    Hands(usize),
+    /// As many hands as players were seated when the game started
+    Orbit,
+}
+
+impl Display for RotationPeriod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hands(hands) => write!(f, "every {hands} hands"),
+            Self::Orbit => write!(f, "every orbit"),
+        }
+    }
+}
+
+impl FromStr for RotationPeriod {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "orbit" => Ok(Self::Orbit),
+            hands => match hands.parse() {
+                Ok(hands) if hands > 0 => Ok(Self::Hands(hands)),
+                _ => Err("rotation period must be a number of hands or orbit"),
+            },
+        }
+    }
+}
+
+/// Games a table plays in turn, a single game for a regular table. They are
+/// shared so a hand's game can be handed to work done off the table
+pub struct GameRotation {
+    pub games: Vec<Arc<dyn GameVariant>>,
+    pub period: RotationPeriod,
+    pub forced_bets: ForcedBets,
+}
+
+impl GameRotation {
+    pub fn single(game: Box<dyn GameVariant>) -> Self {
+        Self {
+            games: vec![Arc::from(game)],
+            period: RotationPeriod::Orbit,
+            forced_bets: ForcedBets::default(),
+        }
+    }
+}
+
+/// Rotation for a mix name ("horse", "8-game"), a comma separated list of
+/// variants such as "holdem,omaha8,razz" or a single variant
+pub fn game_rotation(name: &str, period: RotationPeriod) -> Result<GameRotation, &'static str> {
+    let names: Vec<&str> = match name.to_ascii_lowercase().as_str() {
+        "horse" => HORSE.to_vec(),
+        "8-game" | "eight-game" => EIGHT_GAME.to_vec(),
+        _ => name.split(',').map(str::trim).collect(),
+    };
+    let games = names
+        .iter()
+        .map(|name| variant(name).map(Arc::from))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(GameRotation {
+        games,
+        period,
+        forced_bets: ForcedBets::default(),
+    })
+}
@@ -0,0 +1,386 @@
+use crate::engine::{Hand, HandSeat};
+use crate::game::PokerStep;
+use crate::strategy::{BotView, Strategy};
+use crate::variant::{BettingStructure, ForcedBets, GameVariant};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::Serialize;
+
+const Z_SCORE_95: f64 = 1.96;
+
+/// Seat of a simulated table and the strategy playing it
+pub struct Seat {
+    /// Seats with the same name are reported together
+    pub name: String,
+    pub strategy: Box<dyn Strategy>,
+}
+
+pub struct SimulationConfig {
+    pub hands: u64,
+    /// Chips every seat starts each hand with
+    pub starting_stack: u32,
+    pub seed: u64,
+}
+
+/// Results of the seats played by one strategy
+#[derive(Serialize, Clone, Debug)]
+pub struct StrategyResult {
+    pub name: String,
+    pub seats: usize,
+    /// Hands dealt, once per seat
+    pub hands: u64,
+    /// Chips won minus chips put in
+    pub net: i64,
+    /// Big blinds won per 100 hands
+    pub bb_per_100: f64,
+    /// Half width of the 95% confidence interval of `bb_per_100`
+    pub bb_per_100_margin: f64,
+    /// Net of the hands the strategy took to showdown
+    pub showdown_net: i64,
+    /// Net of the hands won or lost before a showdown
+    pub non_showdown_net: i64,
+    pub showdowns: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SimulationReport {
+    pub game: &'static str,
+    pub hands: u64,
+    pub big_blind: u32,
+    /// In the order of their first seat
+    pub strategies: Vec<StrategyResult>,
+    /// Hands that ended with more or fewer chips than they started with,
+    /// anything but 0 is an engine bug
+    pub conservation_errors: u64,
+}
+
+/// Net of a seat in one hand
+struct SeatResult {
+    net: i64,
+    showdown: bool,
+}
+
+/// Running totals of a strategy, with the variance of its hand results
+#[derive(Default)]
+struct Tally {
+    hands: u64,
+    net: i64,
+    showdown_net: i64,
+    non_showdown_net: i64,
+    showdowns: u64,
+    mean: f64,
+    squared_deviations: f64,
+}
+
+impl Tally {
+    fn record(&mut self, result: &SeatResult) {
+        self.hands += 1;
+        self.net += result.net;
+        if result.showdown {
+            self.showdown_net += result.net;
+            self.showdowns += 1;
+        } else {
+            self.non_showdown_net += result.net;
+        }
+        let delta = result.net as f64 - self.mean;
+        self.mean += delta / self.hands as f64;
+        self.squared_deviations += delta * (result.net as f64 - self.mean);
+    }
+
+    fn into_result(self, name: String, seats: usize, big_blind: u32) -> StrategyResult {
+        let variance = self.squared_deviations / (self.hands.max(2) - 1) as f64;
+        let per_100 = 100.0 / big_blind.max(1) as f64;
+        StrategyResult {
+            name,
+            seats,
+            hands: self.hands,
+            net: self.net,
+            bb_per_100: self.mean * per_100,
+            bb_per_100_margin: Z_SCORE_95 * (variance / self.hands.max(1) as f64).sqrt() * per_100,
+            showdown_net: self.showdown_net,
+            non_showdown_net: self.non_showdown_net,
+            showdowns: self.showdowns,
+        }
+    }
+}
+
+/// What a strategy knows of the hand when it is the turn of the seat at `idx`
+fn view<'a>(
+    variant: &'a dyn GameVariant,
+    betting: &BettingStructure,
+    hand: &'a Hand,
+    players: &'a [HandSeat],
+    idx: usize,
+) -> BotView<'a> {
+    let player = &players[idx];
+    BotView {
+        game: variant,
+        hole_cards: &player.dealt_cards,
+        board: &hand.community_cards,
+        to_call: hand.to_call(players, idx),
+        pot: hand.pot(players),
+        big_blind: betting.big_blind,
+        betting_round: hand.betting_round,
+        opponents: players
+            .iter()
+            .filter(|other| other.is_betting)
+            .count()
+            .saturating_sub(1),
+        stack: player.funds,
+        bet: player.bet,
+        opponent_stack: players
+            .iter()
+            .enumerate()
+            .filter(|&(other, player)| other != idx && player.is_betting)
+            .map(|(_, player)| player.funds + player.bet)
+            .max()
+            .unwrap_or(0),
+        ante: betting.ante,
+        actions: &hand.actions,
+    }
+}
+
+/// Deals one hand with the big blind moving on from `big_blind_idx`,
+/// returning each seat's result and whether the chips were conserved
+fn play_hand(
+    variant: &dyn GameVariant,
+    seats: &mut [Seat],
+    starting_stack: u32,
+    big_blind_idx: usize,
+    rng: &mut StdRng,
+) -> Result<(Vec<SeatResult>, bool), &'static str> {
+    let betting = variant.betting_structure();
+    let mut players: Vec<HandSeat> = (0..seats.len())
+        .map(|_| HandSeat {
+            is_betting: true,
+            funds: starting_stack,
+            ..HandSeat::default()
+        })
+        .collect();
+    let mut deck = variant.deck();
+    deck.shuffle(rng);
+    let mut hand = Hand::default();
+    hand.start(deck);
+    hand.big_blind_idx = big_blind_idx;
+
+    for &step in variant.steps() {
+        if players.iter().filter(|player| player.is_betting).count() <= 1 {
+            break;
+        }
+        match step {
+            PokerStep::Blind => {
+                hand.post_blinds(&mut players, &betting, &ForcedBets::default(), &[]);
+            }
+            PokerStep::Ante => hand.post_antes(&mut players, betting.ante),
+            PokerStep::PreFlop => {
+                hand.deal_hole_cards(&mut players, variant.hole_card_count(), rng)
+            }
+            PokerStep::Flop => _ = hand.deal_community_cards(3, rng),
+            PokerStep::Turn | PokerStep::River => _ = hand.deal_community_cards(1, rng),
+            PokerStep::ThirdStreet
+            | PokerStep::FourthStreet
+            | PokerStep::FifthStreet
+            | PokerStep::SixthStreet
+            | PokerStep::SeventhStreet => {
+                hand.deal_stud_street(&mut players, variant, step, &betting, rng);
+            }
+            PokerStep::Draw => {
+                let n_players = players.len();
+                for offset in 0..n_players {
+                    let idx = (hand.first_to_act + offset) % n_players;
+                    if !players[idx].is_betting {
+                        continue;
+                    }
+                    let view = view(variant, &betting, &hand, &players, idx);
+                    let discards = seats[idx].strategy.discard(&view, rng);
+                    hand.draw(&mut players, idx, discards, rng);
+                }
+            }
+            PokerStep::BettingRound => {
+                while let Some(idx) = hand.next_to_act(&players) {
+                    let view = view(variant, &betting, &hand, &players, idx);
+                    let decision = seats[idx].strategy.act(&view, rng);
+                    let decision = hand.legalize(&players, idx, decision, &betting);
+                    if hand.apply(&mut players, idx, decision, &betting).is_err() {
+                        return Err("the table refused a legal decision");
+                    }
+                }
+                hand.end_betting_round(&players, variant);
+            }
+            PokerStep::Showdown => {}
+        }
+    }
+    let board = hand.community_cards.clone();
+    let settlement = hand.settle(&mut players, variant, &[board], None)?;
+
+    let chips: u64 = players.iter().map(|player| player.funds as u64).sum();
+    let conserved = chips == starting_stack as u64 * seats.len() as u64;
+    let results = players
+        .iter()
+        .map(|player| SeatResult {
+            net: player.funds as i64 - starting_stack as i64,
+            showdown: settlement.showdown && player.is_betting,
+        })
+        .collect();
+    Ok((results, conserved))
+}
+
+/// Plays `config.hands` hands between the seats, the blinds moving one seat
+/// each hand and every stack reset to the starting one. Runs on the calling
+/// thread and replays the same way for the same seed. Fails on a hand the
+/// engine cannot settle
+pub fn simulate(
+    variant: &dyn GameVariant,
+    seats: &mut [Seat],
+    config: &SimulationConfig,
+) -> Result<SimulationReport, &'static str> {
+    if seats.len() < 2 {
+        return Err("a simulation needs at least two seats");
+    }
+    if seats.len() > variant.max_players() {
+        return Err("more seats than the game can deal to");
+    }
+    let big_blind = variant.betting_structure().big_blind;
+    if config.starting_stack < big_blind {
+        return Err("the starting stack should cover the big blind");
+    }
+
+    let mut names: Vec<&str> = Vec::new();
+    for seat in seats.iter() {
+        if !names.contains(&seat.name.as_str()) {
+            names.push(&seat.name);
+        }
+    }
+    let names: Vec<String> = names.into_iter().map(str::to_string).collect();
+    let strategy_of: Vec<usize> = seats
+        .iter()
+        .map(|seat| {
+            names
+                .iter()
+                .position(|name| *name == seat.name)
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut tallies: Vec<Tally> = names.iter().map(|_| Tally::default()).collect();
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut conservation_errors = 0;
+    for hand in 0..config.hands {
+        let big_blind_idx = (hand % seats.len() as u64) as usize;
+        let (results, conserved) = play_hand(
+            variant,
+            seats,
+            config.starting_stack,
+            big_blind_idx,
+            &mut rng,
+        )?;
+        conservation_errors += !conserved as u64;
+        for (seat, result) in results.iter().enumerate() {
+            tallies[strategy_of[seat]].record(result);
+        }
+    }
+
+    Ok(SimulationReport {
+        game: variant.name(),
+        hands: config.hands,
+        big_blind,
+        strategies: tallies
+            .into_iter()
+            .zip(names)
+            .enumerate()
+            .map(|(strategy, (tally, name))| {
+                let n_seats = strategy_of.iter().filter(|&&of| of == strategy).count();
+                tally.into_result(name, n_seats, big_blind)
+            })
+            .collect(),
+        conservation_errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::BotStrategy;
+    use crate::variant::variant;
+
+    fn seats(strategies: &[BotStrategy]) -> Vec<Seat> {
+        strategies
+            .iter()
+            .map(|strategy| Seat {
+                name: strategy.to_string(),
+                strategy: strategy.strategy(),
+            })
+            .collect()
+    }
+
+    fn nets(report: &SimulationReport) -> Vec<i64> {
+        report.strategies.iter().map(|result| result.net).collect()
+    }
+
+    #[test]
+    fn the_same_seed_replays_the_same_hands() {
+        let game = variant("holdem").unwrap();
+        let strategies = [BotStrategy::Random, BotStrategy::CallingStation];
+        let config = |seed| SimulationConfig {
+            hands: 300,
+            starting_stack: 1_000,
+            seed,
+        };
+        let first = simulate(game.as_ref(), &mut seats(&strategies), &config(3)).unwrap();
+        let again = simulate(game.as_ref(), &mut seats(&strategies), &config(3)).unwrap();
+        let other = simulate(game.as_ref(), &mut seats(&strategies), &config(4)).unwrap();
+        assert_eq!(nets(&first), nets(&again));
+        assert_ne!(nets(&first), nets(&other));
+    }
+
+    #[test]
+    fn the_margin_is_the_confidence_interval_of_the_win_rate() {
+        let mut tally = Tally::default();
+        for hand in 0..100 {
+            let net = if hand % 2 == 0 { 300 } else { -100 };
+            tally.record(&SeatResult {
+                net,
+                showdown: false,
+            });
+        }
+        // 100 bb/100 give or take 1.96 standard errors of 2 bb a hand
+        let result = tally.into_result("seat".to_string(), 1, 100);
+        let standard_error = (4.0 * 100.0 / 99.0 / 100.0_f64).sqrt();
+        assert!((result.bb_per_100 - 100.0).abs() < 1e-9);
+        assert!((result.bb_per_100_margin - Z_SCORE_95 * standard_error * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn chips_are_conserved_in_every_game() {
+        let strategies = [
+            BotStrategy::Random,
+            BotStrategy::CallingStation,
+            BotStrategy::Random,
+        ];
+        for name in [
+            "holdem",
+            "omaha",
+            "plo6",
+            "omaha8",
+            "short-deck",
+            "limit-holdem",
+            "omaha8-limit",
+            "stud",
+            "stud8",
+            "razz",
+            "draw",
+            "triple-draw",
+        ] {
+            let game = variant(name).unwrap();
+            let config = SimulationConfig {
+                hands: 60,
+                starting_stack: 500,
+                seed: 11,
+            };
+            let report = simulate(game.as_ref(), &mut seats(&strategies), &config).unwrap();
+            assert_eq!(report.conservation_errors, 0, "{name}");
+            assert_eq!(nets(&report).iter().sum::<i64>(), 0, "{name}");
+        }
+    }
+}
@@ -0,0 +1,504 @@
+use crate::card::Card;
+use crate::cfr::holdem::bot_policy_action;
+use crate::cfr::Action;
+use crate::combinations::combinations;
+use crate::equity::hand_strength;
+use crate::game::PokerStep;
+use crate::pushfold::{bot_equities, solve_push_fold, HandClass, PushFoldChart, PushFoldConfig};
+use crate::variant::{BettingLimit, BettingStructure, GameVariant, RoundRaises};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Hands dealt to estimate a bot's hand strength
+const STRENGTH_SAMPLES: u64 = 300;
+/// Most cards a bot throws away on a draw
+const MAX_DISCARDS: usize = 3;
+/// Fictitious play rounds of the charts push/fold bots solve
+const PUSH_FOLD_ITERATIONS: usize = 500;
+
+/// Strategies bots can play
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BotStrategy {
+    Random,
+    /// Plays few hands, picked by their strength, and bets them
+    TightAggressive,
+    /// Calls everything and never raises
+    CallingStation,
+    /// Shoves or folds heads-up hold'em by the equilibrium chart of its
+    /// stack, plays tight-aggressive anywhere else
+    PushFold,
+    /// Follows the CFR policy loaded for heads-up limit hold'em, plays
+    /// tight-aggressive anywhere else
+    Cfr,
+}
+
+impl BotStrategy {
+    pub fn strategy(self) -> Box<dyn Strategy> {
+        match self {
+            Self::Random => Box::new(RandomStrategy),
+            Self::TightAggressive => Box::new(TightAggressive),
+            Self::CallingStation => Box::new(CallingStation),
+            Self::PushFold => Box::new(PushFold::default()),
+            Self::Cfr => Box::new(CfrStrategy),
+        }
+    }
+}
+
+impl FromStr for BotStrategy {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "random" => Ok(Self::Random),
+            "tight-aggressive" | "tag" => Ok(Self::TightAggressive),
+            "calling-station" | "station" => Ok(Self::CallingStation),
+            "push-fold" | "pushfold" => Ok(Self::PushFold),
+            "cfr" => Ok(Self::Cfr),
+            _ => {
+                Err("unknown bot strategy, expected random, tag, calling-station, push-fold or cfr")
+            }
+        }
+    }
+}
+
+impl Display for BotStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Random => write!(f, "random"),
+            Self::TightAggressive => write!(f, "tight-aggressive"),
+            Self::CallingStation => write!(f, "calling-station"),
+            Self::PushFold => write!(f, "push-fold"),
+            Self::Cfr => write!(f, "cfr"),
+        }
+    }
+}
+
+/// Action a strategy takes on its turn of a betting round
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Fold,
+    Check,
+    Call,
+    /// Chips put in on top of the current bet
+    Raise(u32),
+}
+
+/// What a bot knows of the hand when it is its turn
+pub struct BotView<'a> {
+    pub game: &'a dyn GameVariant,
+    pub hole_cards: &'a [Card],
+    pub board: &'a [Card],
+    /// Chips to put in to match the current bet
+    pub to_call: u32,
+    /// Every chip put in this hand, current bets included
+    pub pot: u32,
+    pub big_blind: u32,
+    /// Starting at 0
+    pub betting_round: usize,
+    /// Players left in the hand besides the bot
+    pub opponents: usize,
+    /// Chips the bot has left behind
+    pub stack: u32,
+    /// Chips the bot has put in this hand
+    pub bet: u32,
+    /// Most chips an opponent still in the hand started it with
+    pub opponent_stack: u32,
+    /// Ante each player posted this hand
+    pub ante: u32,
+    /// Decisions taken in each betting round so far, blinds and antes left
+    /// out
+    pub actions: &'a [Vec<Decision>],
+}
+
+impl BotView<'_> {
+    /// Smallest and largest raise the table takes, `None` for the largest
+    /// when uncapped. `None` when the round allows no more raises
+    pub fn raise_bounds(&self) -> Option<(u32, Option<u32>)> {
+        let opened_by_blinds =
+            self.betting_round == 0 && self.game.steps().contains(&PokerStep::Blind);
+        let mut raises = match opened_by_blinds {
+            true => RoundRaises::blinds(self.big_blind),
+            false => RoundRaises::default(),
+        };
+        for decision in self.actions.get(self.betting_round).into_iter().flatten() {
+            if let Decision::Raise(raise) = decision {
+                raises.record(*raise);
+            }
+        }
+        BettingStructure {
+            big_blind: self.big_blind,
+            ..self.game.betting_structure()
+        }
+        .raise_bounds(self.pot, self.to_call, self.betting_round, raises)
+    }
+
+    /// Raise of about `fraction` of the pot after calling, at least a big
+    /// blind and within the table's bounds, all in when the stack is short
+    /// of it. Calls when no raise is left
+    pub fn raise(&self, fraction: f64) -> Decision {
+        let all_in = self.stack.saturating_sub(self.to_call);
+        let Some((min_raise, max_raise)) = self.raise_bounds().filter(|_| all_in > 0) else {
+            return self.check_or_call();
+        };
+        let amount = ((self.pot + self.to_call) as f64 * fraction) as u32;
+        let amount = amount.max(self.big_blind).max(min_raise);
+        Decision::Raise(max_raise.map_or(amount, |max| amount.min(max)).min(all_in))
+    }
+
+    /// Checks when it is free to stay in, calls otherwise
+    pub fn check_or_call(&self) -> Decision {
+        match self.to_call {
+            0 => Decision::Check,
+            _ => Decision::Call,
+        }
+    }
+
+    /// Checks when it is free to stay in, folds otherwise
+    pub fn check_or_fold(&self) -> Decision {
+        match self.to_call {
+            0 => Decision::Check,
+            _ => Decision::Fold,
+        }
+    }
+
+    /// Share of the pot `hole_cards` win on average against the opponents
+    /// left. A fair share when it cannot be estimated
+    pub fn strength_of(&self, hole_cards: &[Card], dead_cards: &[Card], seed: u64) -> f64 {
+        hand_strength(
+            self.game,
+            hole_cards,
+            self.board,
+            dead_cards,
+            self.opponents,
+            STRENGTH_SAMPLES,
+            seed,
+        )
+        .unwrap_or(1.0 / (self.opponents + 1) as f64)
+    }
+}
+
+/// Decides how a bot plays its hands. Randomness comes from `rng` so seeded
+/// simulations replay the same way
+pub trait Strategy: Send {
+    /// Action on the bot's turn of a betting round
+    fn act(&mut self, view: &BotView, rng: &mut StdRng) -> Decision;
+
+    /// Cards thrown away on a draw, none to stand pat
+    fn discard(&mut self, view: &BotView, rng: &mut StdRng) -> Vec<Card>;
+}
+
+/// Calls most of the time, raises or folds now and then
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn act(&mut self, view: &BotView, rng: &mut StdRng) -> Decision {
+        let roll: f64 = rng.random();
+        match roll {
+            _ if roll < 0.15 => view.raise(rng.random_range(0.5..=1.0)),
+            _ if roll < 0.3 => view.check_or_fold(),
+            _ => view.check_or_call(),
+        }
+    }
+
+    fn discard(&mut self, view: &BotView, rng: &mut StdRng) -> Vec<Card> {
+        let n_cards = rng.random_range(0..=MAX_DISCARDS.min(view.hole_cards.len()));
+        view.hole_cards
+            .choose_multiple(rng, n_cards)
+            .copied()
+            .collect()
+    }
+}
+
+/// Bets hands worth more than a fair share of the pot, calls with the
+/// ones the pot odds pay for and folds the rest
+pub struct TightAggressive;
+
+impl Strategy for TightAggressive {
+    fn act(&mut self, view: &BotView, rng: &mut StdRng) -> Decision {
+        let strength = view.strength_of(view.hole_cards, &[], rng.random());
+        let fair_share = 1.0 / (view.opponents + 1) as f64;
+        let pot_odds = view.to_call as f64 / (view.pot + view.to_call).max(1) as f64;
+        if strength > 1.5 * fair_share {
+            view.raise(0.75)
+        } else if strength >= fair_share.max(pot_odds) {
+            view.check_or_call()
+        } else {
+            view.check_or_fold()
+        }
+    }
+
+    /// Keeps the cards that are strongest once the draw is dealt
+    fn discard(&mut self, view: &BotView, rng: &mut StdRng) -> Vec<Card> {
+        let hand = view.hole_cards;
+        let seed = rng.random();
+        let keep = (0..=MAX_DISCARDS.min(hand.len()))
+            .flat_map(|n_discards| combinations(hand, hand.len() - n_discards))
+            .map(|kept| {
+                let thrown: Vec<Card> = hand
+                    .iter()
+                    .filter(|card| !kept.contains(card))
+                    .copied()
+                    .collect();
+                (view.strength_of(&kept, &thrown, seed), kept)
+            })
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map_or(hand.to_vec(), |(_, kept)| kept);
+        hand.iter()
+            .filter(|card| !keep.contains(card))
+            .copied()
+            .collect()
+    }
+}
+
+/// Never folds or raises, and stands pat on every draw
+pub struct CallingStation;
+
+impl Strategy for CallingStation {
+    fn act(&mut self, view: &BotView, _rng: &mut StdRng) -> Decision {
+        view.check_or_call()
+    }
+
+    fn discard(&mut self, _view: &BotView, _rng: &mut StdRng) -> Vec<Card> {
+        Vec::new()
+    }
+}
+
+/// Plays heads-up no-limit hold'em before the flop by equilibrium push/fold
+/// charts: the small blind shoves or folds, the big blind calls the shove or
+/// folds. Charts are solved once per effective stack, to the half big blind
+#[derive(Default)]
+pub struct PushFold {
+    /// Keyed by stack in half big blinds and ante in hundredths of one
+    charts: HashMap<(u32, u32), PushFoldChart>,
+}
+
+impl PushFold {
+    fn chart(&mut self, stack: f64, ante: f64) -> Option<&PushFoldChart> {
+        let key = ((stack * 2.0).round() as u32, (ante * 100.0).round() as u32);
+        match self.charts.entry(key) {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let config = PushFoldConfig {
+                    stack: key.0 as f64 / 2.0,
+                    ante: key.1 as f64 / 100.0,
+                    iterations: PUSH_FOLD_ITERATIONS,
+                };
+                let chart = solve_push_fold(bot_equities(), &config).ok()?;
+                Some(entry.insert(chart))
+            }
+        }
+    }
+}
+
+impl Strategy for PushFold {
+    fn act(&mut self, view: &BotView, rng: &mut StdRng) -> Decision {
+        let preflop_heads_up = view.opponents == 1
+            && view.board.is_empty()
+            && view.betting_round == 0
+            && view.big_blind > 0
+            && view.game.board_card_count() == 5
+            && view.game.deck().len() == 52
+            && matches!(view.raise_bounds(), Some((_, None)));
+        let hole_cards: [Card; 2] = match view.hole_cards.try_into() {
+            Ok(hole_cards) if preflop_heads_up => hole_cards,
+            _ => return TightAggressive.act(view, rng),
+        };
+        if view.to_call == 0 {
+            return Decision::Check;
+        }
+
+        let big_blind = view.big_blind as f64;
+        let stack = (view.stack + view.bet).min(view.opponent_stack) as f64 / big_blind;
+        let ante = view.ante as f64 / big_blind;
+        let class = HandClass::of(&hole_cards);
+        // Short of covering the blinds every hand is worth playing
+        let (push, call) = match self.chart(stack, ante) {
+            Some(chart) => (chart.push_frequency(class), chart.call_frequency(class)),
+            None => (1.0, 1.0),
+        };
+        let roll: f64 = rng.random();
+        let unopened = view.to_call < view.big_blind;
+        if unopened && roll < push {
+            // A shove short of a full raise still puts the stack in
+            match view.stack.saturating_sub(view.to_call) {
+                0 => Decision::Call,
+                shove => Decision::Raise(shove),
+            }
+        } else if !unopened && roll < call {
+            Decision::Call
+        } else {
+            Decision::Fold
+        }
+    }
+
+    fn discard(&mut self, view: &BotView, rng: &mut StdRng) -> Vec<Card> {
+        TightAggressive.discard(view, rng)
+    }
+}
+
+/// Plays heads-up limit hold'em by the CFR policy set with
+/// `cfr::holdem::set_bot_policy`, tight-aggressive without one or anywhere
+/// else
+pub struct CfrStrategy;
+
+impl Strategy for CfrStrategy {
+    fn act(&mut self, view: &BotView, rng: &mut StdRng) -> Decision {
+        let heads_up_holdem = view.opponents == 1
+            && view.game.board_card_count() == 5
+            && view.game.deck().len() == 52
+            && view.game.betting_structure().limit == BettingLimit::FixedLimit;
+        let history: Vec<Vec<Action>> = view
+            .actions
+            .iter()
+            .map(|round| {
+                round
+                    .iter()
+                    .map(|decision| match decision {
+                        Decision::Fold => Action::Fold,
+                        Decision::Check | Decision::Call => Action::Call,
+                        Decision::Raise(_) => Action::Raise,
+                    })
+                    .collect()
+            })
+            .collect();
+        let round = view.betting_round;
+        let action = match heads_up_holdem {
+            true => bot_policy_action(view.hole_cards, view.board, &history, round, rng),
+            false => None,
+        };
+        match action {
+            Some(Action::Fold) => view.check_or_fold(),
+            Some(Action::Call) => view.check_or_call(),
+            // Limit bets come in one size
+            Some(Action::Raise) => view.raise(0.0),
+            None => TightAggressive.act(view, rng),
+        }
+    }
+
+    fn discard(&mut self, view: &BotView, rng: &mut StdRng) -> Vec<Card> {
+        TightAggressive.discard(view, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{parse_cards, Owner};
+    use crate::variant::variant;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn view<'a>(game: &'a dyn GameVariant, hole_cards: &'a [Card]) -> BotView<'a> {
+        BotView {
+            game,
+            hole_cards,
+            board: &[],
+            to_call: 20,
+            pot: 30,
+            big_blind: 20,
+            betting_round: 0,
+            opponents: 2,
+            stack: 1_000,
+            bet: 0,
+            opponent_stack: 1_000,
+            ante: 0,
+            actions: &[],
+        }
+    }
+
+    /// Whether the table takes `decision` from the player `view` is for
+    fn is_legal(view: &BotView, decision: Decision) -> bool {
+        match decision {
+            Decision::Fold => true,
+            Decision::Check => view.to_call == 0,
+            Decision::Call => view.to_call > 0,
+            Decision::Raise(raise) => {
+                let all_in = view.to_call + raise == view.stack;
+                view.to_call + raise <= view.stack
+                    && view.raise_bounds().is_some_and(|(min_raise, max_raise)| {
+                        (raise >= min_raise || all_in) && max_raise.is_none_or(|max| raise <= max)
+                    })
+            }
+        }
+    }
+
+    #[test]
+    fn strategies_only_take_legal_decisions() {
+        let capped = vec![vec![Decision::Raise(20); 3]];
+        let mut strategies: Vec<(BotStrategy, Box<dyn Strategy>)> = [
+            BotStrategy::Random,
+            BotStrategy::TightAggressive,
+            BotStrategy::CallingStation,
+            BotStrategy::PushFold,
+            BotStrategy::Cfr,
+        ]
+        .into_iter()
+        .map(|strategy| (strategy, strategy.strategy()))
+        .collect();
+        for name in ["holdem", "omaha", "limit-holdem", "stud", "draw"] {
+            let game = variant(name).unwrap();
+            for seed in 0..3 {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut deck = game.deck();
+                deck.shuffle(&mut rng);
+                let hole_cards = &deck[..game.hole_card_count()];
+                let mut views = vec![
+                    view(game.as_ref(), hole_cards),
+                    BotView {
+                        to_call: 0,
+                        bet: 20,
+                        ..view(game.as_ref(), hole_cards)
+                    },
+                    BotView {
+                        stack: 50,
+                        ..view(game.as_ref(), hole_cards)
+                    },
+                    BotView {
+                        stack: 10,
+                        ..view(game.as_ref(), hole_cards)
+                    },
+                ];
+                if name == "limit-holdem" {
+                    views.push(BotView {
+                        to_call: 60,
+                        pot: 90,
+                        actions: &capped,
+                        ..view(game.as_ref(), hole_cards)
+                    });
+                }
+                for view in views.iter() {
+                    for (strategy, bot) in strategies.iter_mut() {
+                        let decision = bot.act(view, &mut rng);
+                        assert!(
+                            is_legal(view, decision),
+                            "{strategy} took {decision:?} in {name} with {} to call and {} behind",
+                            view.to_call,
+                            view.stack
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tight_aggressive_folds_trash_and_raises_premium_hands() {
+        let game = variant("holdem").unwrap();
+        let trash = parse_cards("7c2d", Owner::Player).unwrap();
+        let aces = parse_cards("AsAh", Owner::Player).unwrap();
+        for seed in 0..5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let decision = TightAggressive.act(&view(game.as_ref(), &trash), &mut rng);
+            assert_eq!(decision, Decision::Fold);
+            let decision = TightAggressive.act(&view(game.as_ref(), &aces), &mut rng);
+            assert!(matches!(decision, Decision::Raise(_)), "{decision:?}");
+        }
+    }
+}
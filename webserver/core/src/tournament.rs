@@ -0,0 +1,437 @@
+use crate::variant::BettingStructure;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Blinds and ante of a tournament level
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindLevel {
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub ante: u32,
+}
+
+impl BlindLevel {
+    pub const fn new(small_blind: u32, big_blind: u32, ante: u32) -> Self {
+        Self {
+            small_blind,
+            big_blind,
+            ante,
+        }
+    }
+
+    /// Scales a game's betting to the level. Stud games post no blinds: the
+    /// big blind sizes their bets, the bring-in is a quarter of it and they
+    /// ante a tenth of it on levels without antes
+    pub fn apply(&self, betting: BettingStructure) -> BettingStructure {
+        if betting.bring_in == 0 {
+            return BettingStructure {
+                small_blind: self.small_blind,
+                big_blind: self.big_blind,
+                ante: self.ante,
+                ..betting
+            };
+        }
+        BettingStructure {
+            big_blind: self.big_blind,
+            ante: self.ante.max(self.big_blind / 10),
+            bring_in: (self.big_blind / 4).max(1),
+            ..betting
+        }
+    }
+}
+
+/// Blind schedule of a sit-and-go, antes kick in from the sixth level
+pub const SIT_AND_GO_LEVELS: [BlindLevel; 12] = [
+    BlindLevel::new(10, 20, 0),
+    BlindLevel::new(15, 30, 0),
+    BlindLevel::new(25, 50, 0),
+    BlindLevel::new(50, 100, 0),
+    BlindLevel::new(75, 150, 0),
+    BlindLevel::new(100, 200, 25),
+    BlindLevel::new(150, 300, 25),
+    BlindLevel::new(200, 400, 50),
+    BlindLevel::new(300, 600, 75),
+    BlindLevel::new(400, 800, 100),
+    BlindLevel::new(600, 1200, 150),
+    BlindLevel::new(1000, 2000, 200),
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BountyType {
+    /// The eliminator collects the whole bounty
+    Knockout,
+    /// Half the bounty is paid out, the other half goes onto the
+    /// eliminator's own head
+    Progressive,
+}
+
+impl fmt::Display for BountyType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Knockout => write!(f, "Knockout"),
+            Self::Progressive => write!(f, "Progressive knockout"),
+        }
+    }
+}
+
+impl FromStr for BountyType {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "knockout" | "ko" => Ok(Self::Knockout),
+            "progressive" | "pko" => Ok(Self::Progressive),
+            _ => Err("unknown bounty type, expected knockout or progressive"),
+        }
+    }
+}
+
+/// Bounty on every entrant's head, paid on top of the buy-in
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounty {
+    pub bounty_type: BountyType,
+    /// Starting bounty of each entrant
+    pub amount: u32,
+}
+
+impl Bounty {
+    /// Cash won and amount added to their own head by each player sharing
+    /// the knockout of a player with `head` on them. The bounty is split
+    /// evenly, odd chips going to the first eliminators
+    pub fn split(&self, head: u32, n_eliminators: usize) -> Vec<(u32, u32)> {
+        let n_eliminators = n_eliminators.max(1) as u32;
+        (0..n_eliminators)
+            .map(|eliminator| {
+                let share = head / n_eliminators + (eliminator < head % n_eliminators) as u32;
+                match self.bounty_type {
+                    BountyType::Knockout => (share, 0),
+                    BountyType::Progressive => (share / 2, share - share / 2),
+                }
+            })
+            .collect()
+    }
+}
+
+/// What a player pays into a tournament for
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Purchase {
+    /// Registration, before the start or late
+    Entry,
+    /// Another entry after busting, with a fresh stack and bounty
+    Reentry,
+    /// A starting stack bought during the rebuy period
+    Rebuy,
+    /// Chips bought once at the break after the rebuy period
+    AddOn,
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentConfig {
+    /// Players needed to start, the tournament begins once they registered
+    pub seats: usize,
+    pub starting_stack: u32,
+    pub buy_in: u32,
+    pub levels: Vec<BlindLevel>,
+    /// Seconds each level lasts, the last one goes on until the end
+    pub level_duration: u64,
+    /// None for tournaments without bounties
+    pub bounty: Option<Bounty>,
+    /// Levels players can still register during, 0 to close registration
+    /// at the start
+    pub late_registration: usize,
+    /// Whether busted players can enter again while registration is open
+    pub reentry: bool,
+    /// Levels players can rebuy during, 0 for a freezeout
+    pub rebuy_levels: usize,
+    /// Chips of the add-on, none for tournaments without one. Play stops
+    /// for a break after the rebuy levels while players take it
+    pub add_on: Option<u32>,
+    /// Seconds the add-on break lasts
+    pub break_duration: u64,
+}
+
+impl TournamentConfig {
+    pub fn sit_and_go(seats: usize) -> Self {
+        Self {
+            seats,
+            starting_stack: 1_500,
+            buy_in: 100,
+            levels: SIT_AND_GO_LEVELS.to_vec(),
+            level_duration: 300,
+            bounty: None,
+            late_registration: 0,
+            reentry: false,
+            rebuy_levels: 0,
+            add_on: None,
+            break_duration: 300,
+        }
+    }
+
+    /// Price of a purchase, entries paying for the bounty on top of the buy-in
+    pub fn price(&self, purchase: Purchase) -> u32 {
+        match purchase {
+            Purchase::Entry | Purchase::Reentry => {
+                self.buy_in + self.bounty.map_or(0, |bounty| bounty.amount)
+            }
+            Purchase::Rebuy | Purchase::AddOn => self.buy_in,
+        }
+    }
+
+    /// Seconds into the tournament the add-on break starts, none without one
+    fn break_start(&self) -> Option<u64> {
+        self.add_on
+            .map(|_| self.rebuy_levels as u64 * self.level_duration.max(1))
+    }
+
+    /// Seconds left of the add-on break `elapsed` seconds into the
+    /// tournament, none outside of it
+    pub fn break_left(&self, elapsed: u64) -> Option<u64> {
+        let start = self.break_start()?;
+        let end = start + self.break_duration;
+        (start..end).contains(&elapsed).then(|| end - elapsed)
+    }
+
+    /// Level played `elapsed` seconds into the tournament, with the seconds
+    /// left until the next one, none on the last level. The clock stops
+    /// during the add-on break
+    pub fn level_at(&self, elapsed: u64) -> (usize, Option<u64>) {
+        let on_break = self.break_start().map_or(0, |start| {
+            elapsed.saturating_sub(start).min(self.break_duration)
+        });
+        let played = elapsed - on_break;
+        let duration = self.level_duration.max(1);
+        let last_level = self.levels.len().saturating_sub(1);
+        let level = ((played / duration) as usize).min(last_level);
+        let next_level_in = (level < last_level).then(|| (level as u64 + 1) * duration - played);
+        (level, next_level_in)
+    }
+}
+
+/// Share of the prize pool won by each paid position in percent, first place
+/// first
+pub fn payout_percentages(n_entrants: usize) -> &'static [u32] {
+    match n_entrants {
+        0..=3 => &[100],
+        4..=6 => &[65, 35],
+        7..=10 => &[50, 30, 20],
+        11..=20 => &[40, 25, 15, 12, 8],
+        _ => &[30, 20, 12, 9, 7, 6, 5, 4, 4, 3],
+    }
+}
+
+/// Prize of each paid position, the chips lost to rounding go to the winner
+pub fn payouts(prize_pool: u32, percentages: &[u32]) -> Vec<u32> {
+    let mut prizes: Vec<u32> = percentages
+        .iter()
+        .map(|&percentage| (prize_pool as u64 * percentage as u64 / 100) as u32)
+        .collect();
+    let remainder = prize_pool.saturating_sub(prizes.iter().sum());
+    if let Some(first) = prizes.first_mut() {
+        *first += remainder;
+    }
+    prizes
+}
+
+/// Players to move before a multi-table tournament table deals its next hand
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TableMoves {
+    /// The table breaks, every player moving out
+    pub breaks: bool,
+    /// Table each moved player goes to, the first seated player first
+    pub moves: Vec<usize>,
+}
+
+/// Balances the tables of a multi-table tournament before `table` deals its
+/// next hand. `seated` counts the players at each table, none for broken
+/// tables. The table breaks when the players left fit at one table less and
+/// it is the smallest, otherwise players move out to the smallest tables
+/// until it is within one player of them
+pub fn balance_table(seated: &[Option<usize>], table: usize, table_size: usize) -> TableMoves {
+    let Some(mut n_players) = seated.get(table).copied().flatten() else {
+        return TableMoves::default();
+    };
+    let mut seated = seated.to_vec();
+    let smallest_other = |seated: &[Option<usize>]| {
+        (0..seated.len())
+            .filter(|&idx| idx != table)
+            .filter_map(|idx| seated[idx].map(|n_seated| (n_seated, idx)))
+            .min()
+    };
+    let n_open = seated.iter().flatten().count();
+    let remaining: usize = seated.iter().flatten().sum();
+    let tables_needed = remaining.div_ceil(table_size.max(1)).max(1);
+    let is_smallest = smallest_other(&seated).is_some_and(|(n_other, _)| n_players <= n_other);
+    let breaks = n_open > tables_needed && is_smallest;
+
+    let mut moves = Vec::new();
+    while n_players > 0 {
+        let Some((n_other, to)) = smallest_other(&seated) else {
+            break;
+        };
+        if !breaks && n_players <= n_other + 1 {
+            break;
+        }
+        seated[to] = Some(n_other + 1);
+        n_players -= 1;
+        moves.push(to);
+    }
+    TableMoves { breaks, moves }
+}
+
+/// Whether every table of a multi-table tournament finishes its hand before
+/// the next is dealt, which it does on the bubble while more than one table
+/// is playing
+pub fn plays_hand_for_hand(n_tables: usize, remaining: usize, paid_places: usize) -> bool {
+    n_tables > 1 && remaining == paid_places + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rebuy_config() -> TournamentConfig {
+        TournamentConfig {
+            rebuy_levels: 2,
+            add_on: Some(1_500),
+            break_duration: 120,
+            ..TournamentConfig::sit_and_go(6)
+        }
+    }
+
+    #[test]
+    fn levels_change_on_their_last_second() {
+        let config = TournamentConfig::sit_and_go(6);
+        assert_eq!(config.level_at(0), (0, Some(300)));
+        assert_eq!(config.level_at(299), (0, Some(1)));
+        assert_eq!(config.level_at(300), (1, Some(300)));
+        assert_eq!(config.level_at(11 * 300 - 1), (10, Some(1)));
+        assert_eq!(config.level_at(11 * 300), (11, None));
+        assert_eq!(config.level_at(u32::MAX as u64), (11, None));
+        assert_eq!(config.break_left(0), None);
+    }
+
+    #[test]
+    fn the_clock_stops_for_the_add_on_break() {
+        let config = rebuy_config();
+        assert_eq!(config.break_left(599), None);
+        assert_eq!(config.break_left(600), Some(120));
+        assert_eq!(config.break_left(719), Some(1));
+        assert_eq!(config.break_left(720), None);
+
+        assert_eq!(config.level_at(599), (1, Some(1)));
+        assert_eq!(config.level_at(600), (2, Some(300)));
+        assert_eq!(config.level_at(719), (2, Some(300)));
+        assert_eq!(config.level_at(720), (2, Some(300)));
+        assert_eq!(config.level_at(721), (2, Some(299)));
+        assert_eq!(config.level_at(1_020), (3, Some(300)));
+    }
+
+    #[test]
+    fn entries_pay_for_the_bounty_on_top() {
+        let config = TournamentConfig {
+            bounty: Some(Bounty {
+                bounty_type: BountyType::Knockout,
+                amount: 50,
+            }),
+            ..TournamentConfig::sit_and_go(6)
+        };
+        assert_eq!(config.price(Purchase::Entry), 150);
+        assert_eq!(config.price(Purchase::Reentry), 150);
+        assert_eq!(config.price(Purchase::Rebuy), 100);
+        assert_eq!(config.price(Purchase::AddOn), 100);
+    }
+
+    #[test]
+    fn payouts_spend_the_prize_pool_at_every_field_size() {
+        for n_entrants in [1, 3, 4, 6, 7, 10, 11, 20, 21, 180] {
+            let percentages = payout_percentages(n_entrants);
+            assert_eq!(percentages.iter().sum::<u32>(), 100);
+            assert!(percentages.len() <= n_entrants.max(1));
+            assert!(percentages.windows(2).all(|pair| pair[0] >= pair[1]));
+
+            let prize_pool = n_entrants as u32 * 333;
+            let prizes = payouts(prize_pool, percentages);
+            assert_eq!(prizes.len(), percentages.len());
+            assert_eq!(prizes.iter().sum::<u32>(), prize_pool);
+        }
+        assert_eq!(payouts(1_001, &[65, 35]), vec![651, 350]);
+    }
+
+    #[test]
+    fn split_bounties_give_odd_chips_to_the_first_eliminators() {
+        let progressive = Bounty {
+            bounty_type: BountyType::Progressive,
+            amount: 50,
+        };
+        assert_eq!(progressive.split(101, 1), vec![(50, 51)]);
+        assert_eq!(progressive.split(101, 2), vec![(25, 26), (25, 25)]);
+        assert_eq!(progressive.split(7, 3), vec![(1, 2), (1, 1), (1, 1)]);
+
+        let knockout = Bounty {
+            bounty_type: BountyType::Knockout,
+            amount: 50,
+        };
+        assert_eq!(knockout.split(101, 2), vec![(51, 0), (50, 0)]);
+        for (head, n_eliminators) in [(101, 2), (7, 3), (1, 4)] {
+            let total: u32 = progressive
+                .split(head, n_eliminators)
+                .iter()
+                .map(|(cash, added)| cash + added)
+                .sum();
+            assert_eq!(total, head);
+        }
+    }
+
+    #[test]
+    fn tables_are_kept_within_one_player() {
+        let seated = [Some(9), Some(6), Some(7)];
+        let balance = balance_table(&seated, 0, 9);
+        assert!(!balance.breaks);
+        assert_eq!(balance.moves, vec![1]);
+
+        let seated = [Some(9), Some(5), None, Some(5)];
+        assert_eq!(balance_table(&seated, 0, 9).moves, vec![1, 3]);
+        // Tables within one stay as they are
+        let seated = [Some(8), Some(7), Some(7)];
+        assert_eq!(balance_table(&seated, 0, 9), TableMoves::default());
+    }
+
+    #[test]
+    fn the_smallest_table_breaks_once_the_others_can_seat_it() {
+        let seated = [Some(4), Some(7), Some(6)];
+        let balance = balance_table(&seated, 0, 9);
+        assert!(balance.breaks);
+        assert_eq!(balance.moves, vec![2, 1, 2, 1]);
+
+        // A bigger table only sheds players down to the smallest one's size
+        let seated = [Some(7), Some(4), Some(6)];
+        let balance = balance_table(&seated, 0, 9);
+        assert!(!balance.breaks);
+        assert_eq!(balance.moves, vec![1]);
+        // Nothing breaks while every table is needed
+        let seated = [Some(4), Some(8), Some(8)];
+        assert!(!balance_table(&seated, 0, 9).breaks);
+    }
+
+    #[test]
+    fn the_last_two_tables_merge_into_the_final_table() {
+        let seated = [Some(5), Some(4), None];
+        assert_eq!(balance_table(&seated, 0, 9), TableMoves::default());
+        let balance = balance_table(&seated, 1, 9);
+        assert!(balance.breaks);
+        assert_eq!(balance.moves, vec![0; 4]);
+        // The final table itself is never broken
+        assert_eq!(balance_table(&[None, Some(9)], 1, 9), TableMoves::default());
+    }
+
+    #[test]
+    fn hand_for_hand_is_played_on_the_bubble() {
+        assert!(plays_hand_for_hand(2, 10, 9));
+        assert!(!plays_hand_for_hand(1, 10, 9));
+        assert!(!plays_hand_for_hand(3, 11, 9));
+        assert!(!plays_hand_for_hand(2, 9, 9));
+    }
+}
@@ -0,0 +1,778 @@
+use crate::card::{build_deck_from, Card, DECK};
+use crate::game::{
+    GameType, PokerStep, FIVE_CARD_DRAW_STEPS, STANDARD_POKER_STEPS, STUD_POKER_STEPS,
+    TRIPLE_DRAW_STEPS,
+};
+use crate::hand::{
+    evaluate_best_hand, evaluate_deuce_to_seven_hand, evaluate_hand, evaluate_omaha_low_hand,
+    evaluate_razz_hand, evaluate_short_deck_hand, evaluate_stud_low_hand, hand_score, low_hand_name, razz_low, HandType,
+    SHORT_DECK_RULES,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BettingLimit {
+    NoLimit,
+    PotLimit,
+    FixedLimit,
+}
+
+impl Display for BettingLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoLimit => write!(f, "No Limit"),
+            Self::PotLimit => write!(f, "Pot Limit"),
+            Self::FixedLimit => write!(f, "Fixed Limit"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BettingStructure {
+    pub limit: BettingLimit,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    /// Posted by every player, zero to play without antes
+    pub ante: u32,
+    /// Forced opening bet of the weakest door card in stud games
+    pub bring_in: u32,
+}
+
+impl BettingStructure {
+    /// Smallest and largest raise on top of the current bet, `None` for the
+    /// largest when uncapped. `None` when the round allows no more raises.
+    /// `pot` counts every bet on the table including the caller's
+    pub fn raise_bounds(
+        &self,
+        pot: u32,
+        to_call: u32,
+        betting_round: usize,
+        raises: RoundRaises,
+    ) -> Option<(u32, Option<u32>)> {
+        // A raise is at least the big blind and the last raise of the round
+        let min_raise = self.big_blind.max(raises.last_raise).max(1);
+        match self.limit {
+            BettingLimit::NoLimit => Some((min_raise, None)),
+            BettingLimit::PotLimit => {
+                let max_raise = pot + to_call;
+                Some((min_raise.min(max_raise), Some(max_raise)))
+            }
+            BettingLimit::FixedLimit if raises.n_bets >= FIXED_LIMIT_MAX_BETS => None,
+            BettingLimit::FixedLimit => {
+                // Small bets on the first two rounds, big bets afterwards
+                let size = if betting_round < 2 {
+                    self.big_blind
+                } else {
+                    2 * self.big_blind
+                };
+                Some((size, Some(size)))
+            }
+        }
+    }
+}
+
+/// Bets a fixed limit round allows: a bet and three raises, a posted big
+/// blind counting as the bet
+pub const FIXED_LIMIT_MAX_BETS: u32 = 4;
+
+/// Raising done so far in a betting round
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoundRaises {
+    /// Bets and raises made, a posted big blind counting as the first
+    pub n_bets: u32,
+    /// Largest bet or raise made, zero until someone bets
+    pub last_raise: u32,
+}
+
+impl RoundRaises {
+    /// Round opened by a posted big blind
+    pub fn blinds(big_blind: u32) -> Self {
+        Self {
+            n_bets: 1,
+            last_raise: big_blind,
+        }
+    }
+
+    pub fn record(&mut self, raise: u32) {
+        self.n_bets += 1;
+        self.last_raise = self.last_raise.max(raise);
+    }
+}
+
+/// Who pays the ante of a hand
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnteType {
+    #[default]
+    EveryPlayer,
+    /// The big blind pays the whole table's ante
+    BigBlind,
+    /// The button pays the whole table's ante
+    Button,
+}
+
+impl FromStr for AnteType {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "every-player" | "every" => Ok(Self::EveryPlayer),
+            "big-blind" | "bb" => Ok(Self::BigBlind),
+            "button" => Ok(Self::Button),
+            _ => Err("unknown ante type, expected every-player, big-blind or button"),
+        }
+    }
+}
+
+/// Voluntary blind of twice the big blind, posted before the deal
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Straddle {
+    /// Posted by the player left of the big blind
+    UnderTheGun,
+    /// Posted by the button
+    Mississippi,
+}
+
+impl FromStr for Straddle {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "utg" | "under-the-gun" => Ok(Self::UnderTheGun),
+            "mississippi" | "button" => Ok(Self::Mississippi),
+            _ => Err("unknown straddle, expected utg or mississippi"),
+        }
+    }
+}
+
+/// Antes and straddles a table plays on top of its games' blinds
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ForcedBets {
+    /// Replaces the games' own ante. Paid by each player, or in full by a
+    /// single player for big blind and button antes
+    pub ante: Option<u32>,
+    pub ante_type: AnteType,
+    /// Straddle players may opt into, none to play without straddles
+    pub straddle: Option<Straddle>,
+    /// Whether preflop action starts left of the straddle, giving it the
+    /// last option, instead of left of the big blind
+    pub straddle_acts_last: bool,
+}
+
+impl ForcedBets {
+    pub fn apply(&self, betting: BettingStructure) -> BettingStructure {
+        BettingStructure {
+            ante: self.ante.unwrap_or(betting.ante),
+            ..betting
+        }
+    }
+}
+
+/// Strength of a hand at showdown
+#[derive(Clone, Debug)]
+pub struct ShowdownHand {
+    pub hand_type: HandType,
+    /// Description shown to players, e.g. "Full House" or "8-6-4-2-A"
+    pub name: String,
+    /// Higher is better
+    pub high: u32,
+    /// Qualifying low for split pot games, see `evaluate_low_hand`
+    pub low: Option<[u8; 5]>,
+}
+
+/// High hand of every player, for games without a low half
+pub fn high_hands(
+    hands: &[Vec<Card>],
+    game_type: GameType,
+) -> Result<Vec<ShowdownHand>, &'static str> {
+    hands
+        .iter()
+        .map(|hand| {
+            let (hand_type, sorted_ranks) = evaluate_best_hand(hand, game_type)?;
+            Ok(ShowdownHand {
+                hand_type,
+                name: hand_type.to_string(),
+                high: hand_score(hand_type, &sorted_ranks),
+                low: None,
+            })
+        })
+        .collect()
+}
+
+/// Rules of a poker game as seen by the room engine. Adding a game means
+/// implementing this trait and registering it in `variant`
+pub trait GameVariant: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Unshuffled cards in play
+    fn deck(&self) -> Vec<Card> {
+        DECK.to_vec()
+    }
+
+    fn hole_card_count(&self) -> usize;
+
+    /// Steps of a hand, in order
+    fn steps(&self) -> &'static [PokerStep] {
+        &STANDARD_POKER_STEPS
+    }
+
+    fn betting_structure(&self) -> BettingStructure;
+
+    /// Community cards dealt over a whole hand
+    fn board_card_count(&self) -> usize {
+        self.steps()
+            .iter()
+            .map(|step| match step {
+                PokerStep::Flop => 3,
+                PokerStep::Turn | PokerStep::River => 1,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Most players the deck can deal a full hand to
+    fn max_players(&self) -> usize {
+        (self.deck().len() - self.board_card_count()) / self.hole_card_count()
+    }
+
+    /// Stud only: strength of a door card, the weakest one posts the bring-in
+    fn door_card_strength(&self, _card: &Card) -> Option<u32> {
+        None
+    }
+
+    /// Stud only: strength of the face-up cards, the strongest acts first
+    fn visible_strength(&self, _up_cards: &[Card]) -> u32 {
+        0
+    }
+
+    /// Evaluates every hand, each being a player's hole cards followed by
+    /// the board
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str>;
+}
+
+pub struct TexasHoldem;
+
+impl GameVariant for TexasHoldem {
+    fn name(&self) -> &'static str {
+        "Texas Holdem Poker"
+    }
+
+    fn hole_card_count(&self) -> usize {
+        2
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        BettingStructure {
+            limit: BettingLimit::NoLimit,
+            small_blind: 10,
+            big_blind: 20,
+            ante: 0,
+            bring_in: 0,
+        }
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        high_hands(hands, GameType::TexasHoldemPoker)
+    }
+}
+
+/// Omaha with 4, 5 (PLO5) or 6 (PLO6) hole cards, always playing exactly
+/// two of them
+pub struct Omaha {
+    pub hole_cards: usize,
+}
+
+impl GameVariant for Omaha {
+    fn name(&self) -> &'static str {
+        match self.hole_cards {
+            5 => "5 Card Omaha",
+            6 => "6 Card Omaha",
+            _ => "Omaha Poker",
+        }
+    }
+
+    fn hole_card_count(&self) -> usize {
+        self.hole_cards
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        BettingStructure {
+            limit: BettingLimit::PotLimit,
+            small_blind: 10,
+            big_blind: 20,
+            ante: 0,
+            bring_in: 0,
+        }
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        high_hands(hands, GameType::OmahaPoker)
+    }
+}
+
+/// Omaha Hi-Lo 8-or-better, each pot split between the best high and the
+/// best qualifying low
+pub struct OmahaHiLo;
+
+impl GameVariant for OmahaHiLo {
+    fn name(&self) -> &'static str {
+        "Omaha Hi-Lo"
+    }
+
+    fn hole_card_count(&self) -> usize {
+        4
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        BettingStructure {
+            limit: BettingLimit::PotLimit,
+            small_blind: 10,
+            big_blind: 20,
+            ante: 0,
+            bring_in: 0,
+        }
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        let mut showdown = high_hands(hands, GameType::OmahaPoker)?;
+        for (showdown_hand, hand) in showdown.iter_mut().zip(hands.iter()) {
+            showdown_hand.low = evaluate_omaha_low_hand(hand)?;
+        }
+        Ok(showdown)
+    }
+}
+
+/// Short Deck (6+) Hold'em with a 36 card deck, played with antes only
+pub struct ShortDeck;
+
+impl GameVariant for ShortDeck {
+    fn name(&self) -> &'static str {
+        "Short Deck Holdem"
+    }
+
+    fn deck(&self) -> Vec<Card> {
+        build_deck_from(SHORT_DECK_RULES.lowest_rank)
+    }
+
+    fn hole_card_count(&self) -> usize {
+        2
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        BettingStructure {
+            limit: BettingLimit::NoLimit,
+            small_blind: 0,
+            big_blind: 0,
+            ante: 10,
+            bring_in: 0,
+        }
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        hands
+            .iter()
+            .map(|hand| {
+                let (hand_type, sorted_ranks) = evaluate_short_deck_hand(hand)?;
+                Ok(ShowdownHand {
+                    hand_type,
+                    name: hand_type.to_string(),
+                    high: SHORT_DECK_RULES.score(hand_type, &sorted_ranks),
+                    low: None,
+                })
+            })
+            .collect()
+    }
+}
+
+fn stud_betting_structure() -> BettingStructure {
+    // No blinds are posted in stud, the big blind only sizes the fixed-limit
+    // bets: one on third and fourth street, two afterwards
+    BettingStructure {
+        limit: BettingLimit::FixedLimit,
+        small_blind: 0,
+        big_blind: 20,
+        ante: 2,
+        bring_in: 5,
+    }
+}
+
+/// Seven Card Stud, high hand only. Up to 8 players, when the deck runs out
+/// the last card is dealt face up to the board and shared
+pub struct SevenCardStud;
+
+impl GameVariant for SevenCardStud {
+    fn name(&self) -> &'static str {
+        "Seven Card Stud"
+    }
+
+    fn hole_card_count(&self) -> usize {
+        7
+    }
+
+    fn steps(&self) -> &'static [PokerStep] {
+        &STUD_POKER_STEPS
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        stud_betting_structure()
+    }
+
+    fn max_players(&self) -> usize {
+        8
+    }
+
+    fn door_card_strength(&self, card: &Card) -> Option<u32> {
+        // Lowest rank brings in, clubs being the lowest suit on ties
+        Some(card.rank as u32 * 4 + card.suit as u32)
+    }
+
+    fn visible_strength(&self, up_cards: &[Card]) -> u32 {
+        evaluate_hand(&mut up_cards.to_vec()).map_or(0, |(hand_type, sorted_ranks)| {
+            hand_score(hand_type, &sorted_ranks)
+        })
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        high_hands(hands, GameType::TexasHoldemPoker)
+    }
+}
+
+/// Seven Card Stud Hi-Lo 8-or-better (Stud-8), each pot split between the
+/// best high and the best qualifying low
+pub struct SevenCardStudHiLo;
+
+impl GameVariant for SevenCardStudHiLo {
+    fn name(&self) -> &'static str {
+        "Seven Card Stud Hi-Lo"
+    }
+
+    fn hole_card_count(&self) -> usize {
+        7
+    }
+
+    fn steps(&self) -> &'static [PokerStep] {
+        &STUD_POKER_STEPS
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        stud_betting_structure()
+    }
+
+    fn max_players(&self) -> usize {
+        8
+    }
+
+    fn door_card_strength(&self, card: &Card) -> Option<u32> {
+        SevenCardStud.door_card_strength(card)
+    }
+
+    fn visible_strength(&self, up_cards: &[Card]) -> u32 {
+        SevenCardStud.visible_strength(up_cards)
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        let mut showdown = high_hands(hands, GameType::TexasHoldemPoker)?;
+        for (showdown_hand, hand) in showdown.iter_mut().zip(hands.iter()) {
+            showdown_hand.low = evaluate_stud_low_hand(hand);
+        }
+        Ok(showdown)
+    }
+}
+
+/// Razz, Seven Card Stud played for the ace-to-five low without qualifier
+pub struct Razz;
+
+impl GameVariant for Razz {
+    fn name(&self) -> &'static str {
+        "Razz"
+    }
+
+    fn hole_card_count(&self) -> usize {
+        7
+    }
+
+    fn steps(&self) -> &'static [PokerStep] {
+        &STUD_POKER_STEPS
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        stud_betting_structure()
+    }
+
+    fn max_players(&self) -> usize {
+        8
+    }
+
+    fn door_card_strength(&self, card: &Card) -> Option<u32> {
+        // Highest card brings in, the ace being low and spades the highest
+        // suit on ties
+        let low_rank = (card.rank as u32 + 1) % 13;
+        Some((12 - low_rank) * 4 + (3 - card.suit as u32))
+    }
+
+    fn visible_strength(&self, up_cards: &[Card]) -> u32 {
+        u32::MAX - razz_low(up_cards).0
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        const CATEGORIES: [HandType; 6] = [
+            HandType::HighCard,
+            HandType::Pair,
+            HandType::TwoPair,
+            HandType::ThreeOfAKind,
+            HandType::FullHouse,
+            HandType::FourOfAKind,
+        ];
+
+        hands
+            .iter()
+            .map(|hand| {
+                let (score, low_ranks) = evaluate_razz_hand(hand)?;
+                Ok(ShowdownHand {
+                    hand_type: CATEGORIES[(score / 13u32.pow(5)) as usize],
+                    name: low_hand_name(&low_ranks),
+                    high: u32::MAX - score,
+                    low: None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Five Card Draw, high hand after a single draw
+pub struct FiveCardDraw;
+
+impl GameVariant for FiveCardDraw {
+    fn name(&self) -> &'static str {
+        "Five Card Draw"
+    }
+
+    fn hole_card_count(&self) -> usize {
+        5
+    }
+
+    fn steps(&self) -> &'static [PokerStep] {
+        &FIVE_CARD_DRAW_STEPS
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        BettingStructure {
+            limit: BettingLimit::NoLimit,
+            small_blind: 10,
+            big_blind: 20,
+            ante: 0,
+            bring_in: 0,
+        }
+    }
+
+    fn max_players(&self) -> usize {
+        // Leaves enough of the stub and discards to draw from
+        6
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        high_hands(hands, GameType::TexasHoldemPoker)
+    }
+}
+
+/// Deuce-to-seven Triple Draw, fixed-limit lowball over three draws where
+/// aces are high and straights and flushes count against the hand
+pub struct DeuceToSevenTripleDraw;
+
+impl GameVariant for DeuceToSevenTripleDraw {
+    fn name(&self) -> &'static str {
+        "2-7 Triple Draw"
+    }
+
+    fn hole_card_count(&self) -> usize {
+        5
+    }
+
+    fn steps(&self) -> &'static [PokerStep] {
+        &TRIPLE_DRAW_STEPS
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        BettingStructure {
+            limit: BettingLimit::FixedLimit,
+            small_blind: 10,
+            big_blind: 20,
+            ante: 0,
+            bring_in: 0,
+        }
+    }
+
+    fn max_players(&self) -> usize {
+        6
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        hands
+            .iter()
+            .map(|hand| {
+                let (score, hand_type, sorted_ranks) = evaluate_deuce_to_seven_hand(hand)?;
+                let name = match hand_type {
+                    // Written like other lows, e.g. "7-5-4-3-2"
+                    HandType::HighCard => low_hand_name(&sorted_ranks.map(|rank| (rank + 1) % 13)),
+                    _ => hand_type.to_string(),
+                };
+                Ok(ShowdownHand {
+                    hand_type,
+                    name,
+                    high: u32::MAX - score,
+                    low: None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A game played fixed limit instead of its usual structure, as the flop
+/// games are in mixed games
+pub struct FixedLimitGame {
+    pub name: &'static str,
+    pub game: Box<dyn GameVariant>,
+}
+
+impl GameVariant for FixedLimitGame {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn deck(&self) -> Vec<Card> {
+        self.game.deck()
+    }
+
+    fn hole_card_count(&self) -> usize {
+        self.game.hole_card_count()
+    }
+
+    fn steps(&self) -> &'static [PokerStep] {
+        self.game.steps()
+    }
+
+    fn betting_structure(&self) -> BettingStructure {
+        BettingStructure {
+            limit: BettingLimit::FixedLimit,
+            ..self.game.betting_structure()
+        }
+    }
+
+    fn max_players(&self) -> usize {
+        self.game.max_players()
+    }
+
+    fn door_card_strength(&self, card: &Card) -> Option<u32> {
+        self.game.door_card_strength(card)
+    }
+
+    fn visible_strength(&self, up_cards: &[Card]) -> u32 {
+        self.game.visible_strength(up_cards)
+    }
+
+    fn showdown(&self, hands: &[Vec<Card>]) -> Result<Vec<ShowdownHand>, &'static str> {
+        self.game.showdown(hands)
+    }
+}
+
+pub const VARIANT_NAMES: [&str; 13] = [
+    "texas-holdem",
+    "omaha",
+    "omaha-5",
+    "omaha-6",
+    "omaha-hi-lo",
+    "short-deck",
+    "limit-holdem",
+    "limit-omaha-hi-lo",
+    "seven-card-stud",
+    "stud-hi-lo",
+    "razz",
+    "five-card-draw",
+    "2-7-triple-draw",
+];
+
+pub fn variant(name: &str) -> Result<Box<dyn GameVariant>, &'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "texas-holdem" | "holdem" => Ok(Box::new(TexasHoldem)),
+        "omaha" => Ok(Box::new(Omaha { hole_cards: 4 })),
+        "omaha-5" | "plo5" => Ok(Box::new(Omaha { hole_cards: 5 })),
+        "omaha-6" | "plo6" => Ok(Box::new(Omaha { hole_cards: 6 })),
+        "omaha-hi-lo" | "omaha8" | "plo8" => Ok(Box::new(OmahaHiLo)),
+        "short-deck" | "six-plus" => Ok(Box::new(ShortDeck)),
+        "limit-holdem" => Ok(Box::new(FixedLimitGame {
+            name: "Limit Holdem",
+            game: Box::new(TexasHoldem),
+        })),
+        "limit-omaha-hi-lo" | "omaha8-limit" => Ok(Box::new(FixedLimitGame {
+            name: "Limit Omaha Hi-Lo",
+            game: Box::new(OmahaHiLo),
+        })),
+        "seven-card-stud" | "stud" => Ok(Box::new(SevenCardStud)),
+        "stud-hi-lo" | "stud8" | "stud-8" => Ok(Box::new(SevenCardStudHiLo)),
+        "razz" => Ok(Box::new(Razz)),
+        "five-card-draw" | "draw" => Ok(Box::new(FiveCardDraw)),
+        "2-7-triple-draw" | "triple-draw" => Ok(Box::new(DeuceToSevenTripleDraw)),
+        _ => Err("unknown game variant"),
+    }
+}
+
+/// Variant by the name it is shown with, e.g. "Razz" for `razz`
+pub fn variant_by_display_name(name: &str) -> Option<Box<dyn GameVariant>> {
+    VARIANT_NAMES
+        .iter()
+        .filter_map(|key| variant(key).ok())
+        .find(|game| game.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn structure(limit: BettingLimit) -> BettingStructure {
+        BettingStructure {
+            limit,
+            small_blind: 5,
+            big_blind: 10,
+            ante: 0,
+            bring_in: 0,
+        }
+    }
+
+    #[test]
+    fn pot_limit_raises_at_least_the_last_raise() {
+        let betting = structure(BettingLimit::PotLimit);
+        let mut raises = RoundRaises::blinds(10);
+        assert_eq!(
+            betting.raise_bounds(15, 10, 0, raises),
+            Some((10, Some(25)))
+        );
+        raises.record(25);
+        assert_eq!(
+            betting.raise_bounds(50, 25, 0, raises),
+            Some((25, Some(75)))
+        );
+        assert_eq!(
+            betting.raise_bounds(40, 0, 1, RoundRaises::default()),
+            Some((10, Some(40)))
+        );
+    }
+
+    #[test]
+    fn fixed_limit_caps_a_round_at_four_bets() {
+        let betting = structure(BettingLimit::FixedLimit);
+        let mut raises = RoundRaises::blinds(10);
+        for _ in 1..FIXED_LIMIT_MAX_BETS {
+            assert_eq!(betting.raise_bounds(0, 0, 0, raises), Some((10, Some(10))));
+            raises.record(10);
+        }
+        assert_eq!(betting.raise_bounds(0, 0, 0, raises), None);
+        assert_eq!(
+            betting.raise_bounds(0, 0, 2, RoundRaises::default()),
+            Some((20, Some(20)))
+        );
+    }
+}
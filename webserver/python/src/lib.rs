@@ -0,0 +1,276 @@
+use mini_poker_core::card::{parse_cards as parse_core_cards, Card, Owner};
+use mini_poker_core::equity::{calculate_equity, EquityConfig, EquityResult};
+use mini_poker_core::game::GameType;
+use mini_poker_core::hand::{best_hand, compare_hands as compare_core_hands, hand_score};
+use mini_poker_core::range::{range_equity as core_range_equity, HandRange, RangeEquityConfig};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+// Placeholder for a player whose hole cards are unknown
+const UNKNOWN_HAND: &str = "??";
+
+fn value_error(message: &str) -> PyErr {
+    PyValueError::new_err(message.to_string())
+}
+
+fn cards(text: &str, owner: Owner) -> PyResult<Vec<Card>> {
+    parse_core_cards(text, owner).map_err(|err| value_error(&format!("{err}: {text}")))
+}
+
+fn game(name: &str) -> PyResult<GameType> {
+    name.parse().map_err(value_error)
+}
+
+#[pyclass(name = "Card", frozen)]
+#[derive(Clone)]
+struct PyCard {
+    /// 0 for a two up to 12 for an ace
+    #[pyo3(get)]
+    rank: u8,
+    /// One of "c", "d", "h" or "s"
+    #[pyo3(get)]
+    suit: char,
+    #[pyo3(get)]
+    notation: String,
+}
+
+#[pymethods]
+impl PyCard {
+    fn __str__(&self) -> String {
+        self.notation.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Card('{}')", self.notation)
+    }
+}
+
+impl From<&Card> for PyCard {
+    fn from(card: &Card) -> Self {
+        Self {
+            rank: card.rank as u8,
+            suit: card.suit.into(),
+            notation: card.notation(),
+        }
+    }
+}
+
+#[pyclass(name = "HandResult", frozen)]
+struct PyHandResult {
+    #[pyo3(get)]
+    hand_type: String,
+    /// 0 for high card up to 9 for a royal flush
+    #[pyo3(get)]
+    category: u8,
+    #[pyo3(get)]
+    sorted_ranks: Vec<u8>,
+    #[pyo3(get)]
+    cards: Vec<String>,
+    /// Comparable strength, higher is better
+    #[pyo3(get)]
+    score: u32,
+}
+
+#[pymethods]
+impl PyHandResult {
+    fn __repr__(&self) -> String {
+        format!("HandResult('{}', {:?})", self.hand_type, self.cards)
+    }
+}
+
+#[pyclass(name = "PlayerEquity", frozen)]
+#[derive(Clone)]
+struct PyPlayerEquity {
+    #[pyo3(get)]
+    win: f64,
+    #[pyo3(get)]
+    tie: f64,
+    #[pyo3(get)]
+    equity: f64,
+    /// Half width of the 95% confidence interval, zero when exact
+    #[pyo3(get)]
+    margin: f64,
+}
+
+#[pymethods]
+impl PyPlayerEquity {
+    fn __repr__(&self) -> String {
+        format!("PlayerEquity(equity={:.4}, win={:.4}, tie={:.4})", self.equity, self.win, self.tie)
+    }
+}
+
+#[pyclass(name = "EquityResult", frozen)]
+struct PyEquityResult {
+    /// "Exact" or "Monte Carlo"
+    #[pyo3(get)]
+    method: String,
+    #[pyo3(get)]
+    samples: u64,
+    /// Monte Carlo samples given up on, not counted in `samples`
+    #[pyo3(get)]
+    dropped: u64,
+    #[pyo3(get)]
+    players: Vec<PyPlayerEquity>,
+}
+
+impl From<EquityResult> for PyEquityResult {
+    fn from(result: EquityResult) -> Self {
+        Self {
+            method: result.method.to_string(),
+            samples: result.samples,
+            dropped: result.dropped,
+            players: result
+                .players
+                .iter()
+                .map(|player| PyPlayerEquity {
+                    win: player.win,
+                    tie: player.tie,
+                    equity: player.equity,
+                    margin: player.margin,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Parses cards written as "AsKs", "As Ks" or "As,Ks"
+#[pyfunction]
+fn parse_cards(text: &str) -> PyResult<Vec<PyCard>> {
+    Ok(cards(text, Owner::Player)?.iter().map(PyCard::from).collect())
+}
+
+/// Best hand made by the hole cards and the board
+#[pyfunction]
+#[pyo3(signature = (hole, board = "", game = "holdem"))]
+fn evaluate(hole: &str, board: &str, game: &str) -> PyResult<PyHandResult> {
+    let game_type = self::game(game)?;
+    let hand: Vec<Card> = cards(hole, Owner::Player)?
+        .into_iter()
+        .chain(cards(board, Owner::Community)?)
+        .collect();
+    let best = best_hand(&hand, game_type).map_err(value_error)?;
+
+    Ok(PyHandResult {
+        hand_type: best.hand_type.to_string(),
+        category: best.hand_type as u8,
+        sorted_ranks: best.sorted_ranks.to_vec(),
+        cards: best.cards.iter().map(Card::notation).collect(),
+        score: hand_score(best.hand_type, &best.sorted_ranks),
+    })
+}
+
+/// Indexes of the winning hands, more than one on a split pot
+#[pyfunction]
+#[pyo3(signature = (hands, board = "", game = "holdem"))]
+fn compare_hands(hands: Vec<String>, board: &str, game: &str) -> PyResult<Vec<usize>> {
+    let game_type = self::game(game)?;
+    let board = cards(board, Owner::Community)?;
+    let hands = hands
+        .iter()
+        .map(|hole| Ok(cards(hole, Owner::Player)?.into_iter().chain(board.iter().copied()).collect()))
+        .collect::<PyResult<Vec<Vec<Card>>>>()?;
+    compare_core_hands(hands, game_type).map_err(value_error)
+}
+
+/// Win/tie/equity per player. Pass "??" for a player whose cards are unknown
+#[pyfunction]
+#[pyo3(signature = (
+    hands,
+    board = "",
+    dead = "",
+    game = "holdem",
+    iterations = 100_000,
+    exact_limit = 250_000,
+    seed = 0,
+    threads = 0
+))]
+#[allow(clippy::too_many_arguments)]
+fn equity(
+    py: Python<'_>,
+    hands: Vec<String>,
+    board: &str,
+    dead: &str,
+    game: &str,
+    iterations: u64,
+    exact_limit: u64,
+    seed: u64,
+    threads: usize,
+) -> PyResult<PyEquityResult> {
+    let config = EquityConfig {
+        game_type: self::game(game)?,
+        hands: hands
+            .iter()
+            .map(|hand| match hand.as_str() {
+                UNKNOWN_HAND => Ok(Vec::new()),
+                _ => cards(hand, Owner::Player),
+            })
+            .collect::<PyResult<_>>()?,
+        board: cards(board, Owner::Community)?,
+        dead_cards: cards(dead, Owner::Community)?,
+        exact_limit,
+        iterations,
+        seed,
+        threads,
+    };
+
+    py.allow_threads(|| calculate_equity(&config))
+        .map(PyEquityResult::from)
+        .map_err(value_error)
+}
+
+/// Hold'em equity between ranges such as "QQ+, AKs" or single hands
+#[pyfunction]
+#[pyo3(signature = (
+    ranges,
+    board = "",
+    dead = "",
+    iterations = 100_000,
+    exact_limit = 250_000,
+    seed = 0,
+    threads = 0
+))]
+#[allow(clippy::too_many_arguments)]
+fn range_equity(
+    py: Python<'_>,
+    ranges: Vec<String>,
+    board: &str,
+    dead: &str,
+    iterations: u64,
+    exact_limit: u64,
+    seed: u64,
+    threads: usize,
+) -> PyResult<PyEquityResult> {
+    let config = RangeEquityConfig {
+        ranges: ranges
+            .iter()
+            .map(|range| match range.as_str() {
+                UNKNOWN_HAND => Ok(HandRange::any_two()),
+                _ => HandRange::parse(range).map_err(|err| value_error(&format!("{err}: {range}"))),
+            })
+            .collect::<PyResult<_>>()?,
+        board: cards(board, Owner::Community)?,
+        dead_cards: cards(dead, Owner::Community)?,
+        exact_limit,
+        iterations,
+        seed,
+        threads,
+    };
+
+    py.allow_threads(|| core_range_equity(&config))
+        .map(PyEquityResult::from)
+        .map_err(value_error)
+}
+
+#[pymodule]
+fn mini_poker(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCard>()?;
+    m.add_class::<PyHandResult>()?;
+    m.add_class::<PyPlayerEquity>()?;
+    m.add_class::<PyEquityResult>()?;
+    m.add_function(wrap_pyfunction!(parse_cards, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_hands, m)?)?;
+    m.add_function(wrap_pyfunction!(equity, m)?)?;
+    m.add_function(wrap_pyfunction!(range_equity, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,8 @@
+pub mod args;
+pub mod cfr;
+pub mod compare;
+pub mod equity;
+pub mod eval;
+pub mod icm;
+pub mod pushfold;
+pub mod simulate;
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use mini_poker_core::card::{parse_cards, Card, Owner};
+use mini_poker_core::game::GameType;
+
+// Flags that never take a value
+const SWITCHES: [&str; 4] = ["json", "straddle-acts-last", "reentry", "no-flop-no-drop"];
+
+/// Command line arguments of a subcommand: positional values first, then
+/// `--flag value...` pairs. A flag takes every value up to the next flag
+pub struct CliArgs {
+    pub positional: Vec<String>,
+    flags: HashMap<String, Vec<String>>,
+}
+
+impl CliArgs {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut flags: HashMap<String, Vec<String>> = HashMap::new();
+        let mut current_flag: Option<String> = None;
+
+        for arg in args.iter() {
+            if let Some(flag) = arg.strip_prefix("--") {
+                if flag.is_empty() {
+                    return Err("empty flag".to_string());
+                }
+                flags.entry(flag.to_string()).or_default();
+                current_flag = (!SWITCHES.contains(&flag)).then(|| flag.to_string());
+                continue;
+            }
+            match &current_flag {
+                Some(flag) => flags.entry(flag.clone()).or_default().push(arg.clone()),
+                None if flags.is_empty() => positional.push(arg.clone()),
+                None => return Err(format!("unexpected argument: {arg}")),
+            }
+        }
+
+        Ok(Self { positional, flags })
+    }
+
+    /// Fails on flags the subcommand does not know about
+    pub fn expect_flags(&self, known: &[&str]) -> Result<(), String> {
+        match self.flags.keys().find(|flag| !known.contains(&flag.as_str())) {
+            Some(flag) => Err(format!("unknown flag: --{flag}")),
+            None => Ok(()),
+        }
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.flags.contains_key(name)
+    }
+
+    pub fn values(&self, name: &str) -> &[String] {
+        self.flags.get(name).map_or(&[], |values| values.as_slice())
+    }
+
+    pub fn value(&self, name: &str) -> Result<Option<&str>, String> {
+        match self.values(name) {
+            [] if self.flags.contains_key(name) => Err(format!("--{name} needs a value")),
+            [] => Ok(None),
+            [value] => Ok(Some(value.as_str())),
+            _ => Err(format!("--{name} takes a single value")),
+        }
+    }
+
+    pub fn game(&self) -> Result<GameType, String> {
+        match self.value("game")? {
+            Some(game) => game.parse().map_err(String::from),
+            None => Ok(GameType::TexasHoldemPoker),
+        }
+    }
+
+    pub fn cards(&self, name: &str, owner: Owner) -> Result<Vec<Card>, String> {
+        match self.value(name)? {
+            Some(text) => cards(text, owner),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn number<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.value(name)? {
+            Some(text) => text
+                .parse()
+                .map_err(|_| format!("--{name} expects a number, got {text}")),
+            None => Ok(default),
+        }
+    }
+}
+
+pub fn cards(text: &str, owner: Owner) -> Result<Vec<Card>, String> {
+    parse_cards(text, owner).map_err(|err| format!("{err}: {text}"))
+}
+
+pub fn notation(cards: &[Card]) -> String {
+    cards.iter().map(Card::notation).collect()
+}
@@ -0,0 +1,75 @@
+use crate::cli::args::CliArgs;
+use mini_poker_core::cfr::holdem::{set_bot_policy, LimitHoldem, DEFAULT_BUCKETS};
+use mini_poker_core::cfr::kuhn::Kuhn;
+use mini_poker_core::cfr::leduc::Leduc;
+use mini_poker_core::cfr::{
+    expected_value, exploitability, CfrAlgorithm, CfrGame, CfrSolver, Policy,
+};
+use std::time::Instant;
+
+pub const USAGE: &str = "cfr <kuhn|leduc|limit-holdem> [--iterations n] [--algorithm cfr|cfr+]
+\t\t[--buckets n] [--seed n] [--out file]";
+
+pub fn run(raw_args: &[String]) -> Result<(), String> {
+    let args = CliArgs::parse(raw_args)?;
+    args.expect_flags(&["algorithm", "buckets", "iterations", "out", "seed"])?;
+    let [game] = args.positional.as_slice() else {
+        return Err(format!("usage: {USAGE}"));
+    };
+    if args.value("buckets")?.is_some() && game != "limit-holdem" {
+        return Err("--buckets only abstracts limit-holdem".to_string());
+    }
+
+    match game.as_str() {
+        "kuhn" => train(&Kuhn, &args),
+        "leduc" => train(&Leduc, &args),
+        "limit-holdem" => {
+            let buckets = args.number("buckets", DEFAULT_BUCKETS)?;
+            if buckets == 0 {
+                return Err("--buckets needs at least one bucket".to_string());
+            }
+            train(&LimitHoldem { buckets }, &args)
+        }
+        _ => Err(format!(
+            "unknown game {game}, expected kuhn, leduc or limit-holdem"
+        )),
+    }
+}
+
+fn train<G: CfrGame>(game: &G, args: &CliArgs) -> Result<(), String> {
+    let algorithm: CfrAlgorithm = args.value("algorithm")?.unwrap_or("cfr+").parse()?;
+    let iterations = args.number("iterations", 1_000)?;
+
+    let start = Instant::now();
+    let mut solver = CfrSolver::new(algorithm);
+    solver.train(game, iterations, args.number("seed", 0)?);
+    let policy = solver.policy(game);
+
+    println!("Game:             {}", policy.game);
+    println!("Algorithm:        {}", policy.algorithm);
+    println!("Iterations:       {}", policy.iterations);
+    println!("Information sets: {}", solver.n_info_sets());
+    println!("Training time:    {:.2?}", start.elapsed());
+    // Only games small enough to walk every deal can be measured exactly
+    if let Ok(value) = expected_value(game, &policy) {
+        println!("Game value:       {value:.4} chips a hand for the first player");
+    }
+    if let Ok(exploitability) = exploitability(game, &policy) {
+        println!("Exploitability:   {exploitability:.4} chips a hand");
+    }
+
+    if let Some(path) = args.value("out")? {
+        std::fs::write(path, policy.to_json())
+            .map_err(|err| format!("could not write {path}: {err}"))?;
+        println!("Policy saved to {path}");
+    }
+    Ok(())
+}
+
+/// Reads a limit hold'em policy saved by `cfr` for bots playing `cfr`
+pub fn load_bot_policy(path: &str) -> Result<(), String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|err| format!("could not read {path}: {err}"))?;
+    set_bot_policy(Policy::from_json(&text)?)?;
+    Ok(())
+}
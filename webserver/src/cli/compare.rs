@@ -0,0 +1,66 @@
+use serde_json::json;
+
+use crate::cli::args::{cards, notation, CliArgs};
+use mini_poker_core::card::{Card, Owner};
+use mini_poker_core::hand::{best_hand, compare_hands};
+
+pub const USAGE: &str =
+    "compare <hand> <hand>... [--board cards] [--game holdem|omaha] [--json]\n\t\t(hands may also be given with --hands)";
+
+pub fn run(raw_args: &[String]) -> Result<(), String> {
+    let args = CliArgs::parse(raw_args)?;
+    args.expect_flags(&["board", "game", "hands", "json"])?;
+    let game_type = args.game()?;
+    let board = args.cards("board", Owner::Community)?;
+
+    let holes = args
+        .positional
+        .iter()
+        .chain(args.values("hands").iter())
+        .map(|hand| cards(hand, Owner::Player))
+        .collect::<Result<Vec<Vec<Card>>, String>>()?;
+    if holes.len() < 2 {
+        return Err(format!("usage: {USAGE}"));
+    }
+
+    let hands: Vec<Vec<Card>> = holes
+        .iter()
+        .map(|hole| hole.iter().chain(board.iter()).copied().collect())
+        .collect();
+    let best_hands = hands
+        .iter()
+        .map(|hand| best_hand(hand, game_type))
+        .collect::<Result<Vec<_>, _>>()?;
+    let winners = compare_hands(hands, game_type)?;
+
+    if args.switch("json") {
+        let output = json!({
+            "game": game_type.to_string(),
+            "board": notation(&board),
+            "hands": holes.iter().zip(best_hands.iter()).map(|(hole, best)| json!({
+                "hole": notation(hole),
+                "hand_type": best.hand_type.to_string(),
+                "best_cards": best.cards.iter().map(|card| card.notation()).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "winners": winners,
+        });
+        println!("{output}");
+    } else {
+        println!("Game:  {}", game_type);
+        println!("Board: {}", notation(&board));
+        for (idx, (hole, best)) in holes.iter().zip(best_hands.iter()).enumerate() {
+            let marker = if winners.contains(&idx) { "*" } else { " " };
+            println!(
+                "{} Player {}: {:<12} {} ({})",
+                marker,
+                idx + 1,
+                notation(hole),
+                best.hand_type,
+                notation(&best.cards)
+            );
+        }
+        let names: Vec<String> = winners.iter().map(|idx| format!("Player {}", idx + 1)).collect();
+        println!("Winners: {}", names.join(", "));
+    }
+    Ok(())
+}
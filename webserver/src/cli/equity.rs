@@ -0,0 +1,131 @@
+use serde_json::json;
+
+use crate::cli::args::{cards, notation, CliArgs};
+use mini_poker_core::card::{parse_cards, Owner};
+use mini_poker_core::equity::{calculate_equity, EquityConfig, EquityResult};
+use mini_poker_core::game::GameType;
+use mini_poker_core::range::{range_equity, HandRange, RangeEquityConfig};
+
+pub const USAGE: &str = "equity <hand|range|??> <hand|range|??>... [--board cards] [--dead cards]
+\t\t[--game holdem|omaha] [--iterations n] [--exact-limit n] [--seed n] [--threads n] [--json]";
+
+// Placeholder for a player whose hole cards are unknown
+const UNKNOWN_HAND: &str = "??";
+
+pub fn run(raw_args: &[String]) -> Result<(), String> {
+    let args = CliArgs::parse(raw_args)?;
+    args.expect_flags(&[
+        "board",
+        "dead",
+        "exact-limit",
+        "game",
+        "iterations",
+        "json",
+        "seed",
+        "threads",
+    ])?;
+    if args.positional.len() < 2 {
+        return Err(format!("usage: {USAGE}"));
+    }
+
+    let game_type = args.game()?;
+    let board = args.cards("board", Owner::Community)?;
+    let dead_cards = args.cards("dead", Owner::Community)?;
+    let exact_limit = args.number("exact-limit", 250_000)?;
+    let iterations = args.number("iterations", 100_000)?;
+    let seed = args.number("seed", 0)?;
+    let threads = args.number("threads", 0)?;
+
+    // Anything that is not plain card notation is read as a range
+    let is_range = |player: &String| {
+        player != UNKNOWN_HAND && parse_cards(player, Owner::Player).is_err()
+    };
+
+    let result = if args.positional.iter().any(is_range) {
+        if !matches!(game_type, GameType::TexasHoldemPoker) {
+            return Err("ranges are only supported for holdem".to_string());
+        }
+        let ranges = args
+            .positional
+            .iter()
+            .map(|player| match player.as_str() {
+                UNKNOWN_HAND => Ok(HandRange::any_two()),
+                _ if is_range(player) => HandRange::parse(player),
+                _ => HandRange::from_cards(&cards(player, Owner::Player)?),
+            }
+            .map_err(|err| format!("{err}: {player}")))
+            .collect::<Result<Vec<HandRange>, String>>()?;
+        range_equity(&RangeEquityConfig {
+            ranges,
+            board: board.clone(),
+            dead_cards,
+            exact_limit,
+            iterations,
+            seed,
+            threads,
+        })?
+    } else {
+        let hands = args
+            .positional
+            .iter()
+            .map(|player| match player.as_str() {
+                UNKNOWN_HAND => Ok(Vec::new()),
+                _ => cards(player, Owner::Player),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        calculate_equity(&EquityConfig {
+            game_type,
+            hands,
+            board: board.clone(),
+            dead_cards,
+            exact_limit,
+            iterations,
+            seed,
+            threads,
+        })?
+    };
+
+    print_result(&args, game_type, &notation(&board), &result);
+    Ok(())
+}
+
+fn print_result(args: &CliArgs, game_type: GameType, board: &str, result: &EquityResult) {
+    if args.switch("json") {
+        let output = json!({
+            "game": game_type.to_string(),
+            "board": board,
+            "method": result.method.to_string(),
+            "samples": result.samples,
+            "dropped": result.dropped,
+            "players": args.positional.iter().zip(result.players.iter()).map(|(hand, player)| json!({
+                "hand": hand,
+                "win": player.win,
+                "tie": player.tie,
+                "equity": player.equity,
+                "margin": player.margin,
+            })).collect::<Vec<_>>(),
+        });
+        println!("{output}");
+        return;
+    }
+
+    println!("Game:   {}", game_type);
+    println!("Board:  {}", board);
+    println!("Method: {} ({} runouts)", result.method, result.samples);
+    if result.dropped > 0 {
+        println!(
+            "Dropped {} samples that found no deal without clashing cards",
+            result.dropped
+        );
+    }
+    for (hand, player) in args.positional.iter().zip(result.players.iter()) {
+        println!(
+            "{:<24} win {:>6.2}%  tie {:>6.2}%  equity {:>6.2}% ± {:.2}%",
+            hand,
+            player.win * 100.0,
+            player.tie * 100.0,
+            player.equity * 100.0,
+            player.margin * 100.0
+        );
+    }
+}
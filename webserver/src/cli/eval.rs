@@ -0,0 +1,40 @@
+use serde_json::json;
+
+use crate::cli::args::{cards, notation, CliArgs};
+use mini_poker_core::card::Owner;
+use mini_poker_core::hand::{best_hand, hand_score};
+
+pub const USAGE: &str = "eval <hole cards> [board] [--game holdem|omaha] [--json]";
+
+pub fn run(raw_args: &[String]) -> Result<(), String> {
+    let args = CliArgs::parse(raw_args)?;
+    args.expect_flags(&["game", "json"])?;
+    let game_type = args.game()?;
+
+    let (hole, board) = match args.positional.as_slice() {
+        [hole] => (cards(hole, Owner::Player)?, Vec::new()),
+        [hole, board] => (cards(hole, Owner::Player)?, cards(board, Owner::Community)?),
+        _ => return Err(format!("usage: {USAGE}")),
+    };
+    let hand: Vec<_> = hole.iter().chain(board.iter()).copied().collect();
+    let best = best_hand(&hand, game_type)?;
+
+    if args.switch("json") {
+        let output = json!({
+            "game": game_type.to_string(),
+            "hole": notation(&hole),
+            "board": notation(&board),
+            "hand_type": best.hand_type.to_string(),
+            "best_cards": best.cards.iter().map(|card| card.notation()).collect::<Vec<_>>(),
+            "sorted_ranks": best.sorted_ranks,
+            "score": hand_score(best.hand_type, &best.sorted_ranks),
+        });
+        println!("{output}");
+    } else {
+        println!("Game:      {}", game_type);
+        println!("Hole:      {}", notation(&hole));
+        println!("Board:     {}", notation(&board));
+        println!("Best hand: {} ({})", best.hand_type, notation(&best.cards));
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use serde_json::json;
+
+use crate::cli::args::CliArgs;
+use mini_poker_core::icm::{chip_chop, deal_prizes, icm_equity, DealMethod};
+use mini_poker_core::tournament::payout_percentages;
+
+pub const USAGE: &str = "icm <stack> <stack>... [--payouts prize,prize,...] [--json]";
+
+pub fn run(raw_args: &[String]) -> Result<(), String> {
+    let args = CliArgs::parse(raw_args)?;
+    args.expect_flags(&["json", "payouts"])?;
+    if args.positional.len() < 2 {
+        return Err(format!("usage: {USAGE}"));
+    }
+
+    let stacks = args
+        .positional
+        .iter()
+        .map(|stack| {
+            stack
+                .parse()
+                .map_err(|_| format!("stacks are chip counts, got {stack}"))
+        })
+        .collect::<Result<Vec<u32>, String>>()?;
+    // Percentages of a sit-and-go with as many players by default
+    let payouts = match args.value("payouts")? {
+        Some(text) => text
+            .split(',')
+            .map(|prize| {
+                prize
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("--payouts expects numbers, got {prize}"))
+            })
+            .collect::<Result<Vec<u32>, String>>()?,
+        None => payout_percentages(stacks.len()).to_vec(),
+    };
+
+    let icm = icm_equity(&stacks, &payouts)?;
+    let chop = chip_chop(&stacks, &payouts)?;
+
+    if args.switch("json") {
+        let icm_deal = deal_prizes(DealMethod::Icm, &stacks, &payouts)?;
+        let chop_deal = deal_prizes(DealMethod::ChipChop, &stacks, &payouts)?;
+        let output = json!({
+            "payouts": payouts,
+            "players": (0..stacks.len()).map(|player| json!({
+                "stack": stacks[player],
+                "icm": icm[player],
+                "chip_chop": chop[player],
+                "icm_deal": icm_deal[player],
+                "chip_chop_deal": chop_deal[player],
+            })).collect::<Vec<_>>(),
+        });
+        println!("{output}");
+        return Ok(());
+    }
+
+    let total: u32 = stacks.iter().sum();
+    let prizes: Vec<String> = payouts.iter().map(u32::to_string).collect();
+    println!("Payouts: {}", prizes.join(", "));
+    for (player, ((stack, icm), chop)) in stacks.iter().zip(icm).zip(chop).enumerate() {
+        println!(
+            "Player {:<3} stack {:>9} ({:>6.2}%)  ICM {:>10.2}  chip chop {:>10.2}",
+            player + 1,
+            stack,
+            *stack as f64 * 100.0 / total.max(1) as f64,
+            icm,
+            chop
+        );
+    }
+    Ok(())
+}
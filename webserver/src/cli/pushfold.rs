@@ -0,0 +1,37 @@
+use crate::cli::args::CliArgs;
+use mini_poker_core::pushfold::{
+    preflop_equities, solve_push_fold, PushFoldConfig, DEFAULT_ITERATIONS,
+};
+
+pub const USAGE: &str = "pushfold <stack bb> [--ante bb] [--samples n] [--iterations n]
+\t\t[--seed n] [--threads n] [--json]";
+
+pub fn run(raw_args: &[String]) -> Result<(), String> {
+    let args = CliArgs::parse(raw_args)?;
+    args.expect_flags(&["ante", "iterations", "json", "samples", "seed", "threads"])?;
+    let [stack] = args.positional.as_slice() else {
+        return Err(format!("usage: {USAGE}"));
+    };
+    let stack: f64 = stack
+        .parse()
+        .map_err(|_| format!("stack is a number of big blinds, got {stack}"))?;
+
+    let equities = preflop_equities(
+        args.number("samples", 1_000)?,
+        args.number("seed", 0)?,
+        args.number("threads", 0)?,
+    )?;
+    let config = PushFoldConfig {
+        stack,
+        ante: args.number("ante", 0.0)?,
+        iterations: args.number("iterations", DEFAULT_ITERATIONS)?,
+    };
+    let chart = solve_push_fold(&equities, &config)?;
+
+    if args.switch("json") {
+        println!("{}", chart.to_json());
+    } else {
+        print!("{}", chart.to_text());
+    }
+    Ok(())
+}
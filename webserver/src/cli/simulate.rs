@@ -0,0 +1,75 @@
+use crate::cli::args::CliArgs;
+use crate::cli::cfr::load_bot_policy;
+use mini_poker_core::simulate::{simulate, Seat, SimulationConfig};
+use mini_poker_core::strategy::BotStrategy;
+use mini_poker_core::variant::variant;
+
+pub const USAGE: &str = "simulate <random|tag|calling-station|push-fold|cfr>... [--game mode]
+\t\t[--hands n] [--stack chips] [--seed n] [--policy file] [--json]";
+
+/// Starting stack when none is given, in big blinds
+const DEFAULT_STACK_BB: u32 = 100;
+
+pub fn run(raw_args: &[String]) -> Result<(), String> {
+    let args = CliArgs::parse(raw_args)?;
+    args.expect_flags(&["game", "hands", "json", "policy", "seed", "stack"])?;
+    if args.positional.len() < 2 {
+        return Err(format!("usage: {USAGE}"));
+    }
+
+    let game = args.value("game")?.unwrap_or("texas-holdem");
+    let game = variant(game).map_err(|err| format!("{err}: {game}"))?;
+    let mut seats = args
+        .positional
+        .iter()
+        .map(|name| {
+            let strategy: BotStrategy = name.parse()?;
+            Ok(Seat {
+                name: strategy.to_string(),
+                strategy: strategy.strategy(),
+            })
+        })
+        .collect::<Result<Vec<Seat>, &'static str>>()?;
+    match args.value("policy")? {
+        Some(path) => load_bot_policy(path)?,
+        None if args.positional.iter().any(|name| name == "cfr") => {
+            return Err("cfr seats need --policy".to_string());
+        }
+        None => {}
+    }
+    let config = SimulationConfig {
+        hands: args.number("hands", 10_000)?,
+        starting_stack: args.number(
+            "stack",
+            DEFAULT_STACK_BB * game.betting_structure().big_blind,
+        )?,
+        seed: args.number("seed", 0)?,
+    };
+
+    let report = simulate(game.as_ref(), &mut seats, &config)?;
+    if args.switch("json") {
+        let output = serde_json::to_string(&report).map_err(|err| err.to_string())?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    println!("Game:  {}", report.game);
+    println!("Hands: {} (big blind {})", report.hands, report.big_blind);
+    for result in report.strategies.iter() {
+        println!(
+            "{:<18} seats {:<2} bb/100 {:>9.2} ± {:<8.2} net {:>10}  showdown {:>10}  non-showdown {:>10}",
+            result.name,
+            result.seats,
+            result.bb_per_100,
+            result.bb_per_100_margin,
+            result.net,
+            result.showdown_net,
+            result.non_showdown_net,
+        );
+    }
+    match report.conservation_errors {
+        0 => println!("Chips conserved in every hand"),
+        errors => println!("Chips not conserved in {errors} hands"),
+    }
+    Ok(())
+}
@@ -1,4 +0,0 @@
-pub mod card;
-pub mod hand;
-pub mod game;
-pub mod combinations;
@@ -1,17 +0,0 @@
-use std::fmt::{Display, Formatter};
-
-#[derive(Clone, Copy)]
-pub enum GameType {
-    TexasHoldemPoker = 0,
-    OmahaPoker
-}
-
-
-impl Display for GameType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result{
-        match self {
-            Self::TexasHoldemPoker => write!(f, "Texas Holdem Poker"),
-            Self::OmahaPoker => write!(f, "Omaha Poker")
-        }
-    }
-}
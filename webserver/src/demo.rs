@@ -1,2 +1,4 @@
 pub mod combinations;
 pub mod hand_evaluation;
+pub mod equity;
+pub mod range;
@@ -1,5 +1,5 @@
-use crate::core::card::{Card, Owner, Rank, Suit};
-use crate::core::combinations::combinations;
+use mini_poker_core::card::{Card, Owner, Rank, Suit};
+use mini_poker_core::combinations::combinations;
 
 fn show_combination(combo: &[Card]) {
     for card in combo {
@@ -0,0 +1,87 @@
+use mini_poker_core::card::{Card, Owner, Rank, Suit};
+use mini_poker_core::equity::{calculate_equity, EquityConfig};
+use mini_poker_core::game::GameType;
+
+fn card(rank: Rank, suit: Suit) -> Card {
+    Card {
+        rank,
+        suit,
+        owner: Owner::Player,
+    }
+}
+
+fn show_cards(cards: &[Card]) -> String {
+    if cards.is_empty() {
+        return "??".to_string();
+    }
+    cards
+        .iter()
+        .map(|card| format!("{}", card))
+        .collect::<Vec<String>>()
+        .join("/ ")
+}
+
+fn run_equity_demo(title: &str, config: EquityConfig) {
+    println!("\n{} ", title);
+    println!("Board: {}", show_cards(&config.board));
+    match calculate_equity(&config) {
+        Ok(result) => {
+            println!("Method: {} ({} runouts)", result.method, result.samples);
+            for (hand, player) in config.hands.iter().zip(result.players.iter()) {
+                println!(
+                    "{:<40} win {:>6.2}%  tie {:>6.2}%  equity {:>6.2}% ± {:.2}%",
+                    show_cards(hand),
+                    player.win * 100.0,
+                    player.tie * 100.0,
+                    player.equity * 100.0,
+                    player.margin * 100.0
+                );
+            }
+        }
+        Err(message) => println!("Error: {}", message),
+    }
+}
+
+pub fn equity_demo() {
+    let aces = vec![card(Rank::Ace, Suit::Hearts), card(Rank::Ace, Suit::Diamonds)];
+    let kings = vec![card(Rank::King, Suit::Spades), card(Rank::King, Suit::Clubs)];
+    let flop = vec![
+        card(Rank::Two, Suit::Clubs),
+        card(Rank::Seven, Suit::Diamonds),
+        card(Rank::King, Suit::Hearts),
+    ];
+
+    run_equity_demo(
+        "TEXAS HOLD'EM PREFLOP (AA vs KK) -----",
+        EquityConfig::new(
+            GameType::TexasHoldemPoker,
+            vec![aces.clone(), kings.clone()],
+            Vec::new(),
+        ),
+    );
+
+    run_equity_demo(
+        "TEXAS HOLD'EM FLOP (AA vs KK) --------",
+        EquityConfig::new(
+            GameType::TexasHoldemPoker,
+            vec![aces.clone(), kings.clone()],
+            flop.clone(),
+        ),
+    );
+
+    run_equity_demo(
+        "TEXAS HOLD'EM FLOP (AA vs random) ----",
+        EquityConfig::new(GameType::TexasHoldemPoker, vec![aces, Vec::new()], flop.clone()),
+    );
+
+    let omaha_hand = vec![
+        card(Rank::Ace, Suit::Spades),
+        card(Rank::King, Suit::Spades),
+        card(Rank::Queen, Suit::Hearts),
+        card(Rank::Jack, Suit::Hearts),
+    ];
+    run_equity_demo(
+        "OMAHA FLOP (AKQJ ds vs random) -------",
+        EquityConfig::new(GameType::OmahaPoker, vec![omaha_hand, Vec::new()], flop),
+    );
+}
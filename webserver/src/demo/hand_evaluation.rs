@@ -1,6 +1,6 @@
-use crate::core::card::{Card, Owner, Rank, Suit};
-use crate::core::game::GameType;
-use crate::core::hand::{compare_hands, evaluate_hand, evaluate_hand_comb};
+use mini_poker_core::card::{Card, Owner, Rank, Suit};
+use mini_poker_core::game::GameType;
+use mini_poker_core::hand::{compare_hands, evaluate_hand, evaluate_omaha_hand};
 
 fn show_hand(hand: &[Card]) {
     for card in hand {
@@ -70,7 +70,7 @@ pub fn hand_evaluation_demo() {
 
 pub fn omaha_evaluation_demo() {
     // 4 hole cards (Player) + 5 community cards
-    let mut hand = vec![
+    let hand = vec![
         Card {
             rank: Rank::Ace,
             suit: Suit::Spades,
@@ -122,7 +122,7 @@ pub fn omaha_evaluation_demo() {
     show_hand(&hand);
     println!("-------------------------------------");
 
-    match evaluate_hand_comb(&mut hand, 10) {
+    match evaluate_omaha_hand(&hand) {
         Ok((hand_type, sorted_ranks)) => {
             println!("Best Hand Type: {}", hand_type);
             print!("Sorted rank values: ");
@@ -0,0 +1,73 @@
+use mini_poker_core::card::{parse_cards, Owner};
+use mini_poker_core::equity::EquityConfig;
+use mini_poker_core::game::GameType;
+use mini_poker_core::range::{equity_matrix, range_equity, HandRange, RangeEquityConfig};
+
+fn run_range_demo(title: &str, labels: &[&str], config: RangeEquityConfig) {
+    println!("\n{}", title);
+    match range_equity(&config) {
+        Ok(result) => {
+            println!("Method: {} ({} runouts)", result.method, result.samples);
+            for (label, player) in labels.iter().zip(result.players.iter()) {
+                println!(
+                    "{:<30} win {:>6.2}%  tie {:>6.2}%  equity {:>6.2}% ± {:.2}%",
+                    label,
+                    player.win * 100.0,
+                    player.tie * 100.0,
+                    player.equity * 100.0,
+                    player.margin * 100.0
+                );
+            }
+        }
+        Err(message) => println!("Error: {}", message),
+    }
+}
+
+pub fn range_demo() {
+    let notation = "QQ+, AKs, A5s-A2s, KQo";
+    let range = HandRange::parse(notation).expect("valid range");
+    let hand = parse_cards("AhKd", Owner::Player).expect("valid cards");
+    let board = parse_cards("Kc7d2s", Owner::Community).expect("valid cards");
+
+    println!("RANGE -------------------------------");
+    println!("{} => {} combos", notation, range.len());
+    println!(
+        "After removing AhKd + Kc7d2s => {} combos",
+        range
+            .without_cards(&[hand.clone(), board.clone()].concat())
+            .len()
+    );
+
+    run_range_demo(
+        "RANGE VS HAND (preflop) -------------",
+        &[notation, "AhKd"],
+        RangeEquityConfig::new(
+            vec![range.clone(), HandRange::from_cards(&hand).expect("valid hand")],
+            Vec::new(),
+        ),
+    );
+
+    run_range_demo(
+        "RANGE VS RANGE (flop) ---------------",
+        &[notation, "22-99, ATs+"],
+        RangeEquityConfig::new(
+            vec![
+                range.clone(),
+                HandRange::parse("22-99, ATs+").expect("valid range"),
+            ],
+            board.clone(),
+        ),
+    );
+
+    println!("\nEQUITY MATRIX (AhKd vs range, flop) -");
+    let template = EquityConfig::new(GameType::TexasHoldemPoker, Vec::new(), board);
+    match equity_matrix(&hand, &range, &template) {
+        Ok(matrix) => {
+            println!("CSV:");
+            print!("{}", matrix.to_csv());
+            println!("JSON:");
+            println!("{}", matrix.to_json());
+        }
+        Err(message) => println!("Error: {}", message),
+    }
+}
@@ -1,17 +1,53 @@
 use std::env;
+use std::process;
 
-use crate::core::game::GameType;
+#[cfg(feature = "server")]
+use mini_poker_core::rake::Rake;
+#[cfg(feature = "server")]
+use mini_poker_core::rotation::{game_rotation, RotationPeriod, MIXED_GAME_NAMES};
+#[cfg(feature = "server")]
+use mini_poker_core::strategy::BotStrategy;
+#[cfg(feature = "server")]
+use mini_poker_core::tournament::{Bounty, BountyType, TournamentConfig};
+#[cfg(feature = "server")]
+use mini_poker_core::variant::{AnteType, ForcedBets, VARIANT_NAMES};
+#[cfg(feature = "server")]
+use server::game::gameroom::GameRoomSettings;
+#[cfg(feature = "server")]
+use server::game::mtt::MttConfig;
 
-mod core;
+mod cli;
 mod demo;
+#[cfg(feature = "server")]
 mod server;
 
 const ARGS_MESSAGE: &str = "\
 Required params:
 
-\tserver <mode>
+\tserver <mode> [hands|orbit] [--ante n] [--ante-type every-player|big-blind|button]
+\t\t[--straddle utg|mississippi] [--straddle-acts-last]
+\t\t[--sit-and-go seats] [--stack n] [--buy-in n] [--level-seconds n]
+\t\t[--mtt entrants] [--table-size n] [--bounty n] [--bounty-type knockout|progressive]
+\t\t[--late-registration levels] [--reentry] [--rebuy-levels n] [--add-on chips]
+\t\t[--break-seconds n] [--rake percent] [--rake-cap players:cap,...] [--no-flop-no-drop]
+\t\t[--bots n] [--bot-strategy random|tag|calling-station|push-fold|cfr]
+\t\t[--bot-policy file]
 \t\ttexas-holdem
 \t\tomaha
+\t\tomaha-5
+\t\tomaha-6
+\t\tomaha-hi-lo
+\t\tshort-deck
+\t\tseven-card-stud
+\t\trazz
+\t\tfive-card-draw
+\t\t2-7-triple-draw
+\t\tlimit-holdem
+\t\tlimit-omaha-hi-lo
+\t\tstud-hi-lo
+\t\thorse
+\t\t8-game
+\t\t<mode>,<mode>,... (mixed game, rotating every orbit by default)
 
  \tdemo <mode>
  \t\thand_eval
@@ -19,31 +55,281 @@ Required params:
  \t\tcompare_holdem
  \t\tcompare_omaha
  \t\tcombinations
+ \t\tequity
+ \t\trange
 ";
 
-#[tokio::main]
-async fn main() {
+fn print_usage() {
+    println!("{ARGS_MESSAGE}");
+    println!("\t{}\n", cli::eval::USAGE);
+    println!("\t{}\n", cli::compare::USAGE);
+    println!("\t{}\n", cli::equity::USAGE);
+    println!("\t{}\n", cli::icm::USAGE);
+    println!("\t{}\n", cli::simulate::USAGE);
+    println!("\t{}\n", cli::pushfold::USAGE);
+    println!("\t{}", cli::cfr::USAGE);
+}
+
+fn main() {
     let args: Vec<String> = env::args().collect();
-    assert!(args.len() == 3, "{ARGS_MESSAGE}");
+    if args.len() < 2 {
+        print_usage();
+        return;
+    }
 
-    if args[1] == "server" {
-        handle_server(args[2].clone()).await;
-    } else if args[1] == "demo" {
+    if args[1] == "server" && args.len() >= 3 {
+        handle_server(&args[2..]);
+    } else if args[1] == "demo" && args.len() == 3 {
         handle_demo(args[2].clone());
+    } else if args[1] == "eval" {
+        handle_cli(cli::eval::run(&args[2..]));
+    } else if args[1] == "compare" {
+        handle_cli(cli::compare::run(&args[2..]));
+    } else if args[1] == "equity" {
+        handle_cli(cli::equity::run(&args[2..]));
+    } else if args[1] == "icm" {
+        handle_cli(cli::icm::run(&args[2..]));
+    } else if args[1] == "simulate" {
+        handle_cli(cli::simulate::run(&args[2..]));
+    } else if args[1] == "pushfold" {
+        handle_cli(cli::pushfold::run(&args[2..]));
+    } else if args[1] == "cfr" {
+        handle_cli(cli::cfr::run(&args[2..]));
     } else {
-        println!("{ARGS_MESSAGE}");
+        print_usage();
     }
 }
 
-async fn handle_server(mode: String) {
-    if mode == "texas-holdem" {
-        server::http::start(vec![GameType::TexasHoldemPoker]).await;
-    } else if mode == "omaha" {
-        server::http::start(vec![GameType::OmahaPoker]).await;
-    } else {
+fn handle_cli(result: Result<(), String>) {
+    if let Err(message) = result {
+        eprintln!("Error: {message}");
+        process::exit(1);
+    }
+}
+
+#[cfg(feature = "server")]
+fn handle_server(raw_args: &[String]) {
+    let (mode, period, forced_bets, tournament, table_size, rake, bots) =
+        match server_args(raw_args) {
+            Ok(server_args) => server_args,
+            Err(err) => {
+                eprintln!("Invalid server options: {err}");
+                return;
+            }
+        };
+    let Ok(mut rotation) = game_rotation(&mode, period) else {
         eprintln!("Invalid mode: {mode}");
-        eprintln!("Valid modes:\n\ttexas-holdem\n\tomaha")
+        eprintln!(
+            "Valid modes:\n\t{}\n\t{}",
+            VARIANT_NAMES.join("\n\t"),
+            MIXED_GAME_NAMES.join("\n\t")
+        );
+        return;
+    };
+    rotation.forced_bets = forced_bets;
+
+    let (rooms, tournaments) = match (tournament, table_size) {
+        (Some(tournament), Some(table_size)) => (
+            Vec::new(),
+            vec![MttConfig {
+                mode,
+                period,
+                forced_bets,
+                tournament,
+                table_size,
+            }],
+        ),
+        (tournament, _) => (
+            vec![GameRoomSettings {
+                rotation,
+                tournament,
+                coordinator: None,
+                rake,
+                bots,
+            }],
+            Vec::new(),
+        ),
+    };
+    tokio::runtime::Runtime::new()
+        .expect("Failed to start tokio runtime")
+        .block_on(server::http::start(rooms, tournaments));
+}
+
+/// Game mode, mixed game rotation, table antes and straddles, the
+/// tournament played if any, with the table size of a multi-table one, the
+/// rake of a cash game and the bots seated at the table
+#[cfg(feature = "server")]
+type ServerArgs = (
+    String,
+    RotationPeriod,
+    ForcedBets,
+    Option<TournamentConfig>,
+    Option<usize>,
+    Option<Rake>,
+    Vec<BotStrategy>,
+);
+
+#[cfg(feature = "server")]
+fn server_args(raw_args: &[String]) -> Result<ServerArgs, String> {
+    let args = cli::args::CliArgs::parse(raw_args)?;
+    args.expect_flags(&[
+        "ante",
+        "ante-type",
+        "straddle",
+        "straddle-acts-last",
+        "sit-and-go",
+        "stack",
+        "buy-in",
+        "level-seconds",
+        "mtt",
+        "table-size",
+        "bounty",
+        "bounty-type",
+        "late-registration",
+        "reentry",
+        "rebuy-levels",
+        "add-on",
+        "break-seconds",
+        "rake",
+        "rake-cap",
+        "no-flop-no-drop",
+        "bots",
+        "bot-strategy",
+        "bot-policy",
+    ])?;
+
+    let (mode, period) = match args.positional.as_slice() {
+        [mode] => (mode.clone(), RotationPeriod::Orbit),
+        [mode, period] => (mode.clone(), period.parse()?),
+        _ => return Err("expected a mode and an optional rotation".to_string()),
+    };
+    let forced_bets = ForcedBets {
+        ante: match args.value("ante")? {
+            Some(_) => Some(args.number("ante", 0)?),
+            None => None,
+        },
+        ante_type: args
+            .value("ante-type")?
+            .map_or(Ok(AnteType::EveryPlayer), str::parse)?,
+        straddle: args.value("straddle")?.map(str::parse).transpose()?,
+        straddle_acts_last: args.switch("straddle-acts-last"),
+    };
+    let (format, table_size) = match args.value("mtt")? {
+        Some(_) if args.value("sit-and-go")?.is_some() => {
+            return Err("--sit-and-go and --mtt cannot be combined".to_string())
+        }
+        Some(_) => ("mtt", Some(args.number("table-size", 9)?)),
+        None => ("sit-and-go", None),
+    };
+    let tournament = match args.value(format)? {
+        Some(_) => {
+            let defaults = TournamentConfig::sit_and_go(args.number(format, 0)?);
+            if defaults.seats < 2 {
+                return Err(format!("--{format} needs at least 2 players"));
+            }
+            let late_registration = args.number("late-registration", 0)?;
+            let rebuy_levels = args.number("rebuy-levels", 0)?;
+            if args.switch("reentry") && late_registration == 0 {
+                return Err("--reentry needs --late-registration".to_string());
+            }
+            if args.value("add-on")?.is_some() && rebuy_levels == 0 {
+                return Err("--add-on needs --rebuy-levels".to_string());
+            }
+            Some(TournamentConfig {
+                starting_stack: args.number("stack", defaults.starting_stack)?,
+                buy_in: args.number("buy-in", defaults.buy_in)?,
+                level_duration: args.number("level-seconds", defaults.level_duration)?,
+                bounty: match args.value("bounty")? {
+                    Some(_) => Some(Bounty {
+                        bounty_type: args
+                            .value("bounty-type")?
+                            .map_or(Ok(BountyType::Knockout), str::parse)?,
+                        amount: args.number("bounty", 0)?,
+                    }),
+                    None => None,
+                },
+                late_registration,
+                reentry: args.switch("reentry"),
+                rebuy_levels,
+                add_on: match args.value("add-on")? {
+                    Some(_) => Some(args.number("add-on", 0)?),
+                    None => None,
+                },
+                break_duration: args.number("break-seconds", defaults.break_duration)?,
+                ..defaults
+            })
+        }
+        None => None,
+    };
+    let rake = match args.value("rake")? {
+        Some(_) if tournament.is_some() => {
+            return Err("tournaments are not raked".to_string());
+        }
+        Some(percentage) => Some(Rake {
+            percentage: percentage
+                .parse()
+                .ok()
+                .filter(|percentage| (0.0..=100.0).contains(percentage))
+                .ok_or(format!("--rake expects a percentage, got {percentage}"))?,
+            caps: match args.value("rake-cap")? {
+                Some(text) => text
+                    .split(',')
+                    .map(|cap| {
+                        cap.trim()
+                            .split_once(':')
+                            .and_then(|(players, cap)| {
+                                Some((players.parse().ok()?, cap.parse().ok()?))
+                            })
+                            .ok_or(format!("--rake-cap expects players:cap, got {cap}"))
+                    })
+                    .collect::<Result<Vec<(usize, u32)>, String>>()?,
+                None => Vec::new(),
+            },
+            no_flop_no_drop: args.switch("no-flop-no-drop"),
+        }),
+        None if args.value("rake-cap")?.is_some() || args.switch("no-flop-no-drop") => {
+            return Err("--rake-cap and --no-flop-no-drop need --rake".to_string());
+        }
+        None => None,
+    };
+    let bots = match args.value("bots")? {
+        Some(_) if table_size.is_some() => {
+            return Err("multi-table tournaments seat their own players".to_string());
+        }
+        Some(_) => {
+            let strategy = args
+                .value("bot-strategy")?
+                .map_or(Ok(BotStrategy::Random), str::parse)?;
+            vec![strategy; args.number("bots", 0)?]
+        }
+        None if args.value("bot-strategy")?.is_some() => {
+            return Err("--bot-strategy needs --bots".to_string());
+        }
+        None => Vec::new(),
+    };
+    // Bots added to rooms later on follow the same policy
+    match args.value("bot-policy")? {
+        Some(path) => cli::cfr::load_bot_policy(path)?,
+        None if bots.contains(&BotStrategy::Cfr) => {
+            return Err("--bot-strategy cfr needs --bot-policy".to_string());
+        }
+        None => {}
     }
+    Ok((
+        mode,
+        period,
+        forced_bets,
+        tournament,
+        table_size,
+        rake,
+        bots,
+    ))
+}
+
+#[cfg(not(feature = "server"))]
+fn handle_server(_args: &[String]) {
+    eprintln!("mini-poker was built without the `server` feature");
+    process::exit(1);
 }
 
 fn handle_demo(mode: String) {
@@ -57,10 +343,14 @@ fn handle_demo(mode: String) {
         demo::hand_evaluation::compare_hands_omaha_demo();
     } else if mode == "combinations" {
         demo::combinations::combinations_demo();
+    } else if mode == "equity" {
+        demo::equity::equity_demo();
+    } else if mode == "range" {
+        demo::range::range_demo();
     } else {
         eprintln!("Invalid mode: {mode}");
         eprintln!(
-            "Valid modes:\n\thand_eval\n\thand_eval_omaha\n\tcompare_holdem\n\tcompare_omaha\n\tcombinations"
+            "Valid modes:\n\thand_eval\n\thand_eval_omaha\n\tcompare_holdem\n\tcompare_omaha\n\tcombinations\n\tequity\n\trange"
         );
     }
 }
@@ -0,0 +1,352 @@
+use crate::server::game::gameroom::{GameRoomMessage, PlayerAction, PlayerGameAction};
+use crate::server::game::player::{CardOwnerDTO, PlayerMessage, PlayerWarningType};
+use mini_poker_core::card::{Card, Owner};
+use mini_poker_core::game::PokerStep;
+use mini_poker_core::pushfold::bot_equities;
+use mini_poker_core::strategy::{BotStrategy, BotView, Decision, Strategy};
+use mini_poker_core::variant::{variant_by_display_name, AnteType, GameVariant};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Pause before a bot acts, so people at the table can follow the action
+const THINKING_TIME: Duration = Duration::from_millis(400);
+
+/// Seat played by the server. It follows the hand through the messages a
+/// connected player gets and answers with the same actions
+struct Bot {
+    id: Uuid,
+    strategy: Box<dyn Strategy>,
+    rng: StdRng,
+    /// Game of the hand being played, none until the table announces one
+    game: Option<Box<dyn GameVariant>>,
+    /// Game the table announced it moves on to after the hand that follows
+    /// the announcement, with whether that hand has started
+    upcoming_game: Option<(Box<dyn GameVariant>, bool)>,
+    step: PokerStep,
+    hole_cards: Vec<Card>,
+    board: Vec<Card>,
+    /// Chips each player put in this hand
+    bets: HashMap<Uuid, u32>,
+    /// Chips each player started the hand with
+    stacks: HashMap<Uuid, u32>,
+    /// Ante each player posted this hand
+    ante: u32,
+    /// Decisions taken in each betting round of the hand
+    actions: Vec<Vec<Decision>>,
+    bet_base: u32,
+    dead_money: u32,
+    big_blind: u32,
+    betting_round: usize,
+    /// Players dealt into the hand who have not folded, the bot included
+    in_hand: Vec<Uuid>,
+    /// Whether the bot owes the table an action
+    to_act: bool,
+}
+
+impl Bot {
+    fn new(id: Uuid, strategy: Box<dyn Strategy>) -> Self {
+        Self {
+            id,
+            strategy,
+            rng: StdRng::from_os_rng(),
+            game: None,
+            upcoming_game: None,
+            step: PokerStep::Blind,
+            hole_cards: Vec::new(),
+            board: Vec::new(),
+            bets: HashMap::new(),
+            stacks: HashMap::new(),
+            ante: 0,
+            actions: Vec::new(),
+            bet_base: 0,
+            dead_money: 0,
+            big_blind: 0,
+            betting_round: 0,
+            in_hand: Vec::new(),
+            to_act: false,
+        }
+    }
+
+    fn start_hand(&mut self) {
+        match self.upcoming_game.take() {
+            Some((game, true)) => self.switch_game(game),
+            Some((game, false)) => self.upcoming_game = Some((game, true)),
+            None => {}
+        }
+        self.hole_cards.clear();
+        self.board.clear();
+        self.bets.clear();
+        self.stacks.clear();
+        self.ante = 0;
+        self.actions.clear();
+        self.bet_base = 0;
+        self.dead_money = 0;
+        self.betting_round = 0;
+        self.to_act = false;
+    }
+
+    fn switch_game(&mut self, game: Box<dyn GameVariant>) {
+        self.big_blind = game.betting_structure().big_blind;
+        self.game = Some(game);
+    }
+
+    fn bet(&mut self, player_id: Uuid, amount: u32) {
+        let bet = self.bets.entry(player_id).or_default();
+        *bet += amount;
+        self.bet_base = self.bet_base.max(*bet);
+    }
+
+    /// Follows the hand, returning the bot's answer when one is due
+    fn handle_message(&mut self, message: PlayerMessage) -> Option<PlayerAction> {
+        match message {
+            PlayerMessage::Step { step } => {
+                if matches!(step, PokerStep::Blind | PokerStep::Ante) {
+                    self.start_hand();
+                } else if self.step == PokerStep::BettingRound {
+                    self.betting_round += 1;
+                }
+                self.step = step;
+            }
+            PlayerMessage::BettingPlayers { players } => self.in_hand = players,
+            PlayerMessage::GameState { players, .. } => {
+                self.stacks = players
+                    .iter()
+                    .map(|player| (player.id, player.funds))
+                    .collect();
+            }
+            PlayerMessage::Ante { amount } => {
+                self.ante = amount;
+                for player_id in self.in_hand.clone() {
+                    self.bet(player_id, amount);
+                }
+            }
+            PlayerMessage::Blind {
+                small_blind_player,
+                big_blind_player,
+                small_blind_amount,
+                big_blind_amount,
+                ante_amount,
+                ante_type,
+                ante_player,
+                straddle_player,
+                straddle_amount,
+            } => {
+                if ante_type == AnteType::EveryPlayer {
+                    for player_id in self.in_hand.clone() {
+                        self.bet(player_id, ante_amount);
+                    }
+                    self.ante = ante_amount;
+                }
+                // A single ante grows the pot as much as each player in it
+                // posting their share
+                if ante_player.is_some() {
+                    self.dead_money += ante_amount;
+                    self.ante = ante_amount / self.in_hand.len().max(1) as u32;
+                }
+                self.bet(small_blind_player, small_blind_amount);
+                self.bet(big_blind_player, big_blind_amount);
+                if let Some(straddle_player) = straddle_player {
+                    self.bet(straddle_player, straddle_amount);
+                }
+                self.big_blind = big_blind_amount;
+            }
+            PlayerMessage::BringIn { player_id, amount } => self.bet(player_id, amount),
+            PlayerMessage::TournamentState { blinds, .. } => self.big_blind = blinds.big_blind,
+            PlayerMessage::CardDeal {
+                cards,
+                owner,
+                player_id,
+            } => {
+                let cards = cards.iter().filter_map(|card| card.card(Owner::Player));
+                match owner {
+                    CardOwnerDTO::Player if player_id == Some(self.id) => {
+                        self.hole_cards.extend(cards)
+                    }
+                    CardOwnerDTO::Community => self.board.extend(cards.map(|card| Card {
+                        owner: Owner::Community,
+                        ..card
+                    })),
+                    CardOwnerDTO::Player => {}
+                }
+            }
+            PlayerMessage::PlayerAction {
+                player_id,
+                action,
+                bet_base,
+            } => {
+                match action {
+                    PlayerGameAction::Fold => self.in_hand.retain(|&id| id != player_id),
+                    PlayerGameAction::Call => {
+                        self.bets.insert(player_id, self.bet_base);
+                    }
+                    PlayerGameAction::Raise(_) => {
+                        self.bets.insert(player_id, bet_base);
+                    }
+                    _ => {}
+                }
+                let decision = match action {
+                    PlayerGameAction::Fold => Some(Decision::Fold),
+                    PlayerGameAction::Check => Some(Decision::Check),
+                    PlayerGameAction::Call => Some(Decision::Call),
+                    PlayerGameAction::Raise(amount) => Some(Decision::Raise(amount)),
+                    _ => None,
+                };
+                if let Some(decision) = decision {
+                    if self.actions.len() <= self.betting_round {
+                        self.actions.resize(self.betting_round + 1, Vec::new());
+                    }
+                    self.actions[self.betting_round].push(decision);
+                }
+                self.bet_base = bet_base;
+                if player_id == self.id {
+                    self.to_act = false;
+                }
+            }
+            PlayerMessage::PlayerTurnTimeout { player } => {
+                self.in_hand.retain(|&id| id != player);
+            }
+            // The first announcement is the game the bot was seated at
+            PlayerMessage::UpcomingGame { game, .. } => match variant_by_display_name(&game) {
+                Some(game) if self.game.is_none() => self.switch_game(game),
+                game => self.upcoming_game = game.map(|game| (game, false)),
+            },
+            PlayerMessage::Turn { player_id, .. } if player_id == self.id => {
+                self.to_act = true;
+                return Some(self.decide());
+            }
+            // A refused action is replaced by the one that is always taken
+            PlayerMessage::Warning {
+                warning_type: PlayerWarningType::InvalidAction,
+                ..
+            } if self.to_act => {
+                self.to_act = false;
+                return Some(match self.step {
+                    PokerStep::Draw => PlayerAction::Discard { cards: Vec::new() },
+                    _ => PlayerAction::Call,
+                });
+            }
+            PlayerMessage::DealOffer { .. } => {
+                return Some(PlayerAction::DealVote { accept: true });
+            }
+            // Bots run it once, so people all in against them are not kept
+            // waiting for an answer
+            PlayerMessage::RunItOffer { players, .. } if players.contains(&self.id) => {
+                return Some(PlayerAction::RunIt { times: 1 });
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn decide(&mut self) -> PlayerAction {
+        let Some(game) = self.game.as_deref() else {
+            return match self.step {
+                PokerStep::Draw => PlayerAction::Discard { cards: Vec::new() },
+                _ => PlayerAction::Call,
+            };
+        };
+        let bet = self.bets.get(&self.id).copied().unwrap_or(0);
+        let stack_of = |id: &Uuid| self.stacks.get(id).copied().unwrap_or(0);
+        let view = BotView {
+            game,
+            hole_cards: &self.hole_cards,
+            board: &self.board,
+            to_call: self.bet_base.saturating_sub(bet),
+            pot: self.dead_money + self.bets.values().sum::<u32>(),
+            big_blind: self.big_blind,
+            betting_round: self.betting_round,
+            opponents: self.in_hand.iter().filter(|&&id| id != self.id).count(),
+            stack: stack_of(&self.id).saturating_sub(bet),
+            bet,
+            opponent_stack: self
+                .in_hand
+                .iter()
+                .filter(|&&id| id != self.id)
+                .map(stack_of)
+                .max()
+                .unwrap_or(0),
+            ante: self.ante,
+            actions: &self.actions,
+        };
+        if self.step != PokerStep::Draw {
+            return match self.strategy.act(&view, &mut self.rng) {
+                Decision::Fold => PlayerAction::Fold,
+                Decision::Check => PlayerAction::Check,
+                Decision::Call => PlayerAction::Call,
+                Decision::Raise(amount) => PlayerAction::Raise { amount },
+            };
+        }
+
+        let discards = self.strategy.discard(&view, &mut self.rng);
+        // Replacements come in a card deal of their own
+        self.hole_cards.retain(|card| !discards.contains(card));
+        PlayerAction::Discard {
+            cards: discards.iter().map(Card::notation).collect(),
+        }
+    }
+}
+
+/// Plays the bot's seat until the room drops it
+async fn bot_loop(
+    mut bot: Bot,
+    mut receiver: mpsc::Receiver<PlayerMessage>,
+    gameroom_sender: mpsc::Sender<GameRoomMessage>,
+) {
+    while let Some(message) = receiver.recv().await {
+        if matches!(message, PlayerMessage::TerminateSession) {
+            break;
+        }
+        // Deciding can mean solving a push/fold chart, which takes seconds,
+        // so turns are played off the runtime
+        let is_turn =
+            matches!(message, PlayerMessage::Turn { player_id, .. } if player_id == bot.id);
+        let payload = if is_turn {
+            let decided = tokio::task::spawn_blocking(move || {
+                let payload = bot.handle_message(message);
+                (bot, payload)
+            });
+            let Ok((returned, payload)) = decided.await else {
+                break;
+            };
+            bot = returned;
+            payload
+        } else {
+            bot.handle_message(message)
+        };
+        if let Some(payload) = payload {
+            tokio::time::sleep(THINKING_TIME).await;
+            let message = GameRoomMessage::PlayerAction {
+                payload,
+                from: bot.id,
+            };
+            if gameroom_sender.send(message).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Starts a bot playing `strategy`, returning the sender the room reaches it
+/// through
+pub fn spawn_bot(
+    id: Uuid,
+    strategy: BotStrategy,
+    gameroom_sender: mpsc::Sender<GameRoomMessage>,
+) -> mpsc::Sender<PlayerMessage> {
+    // Push/fold charts are solved from equities that take seconds to
+    // estimate, which is best done off the runtime before the first turn
+    if strategy == BotStrategy::PushFold {
+        tokio::task::spawn_blocking(bot_equities);
+    }
+    let (sender, receiver) = mpsc::channel(100);
+    tokio::spawn(bot_loop(
+        Bot::new(id, strategy.strategy()),
+        receiver,
+        gameroom_sender,
+    ));
+    sender
+}
@@ -1,21 +1,46 @@
-use crate::core::card::{Card, Owner, DECK};
-use crate::core::game::GameType;
-use crate::core::hand::compare_hands;
+use crate::server::game::bot::spawn_bot;
+use crate::server::game::ledger::HouseLedger;
+use crate::server::game::mtt::{MttMessage, TableOrder};
 use crate::server::game::player::{
-    CardDealDTO, CardOwnerDTO, CardReveallDTO, HandRevealDTO, PlayerMessage, PlayerSession,
-    PlayerWarningType,
+    CardDealDTO, CardOwnerDTO, CardReveallDTO, GamePlayerStateDTO, HandRecordDTO,
+    HandRecordPlayerDTO, HandRevealDTO, PlayerEquityDTO, PlayerMessage, PlayerSession,
+    PlayerWarningType, PotResultDTO,
 };
+use crate::server::game::tournament::{BountyDTO, BuyWindows, Deal, TournamentProgress};
+use crate::server::game::wallet::Wallets;
 use axum::extract::ws::WebSocket;
+use mini_poker_core::card::{parse_cards, Card, Owner};
+use mini_poker_core::engine::{Hand, HandSeat, Seated, Settlement};
+use mini_poker_core::equity::runout_equity;
+use mini_poker_core::game::PokerStep;
+use mini_poker_core::hand::low_hand_name;
+use mini_poker_core::icm::DealMethod;
+use mini_poker_core::pot::{all_in_ev, side_pots, Pot, PotAward};
+use mini_poker_core::rake::{attribute_rake, Rake};
+use mini_poker_core::rotation::{GameRotation, RotationPeriod};
+use mini_poker_core::strategy::{BotStrategy, Decision};
+use mini_poker_core::tournament::{BlindLevel, Bounty, Purchase, TournamentConfig};
+use mini_poker_core::variant::{BettingStructure, GameVariant};
 use rand;
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::time::Instant;
 use uuid::Uuid;
 
+/// Boards dealt to estimate all in equity when there are more to enumerate
+const EQUITY_SAMPLES: u64 = 20_000;
+/// Finished hands a room remembers
+const HISTORY_SIZE: usize = 100;
+/// Seconds players have to answer a deal
+const DEAL_TIMEOUT: u64 = 30;
+/// Seconds all in players have to agree how many times to run the board
+const RUN_IT_TIMEOUT: u64 = 10;
+
 #[derive(Clone)]
 struct GameRoomPlayer {
     id: uuid::Uuid,
@@ -23,41 +48,151 @@ struct GameRoomPlayer {
     state: GameRoomPlayerState,
 }
 
+impl Seated for GameRoomPlayer {
+    fn seat(&self) -> &HandSeat {
+        &self.state.seat
+    }
+
+    fn seat_mut(&mut self) -> &mut HandSeat {
+        &mut self.state.seat
+    }
+}
+
 struct GameRoom {
+    id: Uuid,
     players: Vec<GameRoomPlayer>,
     state: GameRoomState,
-    game_type: GameType,
+    rotation: GameRotation,
+    /// Game of the rotation being played
+    game_idx: usize,
+    /// Hands the current game has left before the table moves on, none
+    /// until its first hand
+    hands_left: Option<usize>,
+    betting: BettingStructure,
     turn_duration: u16,
-    min_bet: u32,
     min_funds: u32,
+    /// Latest hands, oldest first
+    history: VecDeque<HandRecordDTO>,
+    /// Sit-and-go played at the table, none for cash games
+    tournament: Option<TournamentProgress>,
+    /// Multi-table tournament the table belongs to, none otherwise
+    coordinator: Option<mpsc::Sender<MttMessage>>,
+    /// Level the tournament coordinator last told the table to play
+    blind_level: Option<BlindLevel>,
+    /// Bounties of the tournament played, none without them
+    bounty: Option<Bounty>,
+    /// What the coordinator last let players buy at the table
+    buy_windows: BuyWindows,
+    wallets: Wallets,
+    /// Taken from the pots of cash games, none for rake free rooms
+    rake: Option<Rake>,
+    ledger: HouseLedger,
 }
 
 impl GameRoom {
+    fn variant(&self) -> &dyn GameVariant {
+        self.rotation.games[self.game_idx].as_ref()
+    }
+
+    /// Game of the hand being played, for work done without the room's lock
+    fn shared_variant(&self) -> Arc<dyn GameVariant> {
+        self.rotation.games[self.game_idx].clone()
+    }
+
     async fn broadcast(self: &Self, message: PlayerMessage) {
         for player in self.players.iter() {
             _ = player.sender.send(message.clone()).await;
         }
     }
+
+    /// What tournament players can buy, nothing in cash games
+    fn buy_windows(&self) -> BuyWindows {
+        match self.tournament.as_ref() {
+            Some(tournament) => tournament.buy_windows(),
+            None => self.buy_windows,
+        }
+    }
+
+    /// Tells a player joining the table the game its next hand is played in
+    async fn announce_game(&self, sender: &mpsc::Sender<PlayerMessage>) {
+        let n_games = self.rotation.games.len();
+        let game_idx = match self.hands_left {
+            Some(0) => (self.game_idx + 1) % n_games,
+            _ => self.game_idx,
+        };
+        let game = &self.rotation.games[game_idx];
+        _ = sender
+            .send(PlayerMessage::UpcomingGame {
+                game: game.name().to_string(),
+                limit: game.betting_structure().limit,
+            })
+            .await;
+    }
+
+    async fn warn(&self, player_id: Uuid, message: &str) {
+        if let Some(player) = self.players.iter().find(|player| player.id == player_id) {
+            _ = player
+                .sender
+                .send(PlayerMessage::Warning {
+                    warning_type: PlayerWarningType::InvalidAction,
+                    message: message.to_string(),
+                })
+                .await;
+        }
+    }
 }
 
 struct GameRoomState {
-    deck: [Card; 52],
-    community_cards: Vec<Card>,
-    big_blind_idx: u8,
-    dealt_card_offset: usize,
-    bet_base: u32,
+    /// Deck, board and betting of the hand being played
+    hand: Hand,
+    /// Community cards when the players were all in, none until then
+    all_in_board: Option<Vec<Card>>,
+    /// Every board of a hand run more than once, empty otherwise
+    runouts: Vec<Vec<Card>>,
+    /// Players all in asked how many times to run the board, empty unless
+    /// their answers are awaited
+    run_it_offer: Vec<Uuid>,
+    /// Bounties collected since the tournament last heard of them
+    bounties: Vec<BountyDTO>,
+    /// Rebuys and add-ons bought since the tournament last heard of them
+    purchases: Vec<(Uuid, Purchase)>,
     current_player_turn: Option<Uuid>,
     current_player_timeout: Option<SystemTime>,
 }
 
+impl GameRoomState {
+    /// Steps left out once the players are all in: nobody can bet, and a
+    /// board run more than once is already dealt
+    fn skips(&self, step: PokerStep) -> bool {
+        match step {
+            PokerStep::BettingRound => self.all_in_board.is_some(),
+            PokerStep::Flop | PokerStep::Turn | PokerStep::River => !self.runouts.is_empty(),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Clone)]
 struct GameRoomPlayerState {
     is_playing: bool,
-    is_betting: bool,
-    dealt_cards: Vec<Card>,
-    bet: u32,
+    /// Cards, bet and stack in the hand being played
+    seat: HandSeat,
     action: PlayerGameAction,
-    funds: u32,
+    /// Cards picked to throw away on the current draw
+    discards: Vec<Card>,
+    /// Whether the player posts the straddle when in the straddle seat
+    straddle: bool,
+    /// Times the player agreed to run the board at the all in being
+    /// played, none until they answer
+    run_it: Option<u32>,
+    /// Funds before the hand's forced bets
+    starting_funds: u32,
+    /// Bounty on the player's head in bounty tournaments
+    bounty: u32,
+    /// Chips bought since the last hand, added before the next one
+    bought_chips: u32,
+    /// Whether the player took the add-on
+    add_on: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -67,6 +202,8 @@ pub enum PlayerGameAction {
     Check,
     Call,
     Raise(u32),
+    /// Number of cards drawn, which cards is only known to the player
+    Discard(u32),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -76,6 +213,15 @@ pub enum PlayerAction {
     Check,
     Call,
     Raise { amount: u32 },
+    Discard { cards: Vec<String> },
+    Straddle { enabled: bool },
+    RunIt { times: u32 },
+    ProposeDeal { method: DealMethod },
+    DealVote { accept: bool },
+    Rebuy,
+    AddOn,
+    Reenter,
+    HandHistory,
     Pong { client_ts: u64, server_ts: u64 },
     Update { is_playing: bool },
 }
@@ -89,6 +235,13 @@ pub enum GameRoomMessage {
         id: uuid::Uuid,
         sender: mpsc::Sender<PlayerMessage>,
     },
+    /// Seats a tournament player with their stack
+    SeatPlayer {
+        id: uuid::Uuid,
+        sender: mpsc::Sender<PlayerMessage>,
+        funds: u32,
+        bounty: u32,
+    },
 }
 
 struct GameRoomStateNotification {
@@ -96,37 +249,85 @@ struct GameRoomStateNotification {
 }
 
 struct GameRoomConfig {
-    min_bet: u32,
+    id: Uuid,
     min_funds: u32,
     turn_duration: u16,
-    game_type: GameType,
+    rotation: GameRotation,
+    tournament: Option<TournamentConfig>,
+    coordinator: Option<mpsc::Sender<MttMessage>>,
+    rake: Option<Rake>,
+    wallets: Wallets,
+    ledger: HouseLedger,
+}
+
+/// What a room plays
+pub struct GameRoomSettings {
+    pub rotation: GameRotation,
+    pub tournament: Option<TournamentConfig>,
+    /// Set on the tables of a multi-table tournament
+    pub coordinator: Option<mpsc::Sender<MttMessage>>,
+    /// Cash games only
+    pub rake: Option<Rake>,
+    /// Bots seated when the room opens, one per strategy
+    pub bots: Vec<BotStrategy>,
 }
 
 impl GameRoom {
     fn new(config: GameRoomConfig) -> Self {
         let players = Vec::new();
         let state = GameRoomState {
-            deck: DECK,
-            community_cards: Vec::new(),
-            big_blind_idx: 0,
-            dealt_card_offset: 0,
-            bet_base: 0,
+            hand: Hand::default(),
+            all_in_board: None,
+            runouts: Vec::new(),
+            run_it_offer: Vec::new(),
+            bounties: Vec::new(),
+            purchases: Vec::new(),
             current_player_turn: None,
             current_player_timeout: None,
         };
 
+        // Tournament players keep playing down to their last chip
+        let is_tournament = config.tournament.is_some() || config.coordinator.is_some();
         assert!(
-            config.min_funds >= 2 * config.min_bet,
+            is_tournament
+                || config
+                    .rotation
+                    .games
+                    .iter()
+                    .all(|game| config.min_funds >= game.betting_structure().big_blind),
             "Min funds should be more or equal than big blind"
         );
+        let betting = config
+            .rotation
+            .forced_bets
+            .apply(config.rotation.games[0].betting_structure());
+        let bounty = config
+            .tournament
+            .as_ref()
+            .and_then(|tournament| tournament.bounty);
 
         Self {
+            id: config.id,
             players,
             state,
-            game_type: config.game_type,
-            min_bet: config.min_bet,
-            min_funds: config.min_funds,
+            rotation: config.rotation,
+            game_idx: 0,
+            hands_left: None,
+            betting,
+            min_funds: match is_tournament {
+                true => 1,
+                false => config.min_funds,
+            },
             turn_duration: config.turn_duration,
+            history: VecDeque::new(),
+            tournament: config.tournament.map(TournamentProgress::new),
+            coordinator: config.coordinator,
+            blind_level: None,
+            bounty,
+            buy_windows: BuyWindows::default(),
+            wallets: config.wallets,
+            rake: config.rake,
+            ledger: config.ledger,
         }
     }
 
@@ -137,6 +338,12 @@ impl GameRoom {
     ) {
         match message {
             GameRoomMessage::PlayerJoin { id, sender } => {
+                // Tournament players get the starting stack when registering
+                let funds = match self.tournament {
+                    Some(_) => 0,
+                    None => 1_000,
+                };
+                self.announce_game(&sender).await;
                 match self.players.iter_mut().find(|player| player.id == id) {
                     Some(player) => {
                         player.sender = sender;
@@ -147,19 +354,74 @@ impl GameRoom {
                             sender,
                             state: GameRoomPlayerState {
                                 is_playing: false,
-                                is_betting: false,
-                                dealt_cards: Vec::new(),
-                                bet: 0,
+                                seat: HandSeat {
+                                    funds,
+                                    ..HandSeat::default()
+                                },
                                 action: PlayerGameAction::None,
-                                funds: 1_000,
+                                discards: Vec::new(),
+                                straddle: false,
+                                run_it: None,
+                                starting_funds: funds,
+                                bounty: 0,
+                                bought_chips: 0,
+                                add_on: false,
                             },
                         });
                     }
                 }
             }
+            GameRoomMessage::SeatPlayer {
+                id,
+                sender,
+                funds,
+                bounty,
+            } => {
+                _ = sender
+                    .send(PlayerMessage::TableAssigned { table_id: self.id })
+                    .await;
+                self.announce_game(&sender).await;
+                self.players.retain(|player| player.id != id);
+                self.players.push(GameRoomPlayer {
+                    id,
+                    sender,
+                    state: GameRoomPlayerState {
+                        is_playing: true,
+                        seat: HandSeat {
+                            funds,
+                            ..HandSeat::default()
+                        },
+                        action: PlayerGameAction::None,
+                        discards: Vec::new(),
+                        straddle: false,
+                        run_it: None,
+                        starting_funds: funds,
+                        bounty,
+                        bought_chips: 0,
+                        add_on: false,
+                    },
+                });
+            }
             GameRoomMessage::PlayerAction { from, payload } => {
                 println!("Gameroom received {:?} from Player {}", payload, from);
 
+                let n_playing = self
+                    .players
+                    .iter()
+                    .filter(|player| player.state.is_playing)
+                    .count();
+                // Mixed tables seat as many players as their smallest game
+                let Some(smallest_game) = self
+                    .rotation
+                    .games
+                    .iter()
+                    .min_by_key(|game| game.max_players())
+                else {
+                    return;
+                };
+                let max_players = smallest_game.max_players();
+                let game_name = smallest_game.name();
+
                 let mut _player = self.players.iter_mut().find(|player| player.id == from);
                 if _player.is_none() {
                     return;
@@ -167,8 +429,79 @@ impl GameRoom {
                 let player = _player.unwrap();
 
                 match payload {
+                    PlayerAction::Update { .. } if self.coordinator.is_some() => {
+                        _ = player
+                            .sender
+                            .send(PlayerMessage::Warning {
+                                warning_type: PlayerWarningType::InvalidAction,
+                                message: "Seats are assigned by the tournament".to_string(),
+                            })
+                            .await;
+                    }
+                    PlayerAction::Update { is_playing } if self.tournament.is_some() => {
+                        let Some(tournament) = self.tournament.as_mut() else {
+                            return;
+                        };
+                        let seats = tournament.config.seats.min(max_players);
+                        let fee = tournament.config.price(Purchase::Entry);
+                        if tournament.has_started() && is_playing && !player.state.is_playing {
+                            self.enter(from, Purchase::Entry, max_players).await;
+                        } else if tournament.has_started() {
+                            // Players cannot leave, they are blinded away instead
+                            _ = player
+                                .sender
+                                .send(PlayerMessage::Warning {
+                                    warning_type: PlayerWarningType::InvalidAction,
+                                    message: "The tournament has already started".to_string(),
+                                })
+                                .await;
+                        } else if is_playing && !player.state.is_playing {
+                            let balance = match self.wallets.debit(from, fee).await {
+                                Ok(balance) => balance,
+                                Err(message) => {
+                                    self.warn(from, message).await;
+                                    return;
+                                }
+                            };
+                            player.state.is_playing = true;
+                            player.state.seat.funds = tournament.config.starting_stack;
+                            player.state.bounty = tournament.head(player.id);
+                            _ = player.sender.send(PlayerMessage::Wallet { balance }).await;
+                            if n_playing + 1 >= seats {
+                                let entrants = self
+                                    .players
+                                    .iter()
+                                    .filter_map(|player| {
+                                        player.state.is_playing.then_some(player.id)
+                                    })
+                                    .collect();
+                                tournament.start(entrants);
+                                let message = tournament.state_message(n_playing + 1);
+                                self.broadcast(message).await;
+                            }
+                        } else if !is_playing && player.state.is_playing {
+                            // Unregistering before the start refunds the entry
+                            let balance = self.wallets.credit(from, fee).await;
+                            player.state.is_playing = false;
+                            player.state.seat.funds = 0;
+                            player.state.bounty = 0;
+                            _ = player.sender.send(PlayerMessage::Wallet { balance }).await;
+                        }
+                    }
                     PlayerAction::Update { is_playing } => {
-                        if is_playing && player.state.funds >= self.min_funds {
+                        if is_playing && !player.state.is_playing && n_playing >= max_players {
+                            // The deck cannot deal another hand, e.g. PLO6 past 7 seats
+                            _ = player
+                                .sender
+                                .send(PlayerMessage::Warning {
+                                    warning_type: PlayerWarningType::InvalidAction,
+                                    message: format!(
+                                        "{} is limited to {} players",
+                                        game_name, max_players
+                                    ),
+                                })
+                                .await;
+                        } else if is_playing && player.state.seat.funds >= self.min_funds {
                             player.state.is_playing = is_playing;
                         }
                     }
@@ -204,6 +537,142 @@ impl GameRoom {
                             })
                             .await;
                     }
+                    PlayerAction::Discard { cards } => {
+                        // Short notation, e.g. ["As", "Td"], none to stand pat
+                        let discards = parse_cards(&cards.concat(), Owner::Player)
+                            .map_err(|err| err.to_string())
+                            .and_then(|discards| {
+                                let mut indexes: Vec<usize> =
+                                    discards.iter().map(Card::index).collect();
+                                indexes.sort_unstable();
+                                indexes.dedup();
+                                let in_hand = discards.iter().all(|discard| {
+                                    player
+                                        .state
+                                        .seat
+                                        .dealt_cards
+                                        .iter()
+                                        .any(|card| card.index() == discard.index())
+                                });
+                                if indexes.len() != discards.len() || !in_hand {
+                                    Err("Can only discard cards in your hand".to_string())
+                                } else {
+                                    Ok(discards)
+                                }
+                            });
+                        match discards {
+                            Ok(discards) => {
+                                player.state.action =
+                                    PlayerGameAction::Discard(discards.len() as u32);
+                                player.state.discards = discards;
+                                _ = notification_sender
+                                    .send(GameRoomStateNotification {
+                                        content: "player updated".to_string(),
+                                    })
+                                    .await;
+                            }
+                            Err(message) => {
+                                _ = player
+                                    .sender
+                                    .send(PlayerMessage::Warning {
+                                        warning_type: PlayerWarningType::InvalidAction,
+                                        message,
+                                    })
+                                    .await;
+                            }
+                        }
+                    }
+                    PlayerAction::Straddle { enabled } => {
+                        player.state.straddle = enabled;
+                    }
+                    PlayerAction::RunIt { times } if self.state.run_it_offer.contains(&from) => {
+                        player.state.run_it = Some(times.max(1));
+                    }
+                    PlayerAction::RunIt { .. } => {
+                        _ = player
+                            .sender
+                            .send(PlayerMessage::Warning {
+                                warning_type: PlayerWarningType::InvalidAction,
+                                message: "There is no run it offer to answer".to_string(),
+                            })
+                            .await;
+                    }
+                    PlayerAction::HandHistory => {
+                        _ = player
+                            .sender
+                            .send(PlayerMessage::HandHistory {
+                                hands: self.history.iter().cloned().collect(),
+                            })
+                            .await;
+                    }
+                    PlayerAction::ProposeDeal { method } => {
+                        let warning = match self.tournament.as_mut() {
+                            None => Some("Deals can only be made in a sit-and-go"),
+                            Some(tournament)
+                                if !tournament.is_running() || !player.state.is_playing =>
+                            {
+                                Some("Only players left in the tournament can propose a deal")
+                            }
+                            Some(tournament) if tournament.deal.is_some() => {
+                                Some("A deal is already being negotiated")
+                            }
+                            Some(tournament) => {
+                                tournament.deal = Some(Deal::new(method, from));
+                                None
+                            }
+                        };
+                        if let Some(message) = warning {
+                            _ = player
+                                .sender
+                                .send(PlayerMessage::Warning {
+                                    warning_type: PlayerWarningType::InvalidAction,
+                                    message: message.to_string(),
+                                })
+                                .await;
+                        }
+                    }
+                    PlayerAction::DealVote { accept } => {
+                        let Some(tournament) = self.tournament.as_mut() else {
+                            return;
+                        };
+                        let Some(deal) = tournament.deal.as_mut().filter(|deal| {
+                            deal.is_offered()
+                                && deal.shares.iter().any(|share| share.player_id == from)
+                        }) else {
+                            _ = player
+                                .sender
+                                .send(PlayerMessage::Warning {
+                                    warning_type: PlayerWarningType::InvalidAction,
+                                    message: "There is no deal to answer".to_string(),
+                                })
+                                .await;
+                            return;
+                        };
+                        if !accept {
+                            tournament.deal = None;
+                        } else if !deal.accepted.contains(&from) {
+                            deal.accepted.push(from);
+                        }
+                        self.broadcast(PlayerMessage::DealVote {
+                            player_id: from,
+                            accept,
+                        })
+                        .await;
+                    }
+                    PlayerAction::Rebuy => self.buy_chips(from, Purchase::Rebuy).await,
+                    PlayerAction::AddOn => self.buy_chips(from, Purchase::AddOn).await,
+                    PlayerAction::Reenter if self.tournament.is_some() => {
+                        self.enter(from, Purchase::Reentry, max_players).await;
+                    }
+                    PlayerAction::Reenter => {
+                        _ = player
+                            .sender
+                            .send(PlayerMessage::Warning {
+                                warning_type: PlayerWarningType::InvalidAction,
+                                message: "Re-entries are made with the tournament".to_string(),
+                            })
+                            .await;
+                    }
                     PlayerAction::Pong {
                         client_ts,
                         server_ts,
@@ -227,64 +696,206 @@ impl GameRoom {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(rename_all = "snake_case")]
-pub enum PokerStep {
-    Blind,
-    PreFlop,
-    Flop,
-    Turn,
-    River,
-    Showdown,
-    BettingRound,
-}
+impl GameRoom {
+    /// Seats a sit-and-go player registering late or entering again after
+    /// busting, with a starting stack
+    async fn enter(&mut self, player_id: Uuid, purchase: Purchase, max_players: usize) {
+        let windows = self.buy_windows();
+        let n_playing = self
+            .players
+            .iter()
+            .filter(|player| player.state.is_playing)
+            .count();
+        let Some(tournament) = self.tournament.as_mut() else {
+            return;
+        };
+        let Some(player) = self
+            .players
+            .iter_mut()
+            .find(|player| player.id == player_id)
+        else {
+            return;
+        };
+        let entries = tournament.entries(player_id);
+        let checked = match purchase {
+            _ if n_playing >= max_players => Err("The table is full"),
+            Purchase::Entry if !windows.late_registration => Err("Registration is closed"),
+            Purchase::Entry if entries > 0 => Err("Busted players re-enter instead"),
+            Purchase::Reentry if !windows.reentry => Err("Re-entry is closed"),
+            Purchase::Reentry if entries == 0 || player.state.is_playing => {
+                Err("Only busted players can re-enter")
+            }
+            _ => Ok(tournament.config.price(purchase)),
+        };
+        let balance = match checked {
+            Ok(price) => self.wallets.debit(player_id, price).await,
+            Err(message) => Err(message),
+        };
+        let balance = match balance {
+            Ok(balance) => balance,
+            Err(message) => {
+                self.warn(player_id, message).await;
+                return;
+            }
+        };
 
-async fn handle_step_blind(gameroom: &mut GameRoom) {
-    {
-        let mut rng = rand::rng();
-        gameroom.state.deck.shuffle(&mut rng);
+        tournament.buy(player_id, purchase);
+        let chips = tournament.config.starting_stack;
+        // Players out of the hand being played, the stack is theirs at once
+        player.state.is_playing = true;
+        player.state.seat.funds = chips;
+        player.state.bounty = tournament.head(player_id);
+        player.state.add_on = false;
+        _ = player.sender.send(PlayerMessage::Wallet { balance }).await;
+        let message = tournament.state_message(n_playing + 1);
+        self.broadcast(PlayerMessage::Purchase {
+            player_id,
+            purchase,
+            chips,
+        })
+        .await;
+        self.broadcast(message).await;
     }
-    gameroom.state.community_cards.clear();
 
-    for player in gameroom.players.iter_mut() {
-        if player.state.is_playing && player.state.funds < gameroom.min_funds {
-            player.state.is_playing = false;
+    /// Charges a tournament player for a rebuy or the add-on, the chips are
+    /// added before the next hand
+    async fn buy_chips(&mut self, player_id: Uuid, purchase: Purchase) {
+        let windows = self.buy_windows();
+        let n_playing = self
+            .players
+            .iter()
+            .filter(|player| player.state.is_playing)
+            .count();
+        let Some(player) = self
+            .players
+            .iter_mut()
+            .find(|player| player.id == player_id)
+        else {
+            return;
+        };
+        let stack = player.state.seat.funds + player.state.bought_chips;
+        let chips = match purchase {
+            Purchase::Rebuy => windows.rebuy,
+            _ => windows.add_on,
+        };
+        let checked = match chips {
+            _ if !player.state.is_playing => Err("Only players in the tournament can buy chips"),
+            None if purchase == Purchase::Rebuy => Err("Rebuys are closed"),
+            None => Err("The add-on is only available at the break"),
+            Some(_) if purchase == Purchase::Rebuy && stack > windows.max_rebuy_stack => {
+                Err("Rebuys are only allowed at or below the starting stack")
+            }
+            Some(_) if purchase == Purchase::AddOn && player.state.add_on => {
+                Err("The add-on can only be taken once")
+            }
+            Some(chips) => Ok(chips),
+        };
+        let bought = match checked {
+            Ok(chips) => self
+                .wallets
+                .debit(player_id, windows.price)
+                .await
+                .map(|balance| (chips, balance)),
+            Err(message) => Err(message),
+        };
+        let (chips, balance) = match bought {
+            Ok(bought) => bought,
+            Err(message) => {
+                self.warn(player_id, message).await;
+                return;
+            }
+        };
+
+        player.state.bought_chips += chips;
+        player.state.add_on |= purchase == Purchase::AddOn;
+        _ = player.sender.send(PlayerMessage::Wallet { balance }).await;
+        // Tables of a multi-table tournament report purchases between hands
+        let message = match self.tournament.as_mut() {
+            Some(tournament) => {
+                tournament.buy(player_id, purchase);
+                Some(tournament.state_message(n_playing))
+            }
+            None => {
+                self.state.purchases.push((player_id, purchase));
+                None
+            }
+        };
+        self.broadcast(PlayerMessage::Purchase {
+            player_id,
+            purchase,
+            chips,
+        })
+        .await;
+        if let Some(message) = message {
+            self.broadcast(message).await;
         }
-        player.state.is_betting = player.state.is_playing;
-        player.state.dealt_cards.clear();
-        player.state.bet = 0;
     }
+}
 
-    let n_players = gameroom.players.iter().len() as u8;
-    let small_blind_idx = gameroom.state.big_blind_idx % n_players as u8;
-    gameroom.state.big_blind_idx = (small_blind_idx + 1) % n_players as u8;
-
-    gameroom.state.bet_base = gameroom.min_bet * 2;
+/// Counts the hand about to start against the current game of a mixed table,
+/// moving on to the next game first when its turn is over. Players are told
+/// the upcoming game at the start of the last hand before a switch
+async fn rotate_game(gameroom: &mut GameRoom) {
+    let n_games = gameroom.rotation.games.len();
+    if n_games < 2 {
+        return;
+    }
 
-    let small_blind_id;
-    let big_blind_id;
-    match gameroom.players.get_mut(small_blind_idx as usize) {
-        Some(player) => {
-            player.state.bet = gameroom.min_bet;
-            player.state.funds -= gameroom.min_bet;
-            small_blind_id = player.id;
-        }
-        None => {
-            panic!("Blind not found")
-        }
+    if gameroom.hands_left == Some(0) {
+        gameroom.game_idx = (gameroom.game_idx + 1) % n_games;
+        gameroom.betting = gameroom
+            .rotation
+            .forced_bets
+            .apply(gameroom.variant().betting_structure());
+        gameroom.hands_left = None;
     }
-    match gameroom
-        .players
-        .get_mut(gameroom.state.big_blind_idx as usize)
-    {
-        Some(player) => {
-            player.state.bet = 2 * gameroom.min_bet;
-            player.state.funds -= 2 * gameroom.min_bet;
-            big_blind_id = player.id;
-        }
-        None => {
-            panic!("Blind not found")
+    let hands = match gameroom.rotation.period {
+        RotationPeriod::Hands(hands) => hands,
+        RotationPeriod::Orbit => gameroom
+            .players
+            .iter()
+            .filter(|player| player.state.is_playing)
+            .count()
+            .max(1),
+    };
+    let hands_left = gameroom.hands_left.unwrap_or(hands) - 1;
+    gameroom.hands_left = Some(hands_left);
+
+    if hands_left == 0 {
+        let next_game = &gameroom.rotation.games[(gameroom.game_idx + 1) % n_games];
+        let message = PlayerMessage::UpcomingGame {
+            game: next_game.name().to_string(),
+            limit: next_game.betting_structure().limit,
+        };
+        gameroom.broadcast(message).await;
+    }
+}
+
+/// Shuffles and seats the players for the new hand
+async fn start_hand(gameroom: &mut GameRoom, step: PokerStep) {
+    let mut deck = gameroom.variant().deck();
+    deck.shuffle(&mut rand::rng());
+    gameroom.state.hand.start(deck);
+    gameroom.state.all_in_board = None;
+    gameroom.state.runouts.clear();
+    gameroom.state.run_it_offer.clear();
+
+    let max_players = gameroom.variant().max_players();
+    // Busted tournament players sit out while they can still rebuy
+    let can_rebuy = gameroom.buy_windows().rebuy.is_some();
+    let mut seated = 0;
+    for player in gameroom.players.iter_mut() {
+        let has_funds = player.state.seat.funds >= gameroom.min_funds;
+        if player.state.is_playing && !has_funds && !can_rebuy {
+            player.state.is_playing = false;
         }
+        player.state.seat.is_betting = player.state.is_playing && has_funds && seated < max_players;
+        seated += player.state.seat.is_betting as usize;
+        player.state.seat.dealt_cards.clear();
+        player.state.seat.face_up.clear();
+        player.state.discards.clear();
+        player.state.seat.bet = 0;
+        player.state.starting_funds = player.state.seat.funds;
     }
 
     gameroom
@@ -292,357 +903,761 @@ async fn handle_step_blind(gameroom: &mut GameRoom) {
             players: gameroom
                 .players
                 .iter()
-                .filter_map(|player| player.state.is_betting.then_some(player.id))
+                .filter_map(|player| player.state.seat.is_betting.then_some(player.id))
                 .collect(),
         })
         .await;
+    // Stacks the hand starts from, before blinds and antes go in
+    gameroom
+        .broadcast(PlayerMessage::GameState {
+            players: gameroom
+                .players
+                .iter()
+                .map(|player| GamePlayerStateDTO {
+                    id: player.id,
+                    is_betting: player.state.seat.is_betting,
+                    bet_amount: 0,
+                    funds: player.state.seat.funds,
+                })
+                .collect(),
+            step,
+        })
+        .await;
+}
+
+async fn handle_step_ante(gameroom: &mut GameRoom) {
+    start_hand(gameroom, PokerStep::Ante).await;
+    let ante = gameroom.betting.ante;
+    gameroom.state.hand.post_antes(&mut gameroom.players, ante);
+    gameroom
+        .broadcast(PlayerMessage::Ante { amount: ante })
+        .await;
+}
+
+async fn handle_step_blind(gameroom: &mut GameRoom) {
+    start_hand(gameroom, PokerStep::Blind).await;
+
+    let betting = gameroom.betting;
+    let forced_bets = gameroom.rotation.forced_bets;
+    let straddling: Vec<bool> = gameroom
+        .players
+        .iter()
+        .map(|player| player.state.straddle)
+        .collect();
+    let blinds =
+        gameroom
+            .state
+            .hand
+            .post_blinds(&mut gameroom.players, &betting, &forced_bets, &straddling);
 
+    let player_id = |idx: usize| gameroom.players[idx].id;
     gameroom
         .broadcast(PlayerMessage::Blind {
-            small_blind_player: small_blind_id,
-            big_blind_player: big_blind_id,
-            small_blind_amount: gameroom.min_bet,
-            big_blind_amount: gameroom.min_bet * 2,
+            small_blind_player: player_id(blinds.small_blind_idx),
+            big_blind_player: player_id(blinds.big_blind_idx),
+            small_blind_amount: betting.small_blind,
+            big_blind_amount: betting.big_blind,
+            ante_amount: betting.ante,
+            ante_type: forced_bets.ante_type,
+            ante_player: blinds.ante_idx.map(player_id),
+            straddle_player: blinds.straddle_idx.map(player_id),
+            straddle_amount: blinds.straddle_amount,
         })
         .await;
 }
 
 async fn handle_step_preflop(gameroom: &mut GameRoom) {
-    gameroom.state.dealt_card_offset = 0;
-    let hole_count = match gameroom.game_type {
-        GameType::TexasHoldemPoker => 2,
-        GameType::OmahaPoker => 4,
-    };
-
-    for player in gameroom.players.iter_mut() {
-        if !player.state.is_betting {
-            continue;
-        }
-
-        for i in 0..hole_count {
-            let mut card = gameroom.state.deck[gameroom.state.dealt_card_offset + i];
-            card.owner = Owner::Player;
-            player.state.dealt_cards.push(card);
-        }
+    let hole_count = gameroom.variant().hole_card_count();
+    gameroom
+        .state
+        .hand
+        .deal_hole_cards(&mut gameroom.players, hole_count, &mut rand::rng());
 
+    for player in gameroom
+        .players
+        .iter()
+        .filter(|player| player.state.seat.is_betting)
+    {
         _ = player
             .sender
             .send(PlayerMessage::CardDeal {
                 cards: player
                     .state
+                    .seat
                     .dealt_cards
                     .iter()
-                    .map(|card| CardDealDTO {
-                        rank: card.rank as u8,
-                        suit: card.suit.into(),
-                    })
+                    .map(|card| CardDealDTO::new(card, false))
                     .collect(),
                 owner: CardOwnerDTO::Player,
+                player_id: Some(player.id),
             })
             .await;
-        gameroom.state.dealt_card_offset += hole_count;
     }
 }
 
-async fn handle_step_flop(gameroom: &mut GameRoom) {
-    for i in 0..3 {
-        gameroom
-            .state
-            .community_cards
-            .push(gameroom.state.deck[gameroom.state.dealt_card_offset + i]);
-    }
+async fn handle_step_deal_community_cards(gameroom: &mut GameRoom, n_cards: usize) {
+    let cards = gameroom
+        .state
+        .hand
+        .deal_community_cards(n_cards, &mut rand::rng());
 
     gameroom
         .broadcast(PlayerMessage::CardDeal {
-            cards: gameroom
-                .state
-                .community_cards
+            cards: cards
                 .iter()
-                .map(|card| CardDealDTO {
-                    rank: card.rank as u8,
-                    suit: card.suit.into(),
-                })
+                .map(|card| CardDealDTO::new(card, true))
                 .collect(),
             owner: CardOwnerDTO::Community,
+            player_id: None,
         })
         .await;
-    gameroom.state.dealt_card_offset += 3;
 }
 
-async fn handle_step_deal_community_cards(gameroom: &mut GameRoom, n_cards: usize) {
-    for _ in 0..n_cards {
+/// Deals a stud street. Face-down cards are only sent to their owner, face-up
+/// cards to the whole table
+async fn handle_step_stud_street(gameroom: &mut GameRoom, step: PokerStep) {
+    let variant = gameroom.shared_variant();
+    let betting = gameroom.betting;
+    let street = gameroom.state.hand.deal_stud_street(
+        &mut gameroom.players,
+        variant.as_ref(),
+        step,
+        &betting,
+        &mut rand::rng(),
+    );
+
+    if !street.board.is_empty() {
         gameroom
-            .state
-            .community_cards
-            .push(gameroom.state.deck[gameroom.state.dealt_card_offset]);
-        gameroom.state.dealt_card_offset += 1;
+            .broadcast(PlayerMessage::CardDeal {
+                cards: street
+                    .board
+                    .iter()
+                    .map(|card| CardDealDTO::new(card, true))
+                    .collect(),
+                owner: CardOwnerDTO::Community,
+                player_id: None,
+            })
+            .await;
     }
+    for (player_idx, cards) in street.dealt.iter() {
+        let player_id = gameroom.players[*player_idx].id;
+        _ = gameroom.players[*player_idx]
+            .sender
+            .send(PlayerMessage::CardDeal {
+                cards: cards
+                    .iter()
+                    .map(|(card, face_up)| CardDealDTO::new(card, *face_up))
+                    .collect(),
+                owner: CardOwnerDTO::Player,
+                player_id: Some(player_id),
+            })
+            .await;
 
-    gameroom
-        .broadcast(PlayerMessage::CardDeal {
-            cards: gameroom
-                .state
-                .community_cards
-                .iter()
-                .rev()
-                .take(n_cards)
-                .map(|card| CardDealDTO {
-                    rank: card.rank as u8,
-                    suit: card.suit.into(),
+        let up_cards: Vec<CardDealDTO> = cards
+            .iter()
+            .filter(|(_, face_up)| *face_up)
+            .map(|(card, _)| CardDealDTO::new(card, true))
+            .collect();
+        if up_cards.is_empty() {
+            continue;
+        }
+        for other in gameroom
+            .players
+            .iter()
+            .filter(|other| other.id != player_id)
+        {
+            _ = other
+                .sender
+                .send(PlayerMessage::CardDeal {
+                    cards: up_cards.clone(),
+                    owner: CardOwnerDTO::Player,
+                    player_id: Some(player_id),
                 })
-                .collect(),
-            owner: CardOwnerDTO::Community,
-        })
-        .await;
+                .await;
+        }
+    }
+
+    if let Some((player_idx, amount)) = street.bring_in {
+        let player_id = gameroom.players[player_idx].id;
+        gameroom
+            .broadcast(PlayerMessage::BringIn { player_id, amount })
+            .await;
+    }
 }
 
-async fn handle_step_betting_round(
+/// Each player in turn throws away the cards they picked and is dealt as
+/// many new ones. Players who do not pick in time stand pat
+async fn handle_step_draw(
     gameroom_mutex: Arc<Mutex<GameRoom>>,
     notification_receiver: &mut mpsc::Receiver<GameRoomStateNotification>,
 ) {
-    loop {
-        let n_players: usize;
-        {
-            let gameroom = gameroom_mutex.lock().await;
-            n_players = gameroom.players.len();
-        }
-
-        for player_idx in 0..n_players {
-            let timeout_instant: Instant;
-            let timeout_time: SystemTime;
+    let n_players: usize;
+    let first_to_act: usize;
+    {
+        let gameroom = gameroom_mutex.lock().await;
+        n_players = gameroom.players.len();
+        first_to_act = gameroom.state.hand.first_to_act;
+    }
 
-            {
-                let mut gameroom = gameroom_mutex.lock().await;
-                let player_is_betting = gameroom.players[player_idx].state.is_betting;
-                if !player_is_betting {
-                    continue;
-                }
+    for offset in 0..n_players {
+        let player_idx = (first_to_act + offset) % n_players;
+        let timeout_instant: Instant;
+        let timeout_time: SystemTime;
 
-                gameroom.state.current_player_turn = Some(gameroom.players[player_idx].id);
-                timeout_instant =
-                    Instant::now() + Duration::from_secs(gameroom.turn_duration as u64);
-                timeout_time =
-                    SystemTime::now() + Duration::from_secs(gameroom.turn_duration as u64);
-                gameroom.state.current_player_timeout = Some(timeout_time);
-
-                gameroom
-                    .broadcast(PlayerMessage::Turn {
-                        player_id: gameroom.players[player_idx].id,
-                        timeout: timeout_time.duration_since(UNIX_EPOCH).unwrap().as_millis()
-                            as u64,
-                    })
-                    .await;
+        {
+            let mut gameroom = gameroom_mutex.lock().await;
+            if !gameroom.players[player_idx].state.seat.is_betting {
+                continue;
             }
 
-            while SystemTime::now() < timeout_time {
-                match tokio::time::timeout_at(timeout_instant, notification_receiver.recv())
-                    .await
-                    .unwrap_or(None)
-                {
-                    Some(notif) => {
-                        print!("State loop received notification: {}", notif.content);
-                    }
-                    None => {}
-                }
-
-                let mut gameroom = gameroom_mutex.lock().await;
-                let bet_base = gameroom.state.bet_base;
-                let mut bet_base_update = gameroom.state.bet_base;
-                let mut is_action = true;
-                let mut pending_broadcast: Option<PlayerMessage> = None;
+            gameroom.state.current_player_turn = Some(gameroom.players[player_idx].id);
+            timeout_instant = Instant::now() + Duration::from_secs(gameroom.turn_duration as u64);
+            timeout_time = SystemTime::now() + Duration::from_secs(gameroom.turn_duration as u64);
+            gameroom.state.current_player_timeout = Some(timeout_time);
 
-                match gameroom.players.get_mut(player_idx) {
-                    Some(player) => {
-                        match player.state.action.clone() {
-                            PlayerGameAction::None => {
-                                is_action = false;
-                            }
-                            PlayerGameAction::Fold => {
-                                player.state.is_betting = false;
-                            }
-                            PlayerGameAction::Call => {
-                                let delta = bet_base - player.state.bet;
-                                if player.state.funds < delta {
-                                    is_action = false;
-                                    _ = player
-                                        .sender
-                                        .send(PlayerMessage::Warning {
-                                            warning_type: PlayerWarningType::InvalidAction,
-                                            message: "Not enough funds".to_string(),
-                                        })
-                                        .await;
-                                } else {
-                                    player.state.funds -= delta;
-                                    player.state.bet = bet_base;
-                                }
-                            }
-                            PlayerGameAction::Check => {
-                                if player.state.bet != bet_base {
-                                    is_action = false;
-                                    let warning = PlayerMessage::Warning {
-                                        warning_type: PlayerWarningType::InvalidAction,
-                                        message: "Cannot check".to_owned(),
-                                    };
-                                    let _ = player.sender.send(warning).await;
-                                    player.state.action = PlayerGameAction::None;
-                                }
-                            }
-                            PlayerGameAction::Raise(raise) => {
-                                let delta = bet_base_update + raise - player.state.bet;
-                                if delta > player.state.funds {
-                                    is_action = false;
-                                    _ = player
-                                        .sender
-                                        .send(PlayerMessage::Warning {
-                                            warning_type: PlayerWarningType::InvalidAction,
-                                            message: "Not enough funds".to_string(),
-                                        })
-                                        .await;
-                                } else {
-                                    bet_base_update += raise;
-                                    player.state.funds -= delta;
-                                    player.state.bet = bet_base_update;
-                                }
-                            }
-                        }
-                        pending_broadcast = Some(PlayerMessage::PlayerAction {
-                            player_id: player.id.clone(),
-                            action: player.state.action.clone(),
-                            bet_base: bet_base_update,
-                        });
-                    }
-                    None => {
-                        is_action = false;
-                    }
-                }
-                if is_action {
-                    gameroom.state.bet_base = bet_base_update;
-                    if pending_broadcast.is_some() {
-                        gameroom.broadcast(pending_broadcast.unwrap()).await;
-                    }
-                    break;
-                }
-            }
+            gameroom
+                .broadcast(PlayerMessage::Turn {
+                    player_id: gameroom.players[player_idx].id,
+                    timeout: timeout_time.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
+                })
+                .await;
+        }
 
+        // The discard may already be in, its notification taken while an
+        // earlier player was drawing, so it is looked at before waiting
+        loop {
             {
                 let mut gameroom = gameroom_mutex.lock().await;
-                let bet_base = gameroom.state.bet_base;
-
-                match gameroom.players.get_mut(player_idx) {
-                    Some(player) => {
-                        if player.state.bet < bet_base && player.state.is_betting {
-                            player.state.is_betting = false;
-                            let player_id = player.id;
-                            gameroom
-                                .broadcast(PlayerMessage::PlayerTurnTimeout { player: player_id })
-                                .await;
-                        }
+                let player = &mut gameroom.players[player_idx];
+                match player.state.action {
+                    PlayerGameAction::Discard(_) => break,
+                    PlayerGameAction::None => {}
+                    _ => {
+                        _ = player
+                            .sender
+                            .send(PlayerMessage::Warning {
+                                warning_type: PlayerWarningType::InvalidAction,
+                                message: "Pick the cards to discard".to_string(),
+                            })
+                            .await;
+                        player.state.action = PlayerGameAction::None;
                     }
-                    None => {}
                 }
             }
+            if SystemTime::now() >= timeout_time {
+                break;
+            }
+            _ = tokio::time::timeout_at(timeout_instant, notification_receiver.recv()).await;
+        }
+
+        let mut gameroom = gameroom_mutex.lock().await;
+        let gameroom = &mut *gameroom;
+        let player = &mut gameroom.players[player_idx];
+        let mut discards = std::mem::take(&mut player.state.discards);
+        if !matches!(player.state.action, PlayerGameAction::Discard(_)) {
+            discards.clear();
         }
 
+        let drawn = gameroom.state.hand.draw(
+            &mut gameroom.players,
+            player_idx,
+            discards,
+            &mut rand::rng(),
+        );
+        let player = &mut gameroom.players[player_idx];
+        player.state.action = PlayerGameAction::Discard(drawn.len() as u32);
+        let player_id = player.id;
+
+        _ = player
+            .sender
+            .send(PlayerMessage::CardDeal {
+                cards: drawn
+                    .iter()
+                    .map(|card| CardDealDTO::new(card, false))
+                    .collect(),
+                owner: CardOwnerDTO::Player,
+                player_id: Some(player_id),
+            })
+            .await;
+
+        gameroom
+            .broadcast(PlayerMessage::PlayerAction {
+                player_id,
+                action: PlayerGameAction::Discard(drawn.len() as u32),
+                bet_base: gameroom.state.hand.bet_base,
+            })
+            .await;
+    }
+}
+
+/// Takes the decision the player at `player_idx` sent, warning them when the
+/// table refuses it. True once a decision is taken
+async fn take_decision(gameroom: &mut GameRoom, player_idx: usize) -> bool {
+    let decision = match gameroom.players[player_idx].state.action {
+        PlayerGameAction::None => return false,
+        PlayerGameAction::Fold => Ok(Decision::Fold),
+        PlayerGameAction::Check => Ok(Decision::Check),
+        PlayerGameAction::Call => Ok(Decision::Call),
+        PlayerGameAction::Raise(raise) => Ok(Decision::Raise(raise)),
+        PlayerGameAction::Discard(_) => Err("Cannot discard during a betting round".to_string()),
+    };
+    let betting = gameroom.betting;
+    let taken = decision.and_then(|decision| {
+        gameroom
+            .state
+            .hand
+            .apply(&mut gameroom.players, player_idx, decision, &betting)
+    });
+    let player = &mut gameroom.players[player_idx];
+    let action = std::mem::replace(&mut player.state.action, PlayerGameAction::None);
+    let player_id = player.id;
+    if let Err(message) = taken {
+        gameroom.warn(player_id, &message).await;
+        return false;
+    }
+
+    gameroom
+        .broadcast(PlayerMessage::PlayerAction {
+            player_id,
+            action,
+            bet_base: gameroom.state.hand.bet_base,
+        })
+        .await;
+    true
+}
+
+/// Acts for a player who did not answer in time: they check when they can
+/// and fold otherwise
+async fn time_out(gameroom: &mut GameRoom, player_idx: usize) {
+    let betting = gameroom.betting;
+    let decision = match gameroom.state.hand.to_call(&gameroom.players, player_idx) {
+        0 => Decision::Check,
+        _ => Decision::Fold,
+    };
+    _ = gameroom
+        .state
+        .hand
+        .apply(&mut gameroom.players, player_idx, decision, &betting);
+    if decision == Decision::Fold {
+        let player_id = gameroom.players[player_idx].id;
+        gameroom
+            .broadcast(PlayerMessage::PlayerTurnTimeout { player: player_id })
+            .await;
+    }
+}
+
+/// Asks the players for their decisions, in turn, until the betting round
+/// is over
+async fn handle_step_betting_round(
+    gameroom_mutex: Arc<Mutex<GameRoom>>,
+    notification_receiver: &mut mpsc::Receiver<GameRoomStateNotification>,
+) {
+    loop {
+        let player_idx: usize;
+        let timeout_instant: Instant;
+        let timeout_time: SystemTime;
+
         {
-            let gameroom = gameroom_mutex.lock().await;
-            let mut active_players = gameroom
-                .players
-                .iter()
-                .filter(|player| player.state.is_betting);
-            if active_players.clone().count() <= 1
-                || active_players.all(|player| player.state.bet == gameroom.state.bet_base)
-            {
+            let mut gameroom = gameroom_mutex.lock().await;
+            let Some(next_idx) = gameroom.state.hand.next_to_act(&gameroom.players) else {
                 break;
+            };
+            player_idx = next_idx;
+
+            gameroom.state.current_player_turn = Some(gameroom.players[player_idx].id);
+            timeout_instant = Instant::now() + Duration::from_secs(gameroom.turn_duration as u64);
+            timeout_time = SystemTime::now() + Duration::from_secs(gameroom.turn_duration as u64);
+            gameroom.state.current_player_timeout = Some(timeout_time);
+
+            gameroom
+                .broadcast(PlayerMessage::Turn {
+                    player_id: gameroom.players[player_idx].id,
+                    timeout: timeout_time.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
+                })
+                .await;
+        }
+
+        // The decision may already be in, its notification taken while an
+        // earlier player was acting, so it is looked at before waiting
+        loop {
+            let mut gameroom = gameroom_mutex.lock().await;
+            if take_decision(&mut gameroom, player_idx).await {
+                break;
+            }
+            if SystemTime::now() >= timeout_time {
+                time_out(&mut gameroom, player_idx).await;
+                break;
+            }
+            drop(gameroom);
+            if let Ok(Some(notif)) =
+                tokio::time::timeout_at(timeout_instant, notification_receiver.recv()).await
+            {
+                print!("State loop received notification: {}", notif.content);
             }
         }
     }
+
+    let mut gameroom = gameroom_mutex.lock().await;
+    let gameroom_ref = &mut *gameroom;
+    let variant = gameroom_ref.shared_variant();
+    gameroom_ref
+        .state
+        .hand
+        .end_betting_round(&gameroom_ref.players, variant.as_ref());
+    drop(gameroom);
+    handle_all_in(gameroom_mutex).await;
 }
 
-async fn handle_step_showdown(gameroom: &mut GameRoom) {
-    let end_players: Vec<usize> = gameroom
-        .players
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, player)| player.state.is_betting.then_some(idx))
-        .collect();
+/// Once at most one player in the hand can still bet, shows every hand with
+/// its equity and deals the rest of the board as many times as all of them
+/// agreed to run it
+async fn handle_all_in(gameroom_mutex: Arc<Mutex<GameRoom>>) {
+    let (variant, contenders, hands, board) = {
+        let mut gameroom = gameroom_mutex.lock().await;
+        let board_size = gameroom.variant().board_card_count();
+        let n_cards = board_size.saturating_sub(gameroom.state.hand.community_cards.len());
+        let contenders: Vec<usize> = gameroom
+            .players
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, player)| player.state.seat.is_betting.then_some(idx))
+            .collect();
+        let n_with_funds = contenders
+            .iter()
+            .filter(|&&idx| gameroom.players[idx].state.seat.funds > 0)
+            .count();
+        if gameroom.state.all_in_board.is_some()
+            || n_cards == 0
+            || contenders.len() < 2
+            || n_with_funds > 1
+        {
+            return;
+        }
+        gameroom.state.all_in_board = Some(gameroom.state.hand.community_cards.clone());
+
+        let hands: Vec<Vec<Card>> = contenders
+            .iter()
+            .map(|&idx| gameroom.players[idx].state.seat.dealt_cards.clone())
+            .collect();
+        (
+            gameroom.shared_variant(),
+            contenders,
+            hands,
+            gameroom.state.hand.community_cards.clone(),
+        )
+    };
+    // Runouts are estimated on every core, away from the runtime and the
+    // room's lock
+    let estimate = tokio::task::spawn_blocking(move || {
+        runout_equity(
+            variant.as_ref(),
+            &hands,
+            &board,
+            &[],
+            EQUITY_SAMPLES,
+            rand::random(),
+        )
+        .map(|equity| (hands, equity))
+    });
+    let Ok(Ok((hands, equity))) = estimate.await else {
+        return;
+    };
 
-    let hands: Vec<Vec<Card>> = end_players
-        .iter()
-        .map(|&idx| {
-            gameroom.players[idx]
-                .state
-                .dealt_cards
-                .iter()
-                .chain(gameroom.state.community_cards.iter())
-                .map(|card| card.clone())
-                .collect()
-        })
-        .collect();
+    let max_runouts = {
+        let gameroom = gameroom_mutex.lock().await;
+        let players: Vec<PlayerEquityDTO> = contenders
+            .iter()
+            .zip(hands.iter())
+            .zip(equity.players.iter())
+            .map(|((&idx, hand), player_equity)| PlayerEquityDTO {
+                player_id: gameroom.players[idx].id,
+                cards: hand
+                    .iter()
+                    .map(|card| CardDealDTO::new(card, true))
+                    .collect(),
+                win: player_equity.win,
+                tie: player_equity.tie,
+                equity: player_equity.equity,
+            })
+            .collect();
+        gameroom
+            .broadcast(PlayerMessage::AllInEquity { players })
+            .await;
 
-    let result = compare_hands(hands, gameroom.game_type);
-    if !result.is_ok() {
+        let board_size = gameroom.variant().board_card_count();
+        let n_cards = board_size.saturating_sub(gameroom.state.hand.community_cards.len());
+        let stub_size = gameroom.state.hand.deck.len() - gameroom.state.hand.dealt_card_offset;
+        stub_size / n_cards
+    };
+    // Run once, the board is dealt street by street as usual
+    let n_runouts = agree_runouts(&gameroom_mutex, &contenders, max_runouts).await;
+    if n_runouts < 2 {
         return;
     }
 
-    let winners: Vec<(usize, Uuid, u32)> = result
-        .unwrap_or(Vec::new())
-        .iter()
-        .map(|&idx| {
-            (
-                idx,
-                gameroom.players[end_players[idx]].id,
-                gameroom.players[end_players[idx]].state.bet,
-            )
-        })
-        .collect();
+    let mut gameroom = gameroom_mutex.lock().await;
+    let board_size = gameroom.variant().board_card_count();
+    let n_cards = board_size.saturating_sub(gameroom.state.hand.community_cards.len());
+    for runout in 0..n_runouts {
+        let offset = gameroom.state.hand.dealt_card_offset;
+        let cards = gameroom.state.hand.deck[offset..offset + n_cards].to_vec();
+        gameroom.state.hand.dealt_card_offset += n_cards;
 
-    let bet_cap: u32 = winners
-        .iter()
-        .max_by_key(|(_, _, bet)| bet)
-        .map_or(0, |(_, _, bet)| *bet);
+        gameroom
+            .broadcast(PlayerMessage::Runout {
+                runout,
+                cards: cards
+                    .iter()
+                    .map(|card| CardDealDTO::new(card, true))
+                    .collect(),
+            })
+            .await;
+        let mut board = gameroom.state.hand.community_cards.clone();
+        board.extend(cards);
+        gameroom.state.runouts.push(board);
+    }
+}
 
-    let bet_pool: u32 = gameroom
-        .players
-        .iter_mut()
-        .map(|player| {
-            if player.state.bet > bet_cap {
-                player.state.funds += player.state.bet - bet_cap;
-                return bet_cap;
-            }
-            player.state.bet
-        })
-        .sum();
+/// Offers the players all in to run the board up to `max_runouts` times,
+/// returning the fewest times one of them agreed to. Players who do not
+/// answer in time run it once
+async fn agree_runouts(
+    gameroom_mutex: &Arc<Mutex<GameRoom>>,
+    contenders: &[usize],
+    max_runouts: usize,
+) -> usize {
+    if max_runouts < 2 {
+        return 1;
+    }
+    {
+        let mut gameroom = gameroom_mutex.lock().await;
+        let players: Vec<Uuid> = contenders
+            .iter()
+            .map(|&idx| gameroom.players[idx].id)
+            .collect();
+        for &idx in contenders.iter() {
+            gameroom.players[idx].state.run_it = None;
+        }
+        gameroom.state.run_it_offer = players.clone();
+        gameroom
+            .broadcast(PlayerMessage::RunItOffer {
+                players,
+                max_runouts,
+                timeout: RUN_IT_TIMEOUT,
+            })
+            .await;
+    }
 
-    let winner_bet_sum: u32 = winners.iter().map(|(_, _, bet)| bet).sum();
-    let mut prizes: Vec<u32> = winners
-        .iter()
-        .map(|(_, _, bet)| *bet * bet_pool / winner_bet_sum)
-        .collect();
+    let deadline = Instant::now() + Duration::from_secs(RUN_IT_TIMEOUT);
+    loop {
+        {
+            let mut gameroom = gameroom_mutex.lock().await;
+            let answers: Vec<Option<u32>> = contenders
+                .iter()
+                .map(|&idx| gameroom.players[idx].state.run_it)
+                .collect();
+            if answers.iter().all(Option::is_some) || Instant::now() >= deadline {
+                gameroom.state.run_it_offer.clear();
+                let times = answers
+                    .iter()
+                    .map(|times| times.unwrap_or(1) as usize)
+                    .min()
+                    .unwrap_or(1);
+                return times.min(max_runouts);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
 
-    let rounding_error: u32 = bet_pool.saturating_sub(prizes.iter().sum());
+/// Equity of the contenders of each pot an all in can be split into, by the
+/// players eligible to win it. Estimated before the showdown takes the lock
+type PotEquities = Vec<(Vec<usize>, Vec<f64>)>;
+
+/// Players eligible to win a pot, their hands and the cards of the players
+/// who cannot
+type PotMatchup = (Vec<usize>, Vec<Vec<Card>>, Vec<Card>);
+
+/// Equity each pot's contenders had when they were all in, none when the
+/// hand did not end all in
+async fn all_in_equities(gameroom_mutex: &Arc<Mutex<GameRoom>>) -> PotEquities {
+    let (variant, board, matchups) = {
+        let gameroom = gameroom_mutex.lock().await;
+        let Some(board) = gameroom.state.all_in_board.clone() else {
+            return Vec::new();
+        };
+        let bets: Vec<u32> = gameroom
+            .players
+            .iter()
+            .map(|player| player.state.seat.bet)
+            .collect();
+        let folded: Vec<bool> = gameroom
+            .players
+            .iter()
+            .map(|player| !player.state.seat.is_betting)
+            .collect();
+        let matchups: Vec<PotMatchup> = side_pots(&bets, &folded)
+            .into_iter()
+            .filter(|pot| pot.eligible.len() > 1)
+            .map(|pot| {
+                let hands: Vec<Vec<Card>> = pot
+                    .eligible
+                    .iter()
+                    .map(|&idx| gameroom.players[idx].state.seat.dealt_cards.clone())
+                    .collect();
+                // Cards of the players who cannot win this pot will not come
+                // either
+                let dead_cards: Vec<Card> = gameroom
+                    .players
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, player)| {
+                        player.state.seat.is_betting && !pot.eligible.contains(idx)
+                    })
+                    .flat_map(|(_, player)| player.state.seat.dealt_cards.iter().copied())
+                    .collect();
+                (pot.eligible, hands, dead_cards)
+            })
+            .collect();
+        (gameroom.shared_variant(), board, matchups)
+    };
+    if matchups.is_empty() {
+        return Vec::new();
+    }
 
-    if rounding_error > 0 {
-        let mut shuffled_prizes_indexes: Vec<usize> = (0..prizes.len()).collect();
-        shuffled_prizes_indexes.shuffle(&mut rand::rng());
+    let estimate = tokio::task::spawn_blocking(move || {
+        matchups
+            .into_iter()
+            .filter_map(|(eligible, hands, dead_cards)| {
+                let equity = runout_equity(
+                    variant.as_ref(),
+                    &hands,
+                    &board,
+                    &dead_cards,
+                    EQUITY_SAMPLES,
+                    rand::random(),
+                )
+                .ok()?;
+                let shares = equity.players.iter().map(|player| player.equity).collect();
+                Some((eligible, shares))
+            })
+            .collect()
+    });
+    estimate.await.unwrap_or_default()
+}
 
-        for i in 0..rounding_error as usize {
-            let price_idx = shuffled_prizes_indexes[i % shuffled_prizes_indexes.len()];
-            prizes[price_idx] += 1;
+async fn handle_step_showdown(gameroom: &mut GameRoom, equities: PotEquities) {
+    let boards = if gameroom.state.runouts.is_empty() {
+        vec![gameroom.state.hand.community_cards.clone()]
+    } else {
+        gameroom.state.runouts.clone()
+    };
+    let variant = gameroom.shared_variant();
+    let settled = gameroom.state.hand.settle(
+        &mut gameroom.players,
+        variant.as_ref(),
+        &boards,
+        gameroom.rake.as_ref(),
+    );
+    let Settlement {
+        pots,
+        hands: board_hands,
+        awards,
+        prizes: player_prizes,
+        rake,
+        contributions,
+        ..
+    } = match settled {
+        Ok(settlement) => settlement,
+        Err(err) => {
+            cancel_hand(gameroom, err).await;
+            return;
         }
+    };
+    let showdown_hands = &board_hands[0];
+    let n_players = gameroom.players.len();
+
+    // Dead money is raked like the bets, on whoever posted it
+    let attribution: Vec<(Uuid, u32)> = gameroom
+        .players
+        .iter()
+        .map(|player| player.id)
+        .zip(attribute_rake(rake, &contributions))
+        .collect();
+    gameroom.ledger.record(&attribution).await;
+
+    let bounties = collect_bounties(gameroom, &pots, &awards);
+    for bounty in bounties.iter() {
+        gameroom.wallets.credit(bounty.eliminator, bounty.cash).await;
     }
+    gameroom.state.bounties.extend(bounties.iter().cloned());
 
-    for (i, (idx, _, _)) in winners.iter().enumerate() {
-        let player_idx = end_players[*idx];
-        gameroom.players[player_idx].state.funds += prizes[i];
+    let ev_prizes: Vec<f64> = if gameroom.state.all_in_board.is_some() {
+        all_in_ev(n_players, &pots, &equities)
+    } else {
+        player_prizes.iter().map(|&prize| prize as f64).collect()
+    };
+    let record = HandRecordDTO {
+        game: gameroom.variant().name().to_string(),
+        boards: boards
+            .iter()
+            .map(|board| board.iter().map(Card::notation).collect())
+            .collect(),
+        players: gameroom
+            .players
+            .iter()
+            .zip(player_prizes.iter().zip(ev_prizes.iter()))
+            .filter(|(player, _)| !player.state.seat.dealt_cards.is_empty())
+            .map(|(player, (&prize, &ev_prize))| {
+                let net = player.state.seat.funds as i64 - player.state.starting_funds as i64;
+                HandRecordPlayerDTO {
+                    player_id: player.id,
+                    net,
+                    ev_net: net as f64 - prize as f64 + ev_prize,
+                }
+            })
+            .collect(),
+        bounties: bounties.clone(),
+        rake,
+    };
+    gameroom.history.push_back(record);
+    if gameroom.history.len() > HISTORY_SIZE {
+        gameroom.history.pop_front();
     }
 
+    let (winners, prizes): (Vec<Uuid>, Vec<u32>) = gameroom
+        .players
+        .iter()
+        .zip(player_prizes)
+        .filter(|(_, prize)| *prize > 0)
+        .map(|(player, prize)| (player.id, prize))
+        .unzip();
+
+    let player_ids = |indexes: &Vec<usize>| -> Vec<Uuid> {
+        indexes
+            .iter()
+            .map(|&idx| gameroom.players[idx].id)
+            .collect()
+    };
+    let pots: Vec<PotResultDTO> = awards
+        .iter()
+        .map(|(runout, award)| PotResultDTO {
+            amount: award.amount,
+            high_winners: player_ids(&award.high_winners),
+            low_winners: player_ids(&award.low_winners),
+            runout: *runout,
+        })
+        .collect();
+
     let player_hands: Vec<HandRevealDTO> = gameroom
         .players
         .iter()
-        .map(|player| HandRevealDTO {
-            player_id: player.id.clone(),
+        .zip(showdown_hands.iter())
+        .map(|(player, showdown_hand)| HandRevealDTO {
+            player_id: player.id,
             cards: player
                 .state
+                .seat
                 .dealt_cards
                 .iter()
                 .map(|card| CardReveallDTO {
@@ -654,17 +1669,82 @@ async fn handle_step_showdown(gameroom: &mut GameRoom) {
                     },
                 })
                 .collect(),
+            hand: showdown_hand.as_ref().map(|hand| hand.name.clone()),
+            low_hand: showdown_hand
+                .as_ref()
+                .and_then(|hand| hand.low)
+                .map(|low| low_hand_name(&low)),
         })
         .collect();
 
     gameroom
         .broadcast(PlayerMessage::Result {
-            winners: winners.iter().map(|(_, id, _)| id.to_owned()).collect(),
+            winners,
             prizes,
+            pots,
             player_hands,
+            bounties,
+            rake,
+        })
+        .await;
+}
+
+/// Tells the table a hand that could not be settled was called off, every
+/// chip having gone back to the player who bet it
+async fn cancel_hand(gameroom: &GameRoom, reason: &str) {
+    gameroom
+        .broadcast(PlayerMessage::HandCancelled {
+            reason: reason.to_string(),
         })
         .await;
-    gameroom.state.bet_base = 0;
+}
+
+/// Splits the bounties of the players knocked out in the hand, the cash being
+/// for the wallets. Whoever won the last pot a player was in knocked them
+/// out, the winners sharing the bounty of a split pot
+fn collect_bounties(
+    gameroom: &mut GameRoom,
+    pots: &[Pot],
+    awards: &[(usize, PotAward)],
+) -> Vec<BountyDTO> {
+    let Some(bounty) = gameroom.bounty else {
+        return Vec::new();
+    };
+    let mut bounties = Vec::new();
+    for busted in 0..gameroom.players.len() {
+        let state = &gameroom.players[busted].state;
+        if !state.is_playing || state.seat.funds > 0 || state.bounty == 0 {
+            continue;
+        }
+        let Some(last_pot) = pots.iter().rposition(|pot| pot.eligible.contains(&busted)) else {
+            continue;
+        };
+        // Awards hold every pot of a board before the next board's
+        let mut eliminators: Vec<usize> = Vec::new();
+        for (_, award) in awards.iter().skip(last_pot).step_by(pots.len()) {
+            for &winner in award.high_winners.iter().chain(award.low_winners.iter()) {
+                if winner != busted && !eliminators.contains(&winner) {
+                    eliminators.push(winner);
+                }
+            }
+        }
+        if eliminators.is_empty() {
+            continue;
+        }
+
+        let head = std::mem::take(&mut gameroom.players[busted].state.bounty);
+        let shares = bounty.split(head, eliminators.len());
+        for (&eliminator, (cash, added_to_head)) in eliminators.iter().zip(shares) {
+            gameroom.players[eliminator].state.bounty += added_to_head;
+            bounties.push(BountyDTO {
+                eliminated: gameroom.players[busted].id,
+                eliminator: gameroom.players[eliminator].id,
+                cash,
+                added_to_head,
+            });
+        }
+    }
+    bounties
 }
 
 async fn handle_poker_step(
@@ -683,7 +1763,7 @@ async fn handle_poker_step(
             handle_step_preflop(&mut *gameroom_mutex.lock().await).await;
         }
         PokerStep::Flop => {
-            handle_step_flop(&mut *gameroom_mutex.lock().await).await;
+            handle_step_deal_community_cards(&mut *gameroom_mutex.lock().await, 3).await;
         }
         PokerStep::Turn => {
             handle_step_deal_community_cards(&mut *gameroom_mutex.lock().await, 1).await;
@@ -692,27 +1772,28 @@ async fn handle_poker_step(
             handle_step_deal_community_cards(&mut *gameroom_mutex.lock().await, 1).await;
         }
         PokerStep::Showdown => {
-            handle_step_showdown(&mut *gameroom_mutex.lock().await).await;
+            let equities = all_in_equities(&gameroom_mutex).await;
+            handle_step_showdown(&mut *gameroom_mutex.lock().await, equities).await;
         }
         PokerStep::BettingRound => {
             handle_step_betting_round(gameroom_mutex, notification_receiver).await;
         }
+        PokerStep::Ante => {
+            handle_step_ante(&mut *gameroom_mutex.lock().await).await;
+        }
+        PokerStep::ThirdStreet
+        | PokerStep::FourthStreet
+        | PokerStep::FifthStreet
+        | PokerStep::SixthStreet
+        | PokerStep::SeventhStreet => {
+            handle_step_stud_street(&mut *gameroom_mutex.lock().await, step).await;
+        }
+        PokerStep::Draw => {
+            handle_step_draw(gameroom_mutex, notification_receiver).await;
+        }
     }
 }
 
-const STANDARD_POKER_STEPS: [PokerStep; 10] = [
-    PokerStep::Blind,
-    PokerStep::PreFlop,
-    PokerStep::BettingRound,
-    PokerStep::Flop,
-    PokerStep::BettingRound,
-    PokerStep::Turn,
-    PokerStep::BettingRound,
-    PokerStep::River,
-    PokerStep::BettingRound,
-    PokerStep::Showdown,
-];
-
 async fn gameroom_message_loop(
     gameroom: Arc<Mutex<GameRoom>>,
     mut receiver: mpsc::Receiver<GameRoomMessage>,
@@ -727,6 +1808,229 @@ async fn gameroom_message_loop(
     }
 }
 
+/// Sets the blinds of the tournament level being played and reports a
+/// sit-and-go's progress
+async fn start_tournament_hand(gameroom: &mut GameRoom) {
+    let level = match (gameroom.tournament.as_ref(), gameroom.blind_level) {
+        (Some(tournament), _) => tournament.level(),
+        (None, Some(level)) => level,
+        (None, None) => return,
+    };
+    let betting = gameroom
+        .rotation
+        .forced_bets
+        .apply(gameroom.variant().betting_structure());
+    gameroom.betting = level.apply(betting);
+
+    let Some(tournament) = gameroom.tournament.as_ref() else {
+        return;
+    };
+    let players_remaining = gameroom
+        .players
+        .iter()
+        .filter(|player| player.state.is_playing)
+        .count();
+    let message = tournament.state_message(players_remaining);
+    gameroom.broadcast(message).await;
+}
+
+/// Players who lost their last chip, with their stacks when the hand
+/// started. They stay at the table to watch, and are not out while they
+/// can still rebuy
+fn take_busted_players(gameroom: &mut GameRoom) -> Vec<(Uuid, u32)> {
+    let mut busted = Vec::new();
+    if gameroom.buy_windows().rebuy.is_some() {
+        return busted;
+    }
+    for player in gameroom.players.iter_mut() {
+        if player.state.is_playing && player.state.seat.funds == 0 {
+            player.state.is_playing = false;
+            busted.push((player.id, player.state.starting_funds));
+        }
+    }
+    busted
+}
+
+/// Adds the chips bought since the last hand to the stacks
+fn add_bought_chips(gameroom: &mut GameRoom) {
+    for player in gameroom.players.iter_mut() {
+        player.state.seat.funds += std::mem::take(&mut player.state.bought_chips);
+    }
+}
+
+/// Takes the players who lost their last chip out of the tournament, the
+/// one left holding every chip wins it and the prizes are paid
+async fn knock_out_players(gameroom: &mut GameRoom) {
+    if !gameroom
+        .tournament
+        .as_ref()
+        .is_some_and(TournamentProgress::is_running)
+    {
+        return;
+    }
+
+    let busted = take_busted_players(gameroom);
+    let bounties = std::mem::take(&mut gameroom.state.bounties);
+    let Some(tournament) = gameroom.tournament.as_mut() else {
+        return;
+    };
+    tournament.record_bounties(&bounties);
+    if busted.is_empty() {
+        return;
+    }
+    let remaining: Vec<Uuid> = gameroom
+        .players
+        .iter()
+        .filter_map(|player| player.state.is_playing.then_some(player.id))
+        .collect();
+    tournament.eliminate(busted, remaining.len());
+    let payouts = match remaining[..] {
+        [winner] => tournament.finish(winner),
+        _ => Vec::new(),
+    };
+
+    let message = tournament.state_message(remaining.len());
+    for (player_id, amount) in payouts {
+        gameroom.wallets.credit(player_id, amount).await;
+    }
+    gameroom.broadcast(message).await;
+}
+
+/// Pauses a sit-and-go while a deal is negotiated. The deal is priced on the
+/// stacks between hands and ends the tournament once every player left
+/// accepts it, players who do not answer in time decline
+async fn negotiate_deal(gameroom_mutex: &Arc<Mutex<GameRoom>>) {
+    loop {
+        {
+            let mut gameroom = gameroom_mutex.lock().await;
+            let stacks: Vec<(Uuid, u32)> = gameroom
+                .players
+                .iter()
+                .filter(|player| player.state.is_playing)
+                .map(|player| (player.id, player.state.seat.funds))
+                .collect();
+            let Some(tournament) = gameroom.tournament.as_mut() else {
+                return;
+            };
+            let Some(deal) = tournament.deal.as_ref() else {
+                return;
+            };
+
+            if !deal.is_offered() {
+                let message = match tournament.offer_deal(stacks) {
+                    Ok(deal) => PlayerMessage::DealOffer {
+                        method: deal.method,
+                        proposed_by: deal.proposed_by,
+                        shares: deal.shares.clone(),
+                        timeout: DEAL_TIMEOUT,
+                    },
+                    Err(err) => {
+                        tournament.deal = None;
+                        PlayerMessage::Warning {
+                            warning_type: PlayerWarningType::InvalidAction,
+                            message: err.to_string(),
+                        }
+                    }
+                };
+                gameroom.broadcast(message).await;
+            } else if deal.is_accepted() {
+                let payouts = tournament.settle_deal();
+                let message = tournament.state_message(0);
+                for (player_id, amount) in payouts {
+                    gameroom.wallets.credit(player_id, amount).await;
+                }
+                gameroom.broadcast(message).await;
+                return;
+            } else if deal.seconds_offered() >= DEAL_TIMEOUT {
+                let silent: Vec<Uuid> = deal
+                    .shares
+                    .iter()
+                    .map(|share| share.player_id)
+                    .filter(|player_id| !deal.accepted.contains(player_id))
+                    .collect();
+                tournament.deal = None;
+                for player_id in silent {
+                    gameroom
+                        .broadcast(PlayerMessage::DealVote {
+                            player_id,
+                            accept: false,
+                        })
+                        .await;
+                }
+                return;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Reports the players a multi-table tournament table knocked out and waits
+/// for its orders, sending players to other tables before the next hand.
+/// False once the table closes
+async fn await_table_order(gameroom_mutex: &Arc<Mutex<GameRoom>>) -> bool {
+    let (coordinator, table_id, busted, bounties, purchases) = {
+        let mut gameroom = gameroom_mutex.lock().await;
+        let Some(coordinator) = gameroom.coordinator.clone() else {
+            return true;
+        };
+        let busted = take_busted_players(&mut gameroom);
+        let bounties = std::mem::take(&mut gameroom.state.bounties);
+        let purchases = std::mem::take(&mut gameroom.state.purchases);
+        (coordinator, gameroom.id, busted, bounties, purchases)
+    };
+
+    // The room is left unlocked so the coordinator's messages get through
+    let (respond_to, order_receiver) = oneshot::channel();
+    let ready = MttMessage::TableReady {
+        table_id,
+        busted,
+        bounties,
+        purchases,
+        respond_to,
+    };
+    if coordinator.send(ready).await.is_err() {
+        return false;
+    }
+    let Ok(TableOrder {
+        level,
+        bounty,
+        buy_windows,
+        moves,
+        close,
+    }) = order_receiver.await
+    else {
+        return false;
+    };
+
+    let mut gameroom = gameroom_mutex.lock().await;
+    gameroom.blind_level = Some(level);
+    gameroom.bounty = bounty;
+    gameroom.buy_windows = buy_windows;
+    for player_move in moves {
+        let Some(idx) = gameroom
+            .players
+            .iter()
+            .position(|player| player.id == player_move.player_id)
+        else {
+            continue;
+        };
+        let player = gameroom.players.remove(idx);
+        _ = player_move
+            .table
+            .send(GameRoomMessage::SeatPlayer {
+                id: player.id,
+                sender: player.sender,
+                funds: player.state.seat.funds + player.state.bought_chips,
+                bounty: player.state.bounty,
+            })
+            .await;
+    }
+    if close {
+        gameroom.broadcast(PlayerMessage::TerminateSession).await;
+    }
+    !close
+}
+
 async fn gameroom_state_loop(
     gameroom: Arc<Mutex<GameRoom>>,
     mut notification_receiver: mpsc::Receiver<GameRoomStateNotification>,
@@ -736,16 +2040,67 @@ async fn gameroom_state_loop(
             continue;
         }
         tokio::time::sleep(Duration::from_secs(5)).await;
+        add_bought_chips(&mut *gameroom.lock().await);
+        if !await_table_order(&gameroom).await {
+            return;
+        }
+        negotiate_deal(&gameroom).await;
+        // Busted players who did not rebuy in time
+        knock_out_players(&mut *gameroom.lock().await).await;
 
-        for step in STANDARD_POKER_STEPS {
+        {
+            let gameroom = gameroom.lock().await;
+            match gameroom.tournament.as_ref() {
+                // The table closes once the tournament has a winner
+                Some(tournament) if tournament.is_over() => {
+                    gameroom.broadcast(PlayerMessage::TerminateSession).await;
+                    return;
+                }
+                // A sit-and-go deals its first hand once every seat is taken
+                Some(tournament) if !tournament.has_started() => continue,
+                _ => {}
+            }
+            // No hands are dealt during the add-on break
+            if gameroom.buy_windows().add_on.is_some() {
+                if let Some(tournament) = gameroom.tournament.as_ref() {
+                    let players_remaining = gameroom
+                        .players
+                        .iter()
+                        .filter(|player| player.state.is_playing)
+                        .count();
+                    let message = tournament.state_message(players_remaining);
+                    gameroom.broadcast(message).await;
+                }
+                continue;
+            }
+            let n_ready = gameroom
+                .players
+                .iter()
+                .filter(|player| {
+                    player.state.is_playing && player.state.seat.funds >= gameroom.min_funds
+                })
+                .count();
+            if n_ready < 2 {
+                continue;
+            }
+        }
+
+        rotate_game(&mut *gameroom.lock().await).await;
+        start_tournament_hand(&mut *gameroom.lock().await).await;
+        let steps = gameroom.lock().await.variant().steps();
+        for &step in steps {
+            if gameroom.lock().await.state.skips(step) {
+                continue;
+            }
             gameroom
                 .lock()
                 .await
-                .broadcast(PlayerMessage::Step { step: step.clone() })
+                .broadcast(PlayerMessage::Step { step })
                 .await;
 
             handle_poker_step(step, gameroom.clone(), &mut notification_receiver).await;
         }
+        knock_out_players(&mut *gameroom.lock().await).await;
     }
 }
 
@@ -755,13 +2110,19 @@ pub struct GameRoomHandle {
 }
 
 impl GameRoomHandle {
-    pub async fn new(game_type: GameType) -> Self {
+    pub async fn new(settings: GameRoomSettings, wallets: Wallets, ledger: HouseLedger) -> Self {
+        let id = Uuid::new_v4();
         let (sender, receiver) = mpsc::channel(100);
         let gameroom_mutex = Arc::new(Mutex::new(GameRoom::new(GameRoomConfig {
-            min_bet: 10,
+            id,
             min_funds: 100,
             turn_duration: 10,
-            game_type,
+            rotation: settings.rotation,
+            tournament: settings.tournament,
+            coordinator: settings.coordinator,
+            rake: settings.rake,
+            wallets,
+            ledger,
         })));
 
         let (notif_sender, notif_receiver) = mpsc::channel(10);
@@ -772,10 +2133,36 @@ impl GameRoomHandle {
         ));
         tokio::spawn(gameroom_state_loop(gameroom_mutex, notif_receiver));
 
-        Self {
-            id: uuid::Uuid::new_v4(),
-            sender,
+        let handle = Self { id, sender };
+        for strategy in settings.bots {
+            handle.add_bot(strategy).await;
         }
+        handle
+    }
+
+    pub fn sender(&self) -> mpsc::Sender<GameRoomMessage> {
+        self.sender.clone()
+    }
+
+    /// Seats a bot playing `strategy`, which joins like a connected player
+    pub async fn add_bot(&self, strategy: BotStrategy) -> Uuid {
+        let id = Uuid::new_v4();
+        let bot_sender = spawn_bot(id, strategy, self.sender.clone());
+        _ = self
+            .sender
+            .send(GameRoomMessage::PlayerJoin {
+                id,
+                sender: bot_sender,
+            })
+            .await;
+        _ = self
+            .sender
+            .send(GameRoomMessage::PlayerAction {
+                payload: PlayerAction::Update { is_playing: true },
+                from: id,
+            })
+            .await;
+        id
     }
 
     pub async fn handle_player_connection(&self, websocket: WebSocket, player_id: Uuid) {
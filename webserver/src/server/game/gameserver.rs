@@ -1,5 +1,8 @@
-use crate::core::game::GameType;
-use crate::server::game::gameroom::GameRoomHandle;
+use crate::server::game::gameroom::{GameRoomHandle, GameRoomSettings};
+use crate::server::game::ledger::HouseLedger;
+use crate::server::game::mtt::{MttConfig, MttHandle};
+use crate::server::game::wallet::Wallets;
+use mini_poker_core::strategy::BotStrategy;
 use tokio::sync::{mpsc, oneshot};
 use tokio;
 use uuid::{self, Uuid};
@@ -8,19 +11,29 @@ use axum::extract::ws::WebSocket;
 
 struct GameServer {
     gameroom_handlers: Vec<GameRoomHandle>,
+    tournament_handlers: Vec<MttHandle>,
+    wallets: Wallets,
+    ledger: HouseLedger,
     receiver: mpsc::Receiver<GameServerMessage>
 }
 
 impl GameServer {
-    fn new(receiver: mpsc::Receiver<GameServerMessage>) -> Self {
+    fn new(receiver: mpsc::Receiver<GameServerMessage>, ledger: HouseLedger) -> Self {
         Self {
             gameroom_handlers: Vec::new(),
+            tournament_handlers: Vec::new(),
+            wallets: Wallets::default(),
+            ledger,
             receiver
         }
     }
 
-    async fn handle_start_gameroom(&mut self, game_type: GameType) {
-        self.gameroom_handlers.push(GameRoomHandle::new(game_type).await);
+    async fn handle_start_gameroom(&mut self, settings: GameRoomSettings) {
+        self.gameroom_handlers.push(GameRoomHandle::new(settings, self.wallets.clone(), self.ledger.clone()).await);
+    }
+
+    fn handle_start_tournament(&mut self, config: MttConfig) {
+        self.tournament_handlers.push(MttHandle::new(config, self.wallets.clone(), self.ledger.clone()));
     }
 
     async fn handle_join_player(&mut self, websocket: WebSocket, room_id: uuid::Uuid) {
@@ -31,14 +44,34 @@ impl GameServer {
 
         match gameroom_handler_attempt {
             Some(gameroom_handler) => gameroom_handler.handle_player_connection(websocket, id).await,
-            None => {}
+            None => {
+                // Tournament tables are not joined directly, the coordinator seats players
+                if let Some(tournament_handler) = self.tournament_handlers.iter().find(|&v| v.id == room_id) {
+                    tournament_handler.handle_player_connection(websocket, id).await;
+                }
+            }
+        }
+    }
+
+    async fn handle_add_bots(&self, room_id: Uuid, strategies: Vec<BotStrategy>, respond_to: oneshot::Sender<Option<Vec<Uuid>>>) {
+        // Tournament tables are seated by their coordinator
+        let Some(gameroom_handler) = self.gameroom_handlers.iter().find(|&v| v.id == room_id) else {
+            let _ = respond_to.send(None);
+            return;
+        };
+        let mut bot_ids = Vec::new();
+        for strategy in strategies {
+            bot_ids.push(gameroom_handler.add_bot(strategy).await);
         }
+        let _ = respond_to.send(Some(bot_ids));
     }
 
     fn handle_list_gamerooms(&self, respond_to: oneshot::Sender<Vec<GameRoomDTO>>) {
         let gameroom_dtos = self.gameroom_handlers.iter().map(
             |game_room_handle| GameRoomDTO { id: game_room_handle.id.clone() }
-        ).collect();
+        ).chain(self.tournament_handlers.iter().map(
+            |tournament_handle| GameRoomDTO { id: tournament_handle.id }
+        )).collect();
         let _ = respond_to.send(gameroom_dtos);
     }
 }
@@ -49,22 +82,28 @@ pub struct GameRoomDTO {
 }
 
 pub enum GameServerMessage {
-    GameRoomStart { game_type: GameType },
+    GameRoomStart { settings: GameRoomSettings },
+    TournamentStart { config: MttConfig },
     PlayerJoin { websocket: WebSocket, room_id: uuid::Uuid },
-    ListGameRooms { respond_to: oneshot::Sender<Vec<GameRoomDTO>> }
+    ListGameRooms { respond_to: oneshot::Sender<Vec<GameRoomDTO>> },
+    /// Seats a bot per strategy, answering with their ids or none for an unknown room
+    AddBots { room_id: uuid::Uuid, strategies: Vec<BotStrategy>, respond_to: oneshot::Sender<Option<Vec<Uuid>>> }
 }
 
 #[derive(Clone)]
 pub struct GameServerHandle {
-    pub sender: mpsc::Sender<GameServerMessage>
+    pub sender: mpsc::Sender<GameServerMessage>,
+    /// Rake taken by every room of the server
+    pub ledger: HouseLedger
 }
 
 impl GameServerHandle {
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel(100);
-        let gameserver = GameServer::new(receiver);
+        let ledger = HouseLedger::default();
+        let gameserver = GameServer::new(receiver, ledger.clone());
         tokio::spawn(gameserver_message_recv_loop(gameserver));
-        Self { sender }
+        Self { sender, ledger }
     }
 
     pub async fn list_gamerooms(&self) -> Vec<GameRoomDTO> {
@@ -77,17 +116,29 @@ impl GameServerHandle {
         let _ = self.sender.send(GameServerMessage::PlayerJoin { websocket, room_id }).await;
     }
 
-    pub async fn gameroom_start(&self, game_type: GameType) {
-        let _ = self.sender.send(GameServerMessage::GameRoomStart { game_type }).await;
+    pub async fn add_bots(&self, room_id: uuid::Uuid, strategies: Vec<BotStrategy>) -> Option<Vec<Uuid>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel();
+        let _ = self.sender.send(GameServerMessage::AddBots { room_id, strategies, respond_to: oneshot_sender }).await;
+        oneshot_receiver.await.expect("Gameserver Channel Closed")
+    }
+
+    pub async fn gameroom_start(&self, settings: GameRoomSettings) {
+        let _ = self.sender.send(GameServerMessage::GameRoomStart { settings }).await;
+    }
+
+    pub async fn tournament_start(&self, config: MttConfig) {
+        let _ = self.sender.send(GameServerMessage::TournamentStart { config }).await;
     }
 }
 
 async fn gameserver_message_recv_loop(mut gameserver: GameServer) {
     while let Some(message) = gameserver.receiver.recv().await {
         match message {
-            GameServerMessage::GameRoomStart { game_type } => gameserver.handle_start_gameroom(game_type).await,
+            GameServerMessage::GameRoomStart { settings } => gameserver.handle_start_gameroom(settings).await,
+            GameServerMessage::TournamentStart { config } => gameserver.handle_start_tournament(config),
             GameServerMessage::PlayerJoin { websocket, room_id } => gameserver.handle_join_player(websocket, room_id).await,
-            GameServerMessage::ListGameRooms { respond_to } => gameserver.handle_list_gamerooms(respond_to)
+            GameServerMessage::ListGameRooms { respond_to } => gameserver.handle_list_gamerooms(respond_to),
+            GameServerMessage::AddBots { room_id, strategies, respond_to } => gameserver.handle_add_bots(room_id, strategies, respond_to).await
         }
     }
 }
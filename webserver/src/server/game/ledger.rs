@@ -0,0 +1,65 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Rake a player paid, for rakeback
+#[derive(Serialize, Clone)]
+pub struct PlayerRakeDTO {
+    pub player_id: Uuid,
+    pub rake: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LedgerDTO {
+    pub total_rake: u64,
+    pub raked_hands: u64,
+    /// Biggest payers first
+    pub players: Vec<PlayerRakeDTO>,
+}
+
+#[derive(Default)]
+struct Books {
+    total_rake: u64,
+    raked_hands: u64,
+    by_player: HashMap<Uuid, u64>,
+}
+
+/// Rake the house took from the cash games, with the share each player paid.
+/// Shared by the rooms
+#[derive(Clone, Default)]
+pub struct HouseLedger {
+    books: Arc<Mutex<Books>>,
+}
+
+impl HouseLedger {
+    /// Books the rake of a hand, split between the players who paid it
+    pub async fn record(&self, attribution: &[(Uuid, u32)]) {
+        let rake: u64 = attribution.iter().map(|&(_, rake)| rake as u64).sum();
+        if rake == 0 {
+            return;
+        }
+        let mut books = self.books.lock().await;
+        books.total_rake += rake;
+        books.raked_hands += 1;
+        for &(player_id, rake) in attribution.iter().filter(|(_, rake)| *rake > 0) {
+            *books.by_player.entry(player_id).or_default() += rake as u64;
+        }
+    }
+
+    pub async fn summary(&self) -> LedgerDTO {
+        let books = self.books.lock().await;
+        let mut players: Vec<PlayerRakeDTO> = books
+            .by_player
+            .iter()
+            .map(|(&player_id, &rake)| PlayerRakeDTO { player_id, rake })
+            .collect();
+        players.sort_by_key(|player| std::cmp::Reverse(player.rake));
+        LedgerDTO {
+            total_rake: books.total_rake,
+            raked_hands: books.raked_hands,
+            players,
+        }
+    }
+}
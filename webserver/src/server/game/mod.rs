@@ -1,3 +1,8 @@
+pub mod bot;
 pub mod gameroom;
 pub mod gameserver;
+pub mod ledger;
+pub mod mtt;
 pub mod player;
+pub mod tournament;
+pub mod wallet;
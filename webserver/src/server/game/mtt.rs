@@ -0,0 +1,577 @@
+use crate::server::game::gameroom::{
+    GameRoomHandle, GameRoomMessage, GameRoomSettings, PlayerAction,
+};
+use crate::server::game::ledger::HouseLedger;
+use crate::server::game::player::{PlayerMessage, PlayerSession, PlayerWarningType};
+use crate::server::game::tournament::{BountyDTO, BuyWindows, TournamentProgress};
+use crate::server::game::wallet::Wallets;
+use axum::extract::ws::WebSocket;
+use mini_poker_core::rotation::{game_rotation, GameRotation, RotationPeriod};
+use mini_poker_core::tournament::{
+    balance_table, plays_hand_for_hand, BlindLevel, Bounty, Purchase, TournamentConfig,
+};
+use mini_poker_core::variant::ForcedBets;
+use rand::seq::SliceRandom;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+/// What a multi-table tournament plays
+pub struct MttConfig {
+    /// Game mode and rotation of every table, as given to `game_rotation`
+    pub mode: String,
+    pub period: RotationPeriod,
+    pub forced_bets: ForcedBets,
+    /// `seats` is the size of the field, the tournament starts once it registered
+    pub tournament: TournamentConfig,
+    /// Most players seated at one table
+    pub table_size: usize,
+}
+
+impl MttConfig {
+    fn rotation(&self) -> GameRotation {
+        let mut rotation =
+            game_rotation(&self.mode, self.period).expect("Tournament mode should be valid");
+        rotation.forced_bets = self.forced_bets;
+        rotation
+    }
+}
+
+/// What a tournament table does before its next hand
+pub struct TableOrder {
+    pub level: BlindLevel,
+    pub bounty: Option<Bounty>,
+    pub buy_windows: BuyWindows,
+    /// Players to send to another table first
+    pub moves: Vec<PlayerMove>,
+    /// The table is broken or the tournament is over
+    pub close: bool,
+}
+
+pub struct PlayerMove {
+    pub player_id: Uuid,
+    pub table: mpsc::Sender<GameRoomMessage>,
+}
+
+pub enum MttMessage {
+    /// A table finished a hand and waits for its orders, reporting the
+    /// players it knocked out with their stacks when the hand started, the
+    /// bounties collected and the rebuys and add-ons bought
+    TableReady {
+        table_id: Uuid,
+        busted: Vec<(Uuid, u32)>,
+        bounties: Vec<BountyDTO>,
+        purchases: Vec<(Uuid, Purchase)>,
+        respond_to: oneshot::Sender<TableOrder>,
+    },
+}
+
+struct MttPlayer {
+    id: Uuid,
+    sender: mpsc::Sender<PlayerMessage>,
+    is_registered: bool,
+    /// None before the tournament starts and once the player is out
+    table_id: Option<Uuid>,
+}
+
+struct MttTable {
+    handle: GameRoomHandle,
+    players: Vec<Uuid>,
+    /// Set while the table waits for its orders
+    waiting: Option<oneshot::Sender<TableOrder>>,
+    is_open: bool,
+}
+
+struct Mtt {
+    config: MttConfig,
+    /// Capped by the game with the fewest seats
+    table_size: usize,
+    progress: TournamentProgress,
+    players: Vec<MttPlayer>,
+    tables: Vec<MttTable>,
+    /// On the bubble every table finishes its hand before the next is dealt,
+    /// so nobody climbs the ladder by stalling
+    hand_for_hand: bool,
+    /// Players knocked out since the last tables were dealt
+    busted: Vec<(Uuid, u32)>,
+    sender: mpsc::Sender<MttMessage>,
+    wallets: Wallets,
+    ledger: HouseLedger,
+}
+
+impl Mtt {
+    fn remaining(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|player| player.table_id.is_some())
+            .count()
+    }
+
+    fn table_idx(&self, table_id: Uuid) -> Option<usize> {
+        self.tables
+            .iter()
+            .position(|table| table.handle.id == table_id)
+    }
+
+    /// Open table with the fewest players other than `from`
+    fn smallest_table(&self, from: usize) -> Option<usize> {
+        (0..self.tables.len())
+            .filter(|&idx| idx != from && self.tables[idx].is_open)
+            .min_by_key(|&idx| self.tables[idx].players.len())
+    }
+
+    async fn broadcast(&self, message: PlayerMessage) {
+        for player in self.players.iter() {
+            _ = player.sender.send(message.clone()).await;
+        }
+    }
+
+    async fn warn(&self, player_id: Uuid, message: &str) {
+        if let Some(player) = self.players.iter().find(|player| player.id == player_id) {
+            _ = player
+                .sender
+                .send(PlayerMessage::Warning {
+                    warning_type: PlayerWarningType::InvalidAction,
+                    message: message.to_string(),
+                })
+                .await;
+        }
+    }
+
+    async fn handle_player_message(&mut self, message: GameRoomMessage) {
+        match message {
+            GameRoomMessage::PlayerJoin { id, sender } => {
+                match self.players.iter_mut().find(|player| player.id == id) {
+                    Some(player) => {
+                        player.sender = sender.clone();
+                        let table = player
+                            .table_id
+                            .and_then(|table_id| self.table_idx(table_id));
+                        if let Some(idx) = table {
+                            _ = self.tables[idx]
+                                .handle
+                                .sender()
+                                .send(GameRoomMessage::PlayerJoin { id, sender })
+                                .await;
+                        }
+                    }
+                    None => self.players.push(MttPlayer {
+                        id,
+                        sender,
+                        is_registered: false,
+                        table_id: None,
+                    }),
+                }
+            }
+            GameRoomMessage::PlayerAction {
+                payload: PlayerAction::Update { is_playing },
+                from,
+            } => {
+                self.handle_registration(from, is_playing).await;
+            }
+            GameRoomMessage::PlayerAction {
+                payload: PlayerAction::Reenter,
+                from,
+            } => {
+                self.enter(from, Purchase::Reentry).await;
+            }
+            GameRoomMessage::PlayerAction { payload, from } => {
+                let table = self
+                    .players
+                    .iter()
+                    .find(|player| player.id == from)
+                    .and_then(|player| player.table_id)
+                    .and_then(|table_id| self.table_idx(table_id));
+                if let Some(idx) = table {
+                    // Dropped rather than waited on when the table is busy
+                    _ = self.tables[idx]
+                        .handle
+                        .sender()
+                        .try_send(GameRoomMessage::PlayerAction { payload, from });
+                }
+            }
+            GameRoomMessage::SeatPlayer { .. } => {}
+        }
+    }
+
+    async fn handle_registration(&mut self, player_id: Uuid, is_playing: bool) {
+        if self.progress.has_started() && is_playing {
+            self.enter(player_id, Purchase::Entry).await;
+            return;
+        }
+        if self.progress.has_started() {
+            self.warn(player_id, "The tournament has already started")
+                .await;
+            return;
+        }
+        let Some(player) = self
+            .players
+            .iter_mut()
+            .find(|player| player.id == player_id)
+        else {
+            return;
+        };
+        if player.is_registered == is_playing {
+            return;
+        }
+        // Unregistering before the start refunds the entry
+        let fee = self.progress.config.price(Purchase::Entry);
+        let balance = match is_playing {
+            true => self.wallets.debit(player_id, fee).await,
+            false => Ok(self.wallets.credit(player_id, fee).await),
+        };
+        match balance {
+            Ok(balance) => {
+                player.is_registered = is_playing;
+                _ = player.sender.send(PlayerMessage::Wallet { balance }).await;
+            }
+            Err(message) => {
+                self.warn(player_id, message).await;
+                return;
+            }
+        }
+
+        let n_registered = self
+            .players
+            .iter()
+            .filter(|player| player.is_registered)
+            .count();
+        if n_registered >= self.progress.config.seats {
+            self.start().await;
+        }
+    }
+
+    /// Seats the field at random, dealing players around the tables so their
+    /// sizes are within one
+    async fn start(&mut self) {
+        let mut entrants: Vec<usize> = (0..self.players.len())
+            .filter(|&idx| self.players[idx].is_registered)
+            .collect();
+        entrants.shuffle(&mut rand::rng());
+        self.progress
+            .start(entrants.iter().map(|&idx| self.players[idx].id).collect());
+
+        let n_tables = entrants.len().div_ceil(self.table_size);
+        for _ in 0..n_tables {
+            self.open_table().await;
+        }
+
+        let funds = self.progress.config.starting_stack;
+        for (seat, &player_idx) in entrants.iter().enumerate() {
+            let table = &mut self.tables[seat % n_tables];
+            let player = &mut self.players[player_idx];
+            table.players.push(player.id);
+            player.table_id = Some(table.handle.id);
+            _ = table
+                .handle
+                .sender()
+                .send(GameRoomMessage::SeatPlayer {
+                    id: player.id,
+                    sender: player.sender.clone(),
+                    funds,
+                    bounty: self.progress.head(player.id),
+                })
+                .await;
+        }
+
+        let message = self.progress.state_message(self.remaining());
+        self.broadcast(message).await;
+    }
+
+    async fn open_table(&mut self) -> usize {
+        let handle = GameRoomHandle::new(
+            GameRoomSettings {
+                rotation: self.config.rotation(),
+                tournament: None,
+                coordinator: Some(self.sender.clone()),
+                rake: None,
+                bots: Vec::new(),
+            },
+            self.wallets.clone(),
+            self.ledger.clone(),
+        )
+        .await;
+        self.tables.push(MttTable {
+            handle,
+            players: Vec::new(),
+            waiting: None,
+            is_open: true,
+        });
+        self.tables.len() - 1
+    }
+
+    /// Seats a player registering late or entering again after busting at
+    /// the table with the fewest players, opening a new one when every
+    /// table is full
+    async fn enter(&mut self, player_id: Uuid, purchase: Purchase) {
+        let windows = self.progress.buy_windows();
+        let Some(player_idx) = self
+            .players
+            .iter()
+            .position(|player| player.id == player_id)
+        else {
+            return;
+        };
+        let player = &self.players[player_idx];
+        let checked = match purchase {
+            Purchase::Entry if !windows.late_registration => Err("Registration is closed"),
+            Purchase::Entry if player.is_registered => Err("Busted players re-enter instead"),
+            Purchase::Reentry if !windows.reentry => Err("Re-entry is closed"),
+            Purchase::Reentry if !player.is_registered || player.table_id.is_some() => {
+                Err("Only busted players can re-enter")
+            }
+            _ => Ok(self.progress.config.price(purchase)),
+        };
+        let balance = match checked {
+            Ok(price) => self.wallets.debit(player_id, price).await,
+            Err(message) => Err(message),
+        };
+        let balance = match balance {
+            Ok(balance) => balance,
+            Err(message) => {
+                self.warn(player_id, message).await;
+                return;
+            }
+        };
+        self.progress.buy(player_id, purchase);
+
+        let table_idx = match self
+            .smallest_table(usize::MAX)
+            .filter(|&idx| self.tables[idx].players.len() < self.table_size)
+        {
+            Some(idx) => idx,
+            None => self.open_table().await,
+        };
+        let table = &mut self.tables[table_idx];
+        let player = &mut self.players[player_idx];
+        player.is_registered = true;
+        player.table_id = Some(table.handle.id);
+        table.players.push(player_id);
+        _ = player.sender.send(PlayerMessage::Wallet { balance }).await;
+        _ = table
+            .handle
+            .sender()
+            .send(GameRoomMessage::SeatPlayer {
+                id: player_id,
+                sender: player.sender.clone(),
+                funds: self.progress.config.starting_stack,
+                bounty: self.progress.head(player_id),
+            })
+            .await;
+
+        self.broadcast(PlayerMessage::Purchase {
+            player_id,
+            purchase,
+            chips: self.progress.config.starting_stack,
+        })
+        .await;
+        let message = self.progress.state_message(self.remaining());
+        self.broadcast(message).await;
+    }
+
+    async fn handle_table_ready(
+        &mut self,
+        table_id: Uuid,
+        busted: Vec<(Uuid, u32)>,
+        bounties: Vec<BountyDTO>,
+        purchases: Vec<(Uuid, Purchase)>,
+        respond_to: oneshot::Sender<TableOrder>,
+    ) {
+        let Some(idx) = self.table_idx(table_id) else {
+            return;
+        };
+        self.progress.record_bounties(&bounties);
+        for (player_id, purchase) in purchases {
+            self.progress.buy(player_id, purchase);
+        }
+        for &(player_id, _) in busted.iter() {
+            self.tables[idx].players.retain(|&id| id != player_id);
+            if let Some(player) = self
+                .players
+                .iter_mut()
+                .find(|player| player.id == player_id)
+            {
+                player.table_id = None;
+            }
+        }
+        self.busted.extend(busted);
+        self.tables[idx].waiting = Some(respond_to);
+
+        let all_waiting = self
+            .tables
+            .iter()
+            .all(|table| !table.is_open || table.waiting.is_some());
+        if self.hand_for_hand && !all_waiting {
+            return;
+        }
+
+        self.knock_out().await;
+        let n_open = self.tables.iter().filter(|table| table.is_open).count();
+        self.hand_for_hand =
+            plays_hand_for_hand(n_open, self.remaining(), self.progress.paid_places());
+        for idx in 0..self.tables.len() {
+            self.deal_table(idx).await;
+        }
+    }
+
+    /// Ranks the players knocked out since the last deal. Players out in the
+    /// same hand-for-hand hand, at any table, finish in the order of their
+    /// stacks when it started
+    async fn knock_out(&mut self) {
+        if self.busted.is_empty() {
+            return;
+        }
+        let busted = std::mem::take(&mut self.busted);
+        let remaining: Vec<Uuid> = self
+            .players
+            .iter()
+            .filter_map(|player| player.table_id.map(|_| player.id))
+            .collect();
+        self.progress.eliminate(busted, remaining.len());
+        let payouts = match remaining[..] {
+            [winner] => self.progress.finish(winner),
+            _ => Vec::new(),
+        };
+        for (player_id, amount) in payouts {
+            self.wallets.credit(player_id, amount).await;
+        }
+
+        let message = self.progress.state_message(remaining.len());
+        self.broadcast(message).await;
+    }
+
+    /// Sends a waiting table its orders, breaking it when the players left fit
+    /// at one table less and it is the smallest, or else moving players out
+    /// until it is within one player of the others
+    async fn deal_table(&mut self, idx: usize) {
+        let Some(respond_to) = self.tables[idx].waiting.take() else {
+            return;
+        };
+        let mut order = TableOrder {
+            level: self.progress.level(),
+            bounty: self.progress.config.bounty,
+            buy_windows: self.progress.buy_windows(),
+            moves: Vec::new(),
+            close: self.progress.is_over(),
+        };
+
+        if !order.close {
+            let seated: Vec<Option<usize>> = self
+                .tables
+                .iter()
+                .map(|table| table.is_open.then_some(table.players.len()))
+                .collect();
+            let balance = balance_table(&seated, idx, self.table_size);
+            order.close = balance.breaks;
+            for to in balance.moves {
+                let player_id = self.tables[idx].players[0];
+                order.moves.push(self.move_player(player_id, idx, to));
+            }
+        }
+        if order.close {
+            self.tables[idx].is_open = false;
+        }
+
+        let message = self.progress.state_message(self.remaining());
+        for player_id in self.tables[idx].players.iter() {
+            if let Some(player) = self.players.iter().find(|player| player.id == *player_id) {
+                _ = player.sender.send(message.clone()).await;
+            }
+        }
+        _ = respond_to.send(order);
+    }
+
+    fn move_player(&mut self, player_id: Uuid, from: usize, to: usize) -> PlayerMove {
+        self.tables[from].players.retain(|&id| id != player_id);
+        self.tables[to].players.push(player_id);
+        let table_id = self.tables[to].handle.id;
+        if let Some(player) = self
+            .players
+            .iter_mut()
+            .find(|player| player.id == player_id)
+        {
+            player.table_id = Some(table_id);
+        }
+        PlayerMove {
+            player_id,
+            table: self.tables[to].handle.sender(),
+        }
+    }
+}
+
+async fn mtt_message_loop(
+    mut mtt: Mtt,
+    mut receiver: mpsc::Receiver<MttMessage>,
+    mut player_receiver: mpsc::Receiver<GameRoomMessage>,
+) {
+    loop {
+        tokio::select! {
+            Some(message) = player_receiver.recv() => mtt.handle_player_message(message).await,
+            Some(message) = receiver.recv() => match message {
+                MttMessage::TableReady { table_id, busted, bounties, purchases, respond_to } => {
+                    mtt.handle_table_ready(table_id, busted, bounties, purchases, respond_to).await
+                }
+            },
+            else => break,
+        }
+    }
+}
+
+/// Multi-table tournament, players join it like a room and are seated once
+/// the field has registered
+pub struct MttHandle {
+    pub id: Uuid,
+    sender: mpsc::Sender<GameRoomMessage>,
+}
+
+impl MttHandle {
+    pub fn new(config: MttConfig, wallets: Wallets, ledger: HouseLedger) -> Self {
+        let (sender, receiver) = mpsc::channel(100);
+        let (mtt_sender, mtt_receiver) = mpsc::channel(100);
+        let max_players = config
+            .rotation()
+            .games
+            .iter()
+            .map(|game| game.max_players())
+            .min()
+            .unwrap_or(2);
+        let mtt = Mtt {
+            table_size: config.table_size.clamp(2, max_players.max(2)),
+            progress: TournamentProgress::new(config.tournament.clone()),
+            config,
+            players: Vec::new(),
+            tables: Vec::new(),
+            hand_for_hand: false,
+            busted: Vec::new(),
+            sender: mtt_sender,
+            wallets,
+            ledger,
+        };
+        tokio::spawn(mtt_message_loop(mtt, mtt_receiver, receiver));
+
+        Self {
+            id: Uuid::new_v4(),
+            sender,
+        }
+    }
+
+    pub async fn handle_player_connection(&self, websocket: WebSocket, player_id: Uuid) {
+        let (player_sender, player_receiver) = mpsc::channel(10);
+        PlayerSession::new(
+            player_id,
+            self.sender.clone(),
+            player_sender.clone(),
+            player_receiver,
+            websocket,
+        );
+        _ = self
+            .sender
+            .send(GameRoomMessage::PlayerJoin {
+                id: player_id,
+                sender: player_sender.clone(),
+            })
+            .await;
+        _ = player_sender
+            .send(PlayerMessage::Session { player_id })
+            .await;
+    }
+}
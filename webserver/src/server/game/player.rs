@@ -10,7 +10,13 @@ use tokio;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use crate::server::game::gameroom::{GameRoomMessage, PlayerAction, PlayerGameAction, PokerStep};
+use crate::server::game::gameroom::{GameRoomMessage, PlayerAction, PlayerGameAction};
+use crate::server::game::tournament::{BountyDTO, BuyWindows, DealShareDTO, StandingDTO};
+use mini_poker_core::card::{Card, Owner, Suit, CARD_RANKS};
+use mini_poker_core::game::PokerStep;
+use mini_poker_core::icm::DealMethod;
+use mini_poker_core::tournament::{BlindLevel, Purchase};
+use mini_poker_core::variant::{AnteType, BettingLimit};
 
 pub struct PlayerSession {
     pub id: uuid::Uuid,
@@ -25,16 +31,37 @@ pub enum PlayerWarningType {
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct GamePlayerStateDTO {
-    id: Uuid,
-    is_betting: bool,
-    bet_amount: u32,
-    funds: u32,
+    pub id: Uuid,
+    pub is_betting: bool,
+    pub bet_amount: u32,
+    pub funds: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct CardDealDTO {
     pub suit: char,
     pub rank: u8,
+    /// Whether the card is shown to the whole table
+    pub face_up: bool,
+}
+
+impl CardDealDTO {
+    pub fn new(card: &Card, face_up: bool) -> Self {
+        Self {
+            suit: card.suit.into(),
+            rank: card.rank as u8,
+            face_up,
+        }
+    }
+
+    /// Card dealt, none when the rank or suit is not a real one
+    pub fn card(&self, owner: Owner) -> Option<Card> {
+        Some(Card {
+            rank: *CARD_RANKS.get(self.rank as usize)?,
+            suit: Suit::try_from(self.suit).ok()?,
+            owner,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -54,7 +81,51 @@ pub enum CardOwnerDTO {
 pub struct HandRevealDTO {
     pub player_id: Uuid,
     pub cards: Vec<CardReveallDTO>,
-    // TODO: Hand Eval
+    /// High hand at showdown, none for folded players
+    pub hand: Option<String>,
+    /// Qualifying low in split pot games, e.g. "8-6-4-2-A"
+    pub low_hand: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PotResultDTO {
+    pub amount: u32,
+    pub high_winners: Vec<Uuid>,
+    /// Empty unless the pot was split with a qualifying low
+    pub low_winners: Vec<Uuid>,
+    /// Board this share of the pot was played on, 0 unless run more than once
+    pub runout: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerEquityDTO {
+    pub player_id: Uuid,
+    pub cards: Vec<CardDealDTO>,
+    pub win: f64,
+    pub tie: f64,
+    /// Share of the pot expected from the remaining board
+    pub equity: f64,
+}
+
+/// Outcome of a finished hand
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HandRecordDTO {
+    pub game: String,
+    /// More than one board when the hand was run more than once
+    pub boards: Vec<String>,
+    pub players: Vec<HandRecordPlayerDTO>,
+    pub bounties: Vec<BountyDTO>,
+    pub rake: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HandRecordPlayerDTO {
+    pub player_id: Uuid,
+    /// Chips won minus chips put in
+    pub net: i64,
+    /// Net had every pot been split by equity at the all in, the same as
+    /// `net` when nobody was all in
+    pub ev_net: f64,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -85,20 +156,124 @@ pub enum PlayerMessage {
         big_blind_player: Uuid,
         small_blind_amount: u32,
         big_blind_amount: u32,
+        ante_amount: u32,
+        ante_type: AnteType,
+        /// Player who paid the whole table's ante, none when everyone antes
+        ante_player: Option<Uuid>,
+        straddle_player: Option<Uuid>,
+        straddle_amount: u32,
+    },
+    Ante {
+        amount: u32,
+    },
+    TournamentState {
+        /// Starting at 1
+        level: usize,
+        blinds: BlindLevel,
+        /// Seconds until the blinds go up, none on the last level or once
+        /// the tournament is over
+        next_level_in: Option<u64>,
+        players_remaining: usize,
+        entrants: usize,
+        starting_stack: u32,
+        prize_pool: u32,
+        rebuys: usize,
+        add_ons: usize,
+        /// Prize of each paid position, first place first
+        payouts: Vec<u32>,
+        /// Players out of the tournament, the winner last
+        standings: Vec<StandingDTO>,
+        buy_windows: BuyWindows,
+        /// Seconds until play resumes, none unless on the add-on break
+        break_ends_in: Option<u64>,
+    },
+    /// Entry or chips bought by a player, the chips are added before the
+    /// next hand
+    Purchase {
+        player_id: Uuid,
+        purchase: Purchase,
+        chips: u32,
+    },
+    /// Player's balance after paying for a purchase
+    Wallet {
+        balance: u32,
+    },
+    /// Deal proposed to the players left in a sit-and-go, play stops until
+    /// they all accept or `timeout` seconds pass
+    DealOffer {
+        method: DealMethod,
+        proposed_by: Uuid,
+        shares: Vec<DealShareDTO>,
+        timeout: u64,
+    },
+    /// A player's answer to the deal on offer
+    DealVote {
+        player_id: Uuid,
+        accept: bool,
+    },
+    /// Table of a multi-table tournament the player was seated at
+    TableAssigned {
+        table_id: Uuid,
+    },
+    /// Game a mixed table switches to after the current hand, or the game
+    /// of the next hand when joining a table
+    UpcomingGame {
+        game: String,
+        limit: BettingLimit,
+    },
+    BringIn {
+        player_id: Uuid,
+        amount: u32,
     },
     CardDeal {
         cards: Vec<CardDealDTO>,
         owner: CardOwnerDTO,
+        /// Player the cards belong to, none for community cards
+        player_id: Option<Uuid>,
     },
     PlayerAction {
         player_id: Uuid,
         action: PlayerGameAction,
         bet_base: u32,
     },
+    /// Asks the players all in how many times to run the rest of the
+    /// board, each answering with a run it action within `timeout` seconds.
+    /// The board is run as many times as all of them agree to, players who
+    /// do not answer run it once
+    RunItOffer {
+        players: Vec<Uuid>,
+        max_runouts: usize,
+        timeout: u64,
+    },
+    /// Hands and equities once no more betting is possible
+    AllInEquity {
+        players: Vec<PlayerEquityDTO>,
+    },
+    /// One of the boards of a hand the players agreed to run more than
+    /// once, without the cards dealt before the all in
+    Runout {
+        runout: usize,
+        cards: Vec<CardDealDTO>,
+    },
     Result {
         winners: Vec<Uuid>,
         prizes: Vec<u32>,
+        /// Main pot first, then side pots
+        pots: Vec<PotResultDTO>,
         player_hands: Vec<HandRevealDTO>,
+        /// Bounties collected for the players knocked out in the hand
+        bounties: Vec<BountyDTO>,
+        /// Taken by the house from the pots before they were paid
+        rake: u32,
+    },
+    /// The hand could not be settled, every chip put in went back to the
+    /// player who bet it
+    HandCancelled {
+        reason: String,
+    },
+    /// Latest hands of the table, oldest first
+    HandHistory {
+        hands: Vec<HandRecordDTO>,
     },
     Warning {
         warning_type: PlayerWarningType,
@@ -226,7 +401,8 @@ async fn player_socket_recv_loop(
                     Some(Err(err)) => {
                         eprintln!("Player inbound socket error: {}", err);
                     }
-                    None => {}
+                    // The socket is closed, e.g. after a terminated session
+                    None => break,
                 }
             }
 
@@ -0,0 +1,379 @@
+use mini_poker_core::icm::{deal_prizes, DealMethod};
+use mini_poker_core::tournament::{
+    payout_percentages, payouts, BlindLevel, Purchase, TournamentConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::server::game::player::PlayerMessage;
+
+/// Finishing position of a player out of the tournament
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StandingDTO {
+    pub player_id: Uuid,
+    /// 1 for the winner
+    pub position: usize,
+    pub prize: u32,
+    /// Bounties collected, including their own head when still in at the end
+    pub bounties: u32,
+}
+
+/// Bounty collected for a knockout, one per eliminator when it is shared
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BountyDTO {
+    pub eliminated: Uuid,
+    pub eliminator: Uuid,
+    pub cash: u32,
+    /// Goes onto the eliminator's own head in progressive bounties
+    pub added_to_head: u32,
+}
+
+/// What tournament players can buy at the current time
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct BuyWindows {
+    /// New players can still register
+    pub late_registration: bool,
+    /// Busted players can enter again
+    pub reentry: bool,
+    /// Chips of a rebuy, none outside the rebuy period
+    pub rebuy: Option<u32>,
+    /// Chips of the add-on, none outside the break
+    pub add_on: Option<u32>,
+    /// Price of a rebuy or the add-on
+    pub price: u32,
+    /// Biggest stack a player can rebuy with
+    pub max_rebuy_stack: u32,
+}
+
+/// Prize a player left takes in a deal
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DealShareDTO {
+    pub player_id: Uuid,
+    pub stack: u32,
+    pub prize: u32,
+}
+
+/// Split of the remaining prizes proposed to the players left
+pub struct Deal {
+    pub method: DealMethod,
+    pub proposed_by: Uuid,
+    /// Set once the deal is offered between hands, on the stacks then
+    pub shares: Vec<DealShareDTO>,
+    pub accepted: Vec<Uuid>,
+    offered_at: Option<Instant>,
+}
+
+impl Deal {
+    pub fn new(method: DealMethod, proposed_by: Uuid) -> Self {
+        Self {
+            method,
+            proposed_by,
+            shares: Vec::new(),
+            accepted: vec![proposed_by],
+            offered_at: None,
+        }
+    }
+
+    pub fn is_offered(&self) -> bool {
+        self.offered_at.is_some()
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.is_offered()
+            && self
+                .shares
+                .iter()
+                .all(|share| self.accepted.contains(&share.player_id))
+    }
+
+    pub fn seconds_offered(&self) -> u64 {
+        self.offered_at
+            .map_or(0, |offered_at| offered_at.elapsed().as_secs())
+    }
+}
+
+/// Clock, prize pool and standings of a tournament
+pub struct TournamentProgress {
+    pub config: TournamentConfig,
+    started_at: Option<Instant>,
+    /// Entries so far, re-entries included
+    entrants: usize,
+    entries: HashMap<Uuid, usize>,
+    rebuys: usize,
+    add_ons: usize,
+    /// Prize of each paid position, set when the tournament starts
+    payouts: Vec<u32>,
+    /// Finished players, the winner last
+    standings: Vec<StandingDTO>,
+    /// Deal being negotiated, none when play goes on
+    pub deal: Option<Deal>,
+    /// Bounty on each player's head, the starting bounty until it changes
+    heads: HashMap<Uuid, u32>,
+    bounties_won: HashMap<Uuid, u32>,
+}
+
+impl TournamentProgress {
+    pub fn new(config: TournamentConfig) -> Self {
+        Self {
+            config,
+            started_at: None,
+            entrants: 0,
+            entries: HashMap::new(),
+            rebuys: 0,
+            add_ons: 0,
+            payouts: Vec::new(),
+            standings: Vec::new(),
+            deal: None,
+            heads: HashMap::new(),
+            bounties_won: HashMap::new(),
+        }
+    }
+
+    pub fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.has_started() && !self.is_over()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.standings.iter().any(|standing| standing.position == 1)
+    }
+
+    pub fn start(&mut self, entrants: Vec<Uuid>) {
+        self.started_at = Some(Instant::now());
+        for player_id in entrants {
+            self.buy(player_id, Purchase::Entry);
+        }
+    }
+
+    /// Adds a purchase to the prize pool, a re-entry putting the starting
+    /// bounty back on the player's head. Players already out finish one
+    /// place lower for every entry made after they busted
+    pub fn buy(&mut self, player_id: Uuid, purchase: Purchase) {
+        match purchase {
+            Purchase::Entry | Purchase::Reentry => {
+                self.entrants += 1;
+                *self.entries.entry(player_id).or_default() += 1;
+                self.heads.remove(&player_id);
+                for standing in self.standings.iter_mut() {
+                    standing.position += 1;
+                }
+            }
+            Purchase::Rebuy => self.rebuys += 1,
+            Purchase::AddOn => self.add_ons += 1,
+        }
+        self.payouts = payouts(self.prize_pool(), payout_percentages(self.entrants));
+        for idx in 0..self.standings.len() {
+            self.standings[idx].prize = self.prize(self.standings[idx].position);
+        }
+    }
+
+    /// Times the player entered the tournament
+    pub fn entries(&self, player_id: Uuid) -> usize {
+        self.entries.get(&player_id).copied().unwrap_or(0)
+    }
+
+    pub fn prize_pool(&self) -> u32 {
+        self.config.buy_in * (self.entrants + self.rebuys + self.add_ons) as u32
+    }
+
+    pub fn buy_windows(&self) -> BuyWindows {
+        if !self.is_running() {
+            return BuyWindows::default();
+        }
+        let config = &self.config;
+        let (level, _) = config.level_at(self.elapsed());
+        let late_registration = level < config.late_registration;
+        BuyWindows {
+            late_registration,
+            reentry: late_registration && config.reentry,
+            rebuy: (level < config.rebuy_levels).then_some(config.starting_stack),
+            add_on: config
+                .add_on
+                .filter(|_| config.break_left(self.elapsed()).is_some()),
+            price: config.price(Purchase::Rebuy),
+            max_rebuy_stack: config.starting_stack,
+        }
+    }
+
+    fn elapsed(&self) -> u64 {
+        self.started_at
+            .map_or(0, |started_at| started_at.elapsed().as_secs())
+    }
+
+    pub fn level(&self) -> BlindLevel {
+        let (level, _) = self.config.level_at(self.elapsed());
+        self.config.levels[level]
+    }
+
+    /// Bounty on a player's head
+    pub fn head(&self, player_id: Uuid) -> u32 {
+        let starting_bounty = self.config.bounty.map_or(0, |bounty| bounty.amount);
+        self.heads
+            .get(&player_id)
+            .copied()
+            .unwrap_or(starting_bounty)
+    }
+
+    pub fn record_bounties(&mut self, bounties: &[BountyDTO]) {
+        for bounty in bounties.iter() {
+            self.heads.insert(bounty.eliminated, 0);
+            let head = self.head(bounty.eliminator);
+            self.heads
+                .insert(bounty.eliminator, head + bounty.added_to_head);
+            *self.bounties_won.entry(bounty.eliminator).or_default() += bounty.cash;
+        }
+    }
+
+    fn bounties_won(&self, player_id: Uuid) -> u32 {
+        self.bounties_won.get(&player_id).copied().unwrap_or(0)
+    }
+
+    pub fn paid_places(&self) -> usize {
+        self.payouts.len()
+    }
+
+    fn prize(&self, position: usize) -> u32 {
+        self.payouts.get(position - 1).copied().unwrap_or(0)
+    }
+
+    /// Ranks the players knocked out in one hand given their stacks when it
+    /// started, the bigger stack finishing ahead. `remaining` counts the
+    /// players still in once they are out. Their prizes are only paid when
+    /// the tournament ends, later entries can still move them down
+    pub fn eliminate(&mut self, mut busted: Vec<(Uuid, u32)>, remaining: usize) {
+        busted.sort_by_key(|&(_, starting_stack)| starting_stack);
+        let n_busted = busted.len();
+        for (offset, (player_id, _)) in busted.into_iter().enumerate() {
+            let position = remaining + n_busted - offset;
+            self.standings.push(StandingDTO {
+                player_id,
+                position,
+                prize: self.prize(position),
+                bounties: self.bounties_won(player_id),
+            });
+        }
+    }
+
+    /// The last player standing holds every chip and their own bounty.
+    /// Returns the money every player takes home from the final standings
+    pub fn finish(&mut self, winner: Uuid) -> Vec<(Uuid, u32)> {
+        let head = self.head(winner);
+        self.standings.push(StandingDTO {
+            player_id: winner,
+            position: 1,
+            prize: self.prize(1),
+            bounties: self.bounties_won(winner) + head,
+        });
+        let mut payouts = self.prizes();
+        payouts.push((winner, head));
+        payouts
+    }
+
+    /// Prize of every finished player, final once the tournament is over
+    fn prizes(&self) -> Vec<(Uuid, u32)> {
+        self.standings
+            .iter()
+            .map(|standing| (standing.player_id, standing.prize))
+            .collect()
+    }
+
+    /// Prices the pending deal on the stacks of the players left
+    pub fn offer_deal(&mut self, stacks: Vec<(Uuid, u32)>) -> Result<&Deal, &'static str> {
+        let Some(deal) = self.deal.as_mut() else {
+            return Err("No deal was proposed");
+        };
+        let chips: Vec<u32> = stacks.iter().map(|&(_, stack)| stack).collect();
+        let prizes = deal_prizes(deal.method, &chips, &self.payouts)?;
+        deal.shares = stacks
+            .into_iter()
+            .zip(prizes)
+            .map(|((player_id, stack), prize)| DealShareDTO {
+                player_id,
+                stack,
+                prize,
+            })
+            .collect();
+        deal.offered_at = Some(Instant::now());
+        Ok(deal)
+    }
+
+    /// Ends the tournament on the accepted deal, the players left finishing
+    /// in chip count order and keeping their own bounties. Returns the money
+    /// every player takes home from the final standings
+    pub fn settle_deal(&mut self) -> Vec<(Uuid, u32)> {
+        let Some(deal) = self.deal.take() else {
+            return Vec::new();
+        };
+        let mut shares = deal.shares;
+        shares.sort_by_key(|share| share.stack);
+        let n_shares = shares.len();
+        let mut heads = Vec::with_capacity(n_shares);
+        for (offset, share) in shares.into_iter().enumerate() {
+            let head = self.head(share.player_id);
+            self.standings.push(StandingDTO {
+                player_id: share.player_id,
+                position: n_shares - offset,
+                prize: share.prize,
+                bounties: self.bounties_won(share.player_id) + head,
+            });
+            heads.push((share.player_id, head));
+        }
+        let mut payouts = self.prizes();
+        payouts.extend(heads);
+        payouts
+    }
+
+    pub fn state_message(&self, players_remaining: usize) -> PlayerMessage {
+        let (level, next_level_in) = self.config.level_at(self.elapsed());
+        PlayerMessage::TournamentState {
+            level: level + 1,
+            blinds: self.config.levels[level],
+            next_level_in: next_level_in.filter(|_| self.is_running()),
+            players_remaining,
+            entrants: self.entrants,
+            starting_stack: self.config.starting_stack,
+            prize_pool: self.prize_pool(),
+            rebuys: self.rebuys,
+            add_ons: self.add_ons,
+            payouts: self.payouts.clone(),
+            standings: self.standings.clone(),
+            buy_windows: self.buy_windows(),
+            break_ends_in: self
+                .config
+                .break_left(self.elapsed())
+                .filter(|_| self.is_running()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prizes_are_paid_from_the_final_standings() {
+        let players: Vec<Uuid> = (0..7).map(|_| Uuid::new_v4()).collect();
+        let mut progress = TournamentProgress::new(TournamentConfig::sit_and_go(9));
+        progress.start(players.clone());
+        for (remaining, &player_id) in (2..=6).rev().zip(players[2..].iter().rev()) {
+            progress.eliminate(vec![(player_id, 0)], remaining);
+        }
+        assert_eq!(progress.standings.last().unwrap().prize, 140);
+
+        progress.buy(players[6], Purchase::Reentry);
+        assert_eq!(progress.standings.last().unwrap().position, 4);
+        assert_eq!(progress.standings.last().unwrap().prize, 0);
+        progress.eliminate(vec![(players[6], 0)], 2);
+        progress.eliminate(vec![(players[1], 0)], 1);
+        let payouts = progress.finish(players[0]);
+
+        let paid: u32 = payouts.iter().map(|&(_, amount)| amount).sum();
+        assert_eq!(paid, progress.prize_pool());
+        assert_eq!(paid, 800);
+    }
+}
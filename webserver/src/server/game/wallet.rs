@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Balance of a player the server has not charged yet
+const STARTING_BALANCE: u32 = 10_000;
+
+/// Money players hold away from the tables, shared by the rooms and
+/// tournaments that charge them
+#[derive(Clone, Default)]
+pub struct Wallets {
+    balances: Arc<Mutex<HashMap<Uuid, u32>>>,
+}
+
+impl Wallets {
+    /// Takes `amount` from the player's balance, returning what is left
+    pub async fn debit(&self, player_id: Uuid, amount: u32) -> Result<u32, &'static str> {
+        let mut balances = self.balances.lock().await;
+        let balance = balances.entry(player_id).or_insert(STARTING_BALANCE);
+        if *balance < amount {
+            return Err("Not enough money in the wallet");
+        }
+        *balance -= amount;
+        Ok(*balance)
+    }
+
+    /// Gives `amount` back to the player, returning the new balance
+    pub async fn credit(&self, player_id: Uuid, amount: u32) -> u32 {
+        let mut balances = self.balances.lock().await;
+        let balance = balances.entry(player_id).or_insert(STARTING_BALANCE);
+        *balance += amount;
+        *balance
+    }
+}
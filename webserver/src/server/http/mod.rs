@@ -1,16 +1,35 @@
 use axum::{
-    extract::ws::{WebSocket, WebSocketUpgrade},
-    response::Response,
-    routing, Router,
+    extract::{
+        ws::{WebSocket, WebSocketUpgrade},
+        Path,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing, Json, Router,
 };
+use serde::Deserialize;
+use uuid::Uuid;
 
-use crate::{core::game::GameType, server::game::gameserver::GameServerHandle};
+use crate::server::game::gameroom::GameRoomSettings;
+use crate::server::game::gameserver::GameServerHandle;
+use crate::server::game::mtt::MttConfig;
+use mini_poker_core::strategy::BotStrategy;
 
-pub async fn start(rooms: Vec<GameType>) {
+/// Bots to seat at a room
+#[derive(Deserialize)]
+struct AddBotsRequest {
+    count: usize,
+    strategy: BotStrategy,
+}
+
+pub async fn start(rooms: Vec<GameRoomSettings>, tournaments: Vec<MttConfig>) {
     let gameserver_handle = GameServerHandle::new();
     for room in rooms {
         gameserver_handle.gameroom_start(room).await;
     }
+    for tournament in tournaments {
+        gameserver_handle.tournament_start(tournament).await;
+    }
 
     let rooms = gameserver_handle.list_gamerooms().await;
     for room in rooms {
@@ -31,10 +50,39 @@ pub async fn start(rooms: Vec<GameType>) {
         }
     }
 
-    let app = Router::new().route(
-        "/ws",
-        routing::any(move |ws| player_conn_handler(ws, gameserver_handle.clone())),
-    );
+    async fn add_bots_handler(
+        room_id: Uuid,
+        request: AddBotsRequest,
+        gameserver_handle: GameServerHandle,
+    ) -> Response {
+        let strategies = vec![request.strategy; request.count];
+        match gameserver_handle.add_bots(room_id, strategies).await {
+            Some(bot_ids) => Json(bot_ids).into_response(),
+            None => (StatusCode::NOT_FOUND, "No such room").into_response(),
+        }
+    }
+
+    let ledger = gameserver_handle.ledger.clone();
+    let bots_handle = gameserver_handle.clone();
+    let app = Router::new()
+        .route(
+            "/ws",
+            routing::any(move |ws| player_conn_handler(ws, gameserver_handle.clone())),
+        )
+        // Rake taken so far, with what each player paid for rakeback
+        .route(
+            "/ledger",
+            routing::get(move || async move { Json(ledger.summary().await) }),
+        )
+        // Seats bots at a cash game or sit-and-go, answering with their ids
+        .route(
+            "/rooms/{room_id}/bots",
+            routing::post(
+                move |Path(room_id): Path<Uuid>, Json(request): Json<AddBotsRequest>| {
+                    add_bots_handler(room_id, request, bots_handle.clone())
+                },
+            ),
+        );
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
     axum::serve(listener, app).await.unwrap();
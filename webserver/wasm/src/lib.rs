@@ -0,0 +1,203 @@
+use mini_poker_core::card::{parse_cards as parse_core_cards, Card, Owner};
+use mini_poker_core::equity::{calculate_equity, EquityConfig, EquityMethod};
+use mini_poker_core::game::GameType;
+use mini_poker_core::hand::{best_hand, compare_hands as compare_core_hands, hand_score};
+use wasm_bindgen::prelude::*;
+
+// Placeholder for a player whose hole cards are unknown
+const UNKNOWN_HAND: &str = "??";
+
+fn cards(text: &str, owner: Owner) -> Result<Vec<Card>, JsError> {
+    parse_core_cards(text, owner).map_err(|err| JsError::new(&format!("{err}: {text}")))
+}
+
+fn game(name: &str) -> Result<GameType, JsError> {
+    name.parse().map_err(|err: &str| JsError::new(err))
+}
+
+#[wasm_bindgen(js_name = Card)]
+pub struct JsCard {
+    /// 0 for a two up to 12 for an ace
+    pub rank: u8,
+    suit: char,
+    notation: String,
+}
+
+#[wasm_bindgen(js_class = Card)]
+impl JsCard {
+    /// One of "c", "d", "h" or "s"
+    #[wasm_bindgen(getter)]
+    pub fn suit(&self) -> String {
+        self.suit.to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn notation(&self) -> String {
+        self.notation.clone()
+    }
+}
+
+#[wasm_bindgen]
+pub struct HandResult {
+    /// 0 for high card up to 9 for a royal flush
+    pub category: u8,
+    /// Comparable strength, higher is better
+    pub score: u32,
+    hand_type: String,
+    sorted_ranks: Vec<u8>,
+    cards: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl HandResult {
+    /// Display name such as "Two Pair"
+    #[wasm_bindgen(getter, js_name = handType)]
+    pub fn hand_type(&self) -> String {
+        self.hand_type.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = sortedRanks)]
+    pub fn sorted_ranks(&self) -> Vec<u8> {
+        self.sorted_ranks.clone()
+    }
+
+    /// The cards making up the hand, to highlight on the table
+    #[wasm_bindgen(getter)]
+    pub fn cards(&self) -> Vec<String> {
+        self.cards.clone()
+    }
+}
+
+#[wasm_bindgen]
+pub struct EquityResult {
+    /// Number of runouts evaluated
+    pub samples: u64,
+    exact: bool,
+    win: Vec<f64>,
+    tie: Vec<f64>,
+    equity: Vec<f64>,
+    margin: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl EquityResult {
+    /// Whether every runout was enumerated instead of sampled
+    #[wasm_bindgen(getter)]
+    pub fn exact(&self) -> bool {
+        self.exact
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn win(&self) -> Vec<f64> {
+        self.win.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tie(&self) -> Vec<f64> {
+        self.tie.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn equity(&self) -> Vec<f64> {
+        self.equity.clone()
+    }
+
+    /// Half width of the 95% confidence interval per player, zero when exact
+    #[wasm_bindgen(getter)]
+    pub fn margin(&self) -> Vec<f64> {
+        self.margin.clone()
+    }
+}
+
+/// Parses cards written as "AsKs", "As Ks" or "As,Ks"
+#[wasm_bindgen(js_name = parseCards)]
+pub fn parse_cards(text: &str) -> Result<Vec<JsCard>, JsError> {
+    Ok(cards(text, Owner::Player)?
+        .iter()
+        .map(|card| JsCard {
+            rank: card.rank as u8,
+            suit: card.suit.into(),
+            notation: card.notation(),
+        })
+        .collect())
+}
+
+/// Best hand made so far by the hole cards and the board, which may be
+/// empty or incomplete
+#[wasm_bindgen]
+pub fn evaluate(hole: &str, board: &str, game: &str) -> Result<HandResult, JsError> {
+    let game_type = self::game(game)?;
+    let hand: Vec<Card> = cards(hole, Owner::Player)?
+        .into_iter()
+        .chain(cards(board, Owner::Community)?)
+        .collect();
+    let best = best_hand(&hand, game_type).map_err(JsError::new)?;
+
+    Ok(HandResult {
+        category: best.hand_type as u8,
+        score: hand_score(best.hand_type, &best.sorted_ranks),
+        hand_type: best.hand_type.to_string(),
+        sorted_ranks: best.sorted_ranks.to_vec(),
+        cards: best.cards.iter().map(Card::notation).collect(),
+    })
+}
+
+/// Indexes of the winning hands, more than one on a split pot
+#[wasm_bindgen(js_name = compareHands)]
+pub fn compare_hands(hands: Vec<String>, board: &str, game: &str) -> Result<Vec<u32>, JsError> {
+    let game_type = self::game(game)?;
+    let board = cards(board, Owner::Community)?;
+    let hands = hands
+        .iter()
+        .map(|hole| {
+            Ok(cards(hole, Owner::Player)?
+                .into_iter()
+                .chain(board.iter().copied())
+                .collect())
+        })
+        .collect::<Result<Vec<Vec<Card>>, JsError>>()?;
+
+    Ok(compare_core_hands(hands, game_type)
+        .map_err(JsError::new)?
+        .into_iter()
+        .map(|idx| idx as u32)
+        .collect())
+}
+
+/// Win/tie/equity per player, computed on the calling thread. Pass "??" for
+/// a player whose cards are unknown
+#[wasm_bindgen]
+pub fn equity(
+    hands: Vec<String>,
+    board: &str,
+    dead: &str,
+    game: &str,
+    iterations: u32,
+    seed: u32,
+) -> Result<EquityResult, JsError> {
+    let mut config = EquityConfig::new(
+        self::game(game)?,
+        hands
+            .iter()
+            .map(|hand| match hand.as_str() {
+                UNKNOWN_HAND => Ok(Vec::new()),
+                _ => cards(hand, Owner::Player),
+            })
+            .collect::<Result<_, JsError>>()?,
+        cards(board, Owner::Community)?,
+    );
+    config.dead_cards = cards(dead, Owner::Community)?;
+    config.iterations = iterations as u64;
+    config.seed = seed as u64;
+    config.threads = 1;
+
+    let result = calculate_equity(&config).map_err(JsError::new)?;
+    Ok(EquityResult {
+        samples: result.samples,
+        exact: result.method == EquityMethod::Exact,
+        win: result.players.iter().map(|player| player.win).collect(),
+        tie: result.players.iter().map(|player| player.tie).collect(),
+        equity: result.players.iter().map(|player| player.equity).collect(),
+        margin: result.players.iter().map(|player| player.margin).collect(),
+    })
+}